@@ -0,0 +1,132 @@
+//! 缓存管理模块
+//!
+//! 各功能模块的内存缓存统一在此登记，便于查看状态和强制刷新
+
+use log::info;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::jd;
+
+/// 缓存统一管理接口
+pub trait CacheControl: Sync {
+    /// 缓存名称（invalidate_cache 的 kind 参数）
+    fn name(&self) -> &'static str;
+    /// 当前条目数
+    fn entry_count(&self) -> usize;
+    /// 最早一条缓存的存活时长
+    fn oldest_age(&self) -> Option<Duration>;
+    /// 清空缓存
+    fn clear(&self);
+}
+
+/// 带过期时间的内存缓存
+pub struct TtlCache<V> {
+    name: &'static str,
+    ttl: Duration,
+    entries: Mutex<BTreeMap<String, (Instant, V)>>,
+}
+
+impl<V: Clone> TtlCache<V> {
+    pub const fn new(name: &'static str, ttl: Duration) -> Self {
+        Self {
+            name,
+            ttl,
+            entries: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// 读取未过期的缓存，过期条目会被顺带移除
+    pub fn get(&self, key: &str) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap();
+        let (cached_at, value) = entries.get(key)?;
+        if cached_at.elapsed() < self.ttl {
+            return Some(value.clone());
+        }
+        entries.remove(key);
+        None
+    }
+
+    /// 写入缓存
+    pub fn insert(&self, key: impl Into<String>, value: V) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.into(), (Instant::now(), value));
+    }
+}
+
+impl<V: Send> CacheControl for TtlCache<V> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn entry_count(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    fn oldest_age(&self) -> Option<Duration> {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .map(|(cached_at, _)| cached_at.elapsed())
+            .max()
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// 已登记的全部缓存（新增缓存时在此登记）
+static CACHES: &[&dyn CacheControl] = &[
+    &jd::AUTHOR_TEMPLATE_CACHE,
+    &jd::SKU_INFO_CACHE,
+    &jd::LOGIN_VERIFY_CACHE,
+];
+
+/// 缓存状态
+#[derive(Debug, Serialize)]
+pub struct CacheStatus {
+    pub name: String,
+    pub entries: usize,
+    pub age_secs: Option<u64>,
+}
+
+/// 清除缓存（kind 为空时清除全部），返回清除的缓存数量
+#[tauri::command]
+pub fn invalidate_cache(kind: Option<String>) -> Result<usize, String> {
+    let targets: Vec<_> = match kind.as_deref() {
+        Some(kind) => {
+            let matched: Vec<_> = CACHES.iter().filter(|c| c.name() == kind).collect();
+            if matched.is_empty() {
+                return Err(format!("未知缓存: {}", kind));
+            }
+            matched
+        }
+        None => CACHES.iter().collect(),
+    };
+
+    for cache in &targets {
+        cache.clear();
+        info!("[缓存] 已清除: {}", cache.name());
+    }
+
+    Ok(targets.len())
+}
+
+/// 获取所有缓存的条目数和存活时长
+#[tauri::command]
+pub fn get_cache_status() -> Vec<CacheStatus> {
+    CACHES
+        .iter()
+        .map(|c| CacheStatus {
+            name: c.name().to_string(),
+            entries: c.entry_count(),
+            age_secs: c.oldest_age().map(|d| d.as_secs()),
+        })
+        .collect()
+}
@@ -0,0 +1,237 @@
+//! 应用配置模块
+//!
+//! 代理、请求超时、日志级别、默认浏览器等设置保存在数据目录下 data/config.json，
+//! 启动时加载并应用到各模块；修改设置的命令同步更新内存中的配置并写回文件。
+//! 代理密码在文件中加密保存，内存和前端拿到的配置中为明文。
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{LazyLock, RwLock};
+
+use crate::cookie::Browser;
+use crate::crypto;
+use crate::http::{self, ProxyConfig};
+use crate::logging::{self, LogVerbosity};
+use crate::utils;
+
+/// 应用配置（缺失的字段使用默认值，兼容旧版只有 disable_webview_gpu 的配置文件）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// 读取 Cookie 时默认使用的浏览器
+    pub default_browser: Browser,
+    /// 默认浏览器配置文件（如 Default、Profile 1）
+    pub default_profile: Option<String>,
+    /// 请求超时（秒）
+    pub request_timeout_secs: u64,
+    /// Cookie 文件是否加密保存
+    pub encrypt_storage: bool,
+    /// 是否禁用 WebView2 GPU 加速（重启后生效）
+    pub disable_webview_gpu: bool,
+    /// 全局代理
+    pub proxy: Option<ProxyConfig>,
+    /// 自定义 User-Agent（为空时使用默认值）
+    pub user_agent: Option<String>,
+    /// 响应日志详细程度
+    pub log_verbosity: LogVerbosity,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            default_browser: Browser::default(),
+            default_profile: None,
+            request_timeout_secs: http::DEFAULT_TIMEOUT_SECS,
+            encrypt_storage: true,
+            disable_webview_gpu: true,
+            proxy: None,
+            user_agent: None,
+            log_verbosity: LogVerbosity::default(),
+        }
+    }
+}
+
+/// 配置文件中加密保存的代理密码前缀（没有前缀的为旧版明文密码，下次保存时加密）
+const ENCRYPTED_PASSWORD_PREFIX: &str = "enc:";
+
+/// 加密代理密码后的配置，用于写入文件
+fn encrypt_proxy_password(config: &AppConfig) -> Result<AppConfig, String> {
+    let mut config = config.clone();
+    if let Some(password) = config
+        .proxy
+        .as_mut()
+        .and_then(|proxy| proxy.password.as_mut())
+        .filter(|password| !password.is_empty())
+    {
+        let encrypted =
+            crypto::encrypt(password).map_err(|e| format!("加密代理密码失败: {}", e))?;
+        *password = format!("{}{}", ENCRYPTED_PASSWORD_PREFIX, encrypted);
+    }
+    Ok(config)
+}
+
+/// 解密从文件读取的代理密码（密钥变化等原因无法解密时丢弃密码，需要重新填写）
+fn decrypt_proxy_password(config: &mut AppConfig) {
+    let Some(proxy) = config.proxy.as_mut() else {
+        return;
+    };
+    let Some(encrypted) = proxy
+        .password
+        .as_deref()
+        .and_then(|password| password.strip_prefix(ENCRYPTED_PASSWORD_PREFIX))
+    else {
+        return;
+    };
+    proxy.password = match crypto::decrypt(encrypted) {
+        Ok(password) => Some(password),
+        Err(e) => {
+            warn!("[配置] 解密代理密码失败，已忽略: {}", e);
+            None
+        }
+    };
+}
+
+/// 当前配置（首次访问时从文件读取，文件不存在或损坏时使用默认值）
+static CONFIG: LazyLock<RwLock<AppConfig>> =
+    LazyLock::new(|| RwLock::new(read_config_file().ok().flatten().unwrap_or_default()));
+
+/// 配置文件路径（数据目录下 data/config.json）
+fn config_path() -> Result<PathBuf, String> {
+    Ok(utils::get_data_dir()?.join("data").join("config.json"))
+}
+
+/// 读取配置文件，文件不存在时返回 None
+fn read_config_file() -> Result<Option<AppConfig>, String> {
+    let file_path = config_path()?;
+    if !file_path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        std::fs::read_to_string(&file_path).map_err(|e| format!("读取配置文件失败: {}", e))?;
+    let mut config: AppConfig =
+        serde_json::from_str(&content).map_err(|e| format!("配置文件格式错误: {}", e))?;
+    decrypt_proxy_password(&mut config);
+    Ok(Some(config))
+}
+
+/// 写入配置文件（原子替换）
+fn write_config_file(config: &AppConfig) -> Result<(), String> {
+    let file_path = config_path()?;
+
+    // 创建 data 文件夹
+    if let Some(data_dir) = file_path.parent() {
+        std::fs::create_dir_all(data_dir).map_err(|e| format!("创建 data 目录失败: {}", e))?;
+    }
+
+    let json_content = serde_json::to_string_pretty(&encrypt_proxy_password(config)?)
+        .map_err(|e| format!("序列化配置失败: {}", e))?;
+    utils::write_file_atomic(&file_path, &json_content)
+}
+
+/// 把配置应用到 HTTP 客户端和日志模块（WebView2 GPU 设置仅在启动时读取）
+fn apply(config: &AppConfig) -> Result<(), String> {
+    http::configure_client(config.proxy.clone(), config.request_timeout_secs)?;
+    http::apply_user_agent(config.user_agent.as_deref())?;
+    logging::apply_log_verbosity(config.log_verbosity);
+    Ok(())
+}
+
+/// 当前配置
+pub fn current() -> AppConfig {
+    CONFIG.read().unwrap().clone()
+}
+
+/// 修改配置并写回文件（调用方负责把变更应用到对应模块）
+pub fn update(f: impl FnOnce(&mut AppConfig)) -> Result<(), String> {
+    // 持有写锁直到写完文件，避免并发修改互相覆盖
+    let mut config = CONFIG.write().unwrap();
+    f(&mut config);
+    write_config_file(&config)
+}
+
+/// 从文件加载配置并应用，首次启动没有配置文件时写入默认值
+pub fn load() -> Result<AppConfig, String> {
+    let config = match read_config_file()? {
+        Some(config) => config,
+        None => {
+            let config = AppConfig::default();
+            write_config_file(&config)?;
+            info!("[配置] 首次启动，已写入默认配置");
+            config
+        }
+    };
+
+    apply(&config)?;
+    *CONFIG.write().unwrap() = config.clone();
+    Ok(config)
+}
+
+/// 重新读取配置文件并应用
+#[tauri::command]
+pub fn load_config() -> Result<AppConfig, String> {
+    load()
+}
+
+/// 应用并保存配置（disable_webview_gpu 重启后生效）
+/// 配置无效（如代理地址错误）时返回错误，不写入文件
+#[tauri::command]
+pub fn save_config(config: AppConfig) -> Result<(), String> {
+    apply(&config)?;
+    update(|current| *current = config)?;
+    info!("[配置] 配置已保存");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_config_uses_defaults() {
+        let config: AppConfig = serde_json::from_str(r#"{"disable_webview_gpu":false}"#).unwrap();
+
+        assert!(!config.disable_webview_gpu);
+        assert_eq!(config.request_timeout_secs, http::DEFAULT_TIMEOUT_SECS);
+        assert!(config.encrypt_storage);
+        assert_eq!(config.default_browser, Browser::Chrome);
+        assert_eq!(config.log_verbosity, LogVerbosity::Summary);
+
+        let json = serde_json::to_string(&AppConfig::default()).unwrap();
+        let parsed: AppConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, AppConfig::default());
+    }
+
+    #[test]
+    fn test_proxy_password_encrypted_on_disk() {
+        let config = AppConfig {
+            proxy: Some(ProxyConfig {
+                url: "http://127.0.0.1:7890".to_string(),
+                username: Some("user".to_string()),
+                password: Some("secret".to_string()),
+            }),
+            ..AppConfig::default()
+        };
+
+        let stored = encrypt_proxy_password(&config).unwrap();
+        let json = serde_json::to_string(&stored).unwrap();
+        assert!(!json.contains("secret"), "{}", json);
+        assert!(json.contains(ENCRYPTED_PASSWORD_PREFIX));
+
+        let mut loaded: AppConfig = serde_json::from_str(&json).unwrap();
+        decrypt_proxy_password(&mut loaded);
+        assert_eq!(loaded, config);
+
+        // 旧版明文密码原样读取
+        let mut legacy = config.clone();
+        decrypt_proxy_password(&mut legacy);
+        assert_eq!(legacy, config);
+
+        // 无法解密时丢弃密码
+        let mut broken = config.clone();
+        broken.proxy.as_mut().unwrap().password = Some("enc:not-base64".to_string());
+        decrypt_proxy_password(&mut broken);
+        assert_eq!(broken.proxy.unwrap().password, None);
+    }
+}
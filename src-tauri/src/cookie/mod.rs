@@ -1,10 +1,21 @@
-//! Chrome Cookie 读取模块 - 使用 CDP 协议
+//! 浏览器 Cookie 读取模块 - 使用 CDP 协议（支持 Chrome、Edge、Brave）
 
+mod qrcode;
 mod reader;
+mod sqlite;
 
-pub use reader::{get_chrome_profiles, read_chrome_cookies_cdp, ChromeProfile};
+pub use qrcode::{QrCodeLogin, QrCodeStatus};
+pub use reader::{
+    get_chrome_profiles, read_chrome_cookies_cdp, Browser, ChromeProfile, CookieNameFilter,
+    ReadCookieOptions,
+};
 
+use log::info;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::config;
+use crate::logging;
 
 /// Cookie 数据结构
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,15 +27,20 @@ pub struct Cookie {
     pub expires: Option<i64>,
     pub is_secure: bool,
     pub is_http_only: bool,
+    /// SameSite 属性（Strict / Lax / None），来源不提供时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
 }
 
 /// Cookie 读取错误类型
 #[derive(Debug, Serialize, Deserialize)]
 pub enum CookieError {
-    /// Chrome 未安装
-    ChromeNotFound,
+    /// 浏览器未安装
+    BrowserNotFound(Browser),
     /// 浏览器启动失败
     BrowserLaunchFailed(String),
+    /// 配置文件正被运行中的浏览器占用
+    ProfileLocked(Browser),
     /// 没有找到 Cookie
     NoCookies,
     /// 其他错误
@@ -34,8 +50,15 @@ pub enum CookieError {
 impl std::fmt::Display for CookieError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            CookieError::ChromeNotFound => write!(f, "未检测到 Chrome 浏览器"),
+            CookieError::BrowserNotFound(browser) => {
+                write!(f, "未检测到 {} 浏览器", browser.name())
+            }
             CookieError::BrowserLaunchFailed(msg) => write!(f, "浏览器启动失败: {}", msg),
+            CookieError::ProfileLocked(browser) => write!(
+                f,
+                "{0} 正在运行，配置文件被占用，请关闭 {0} 或选择其他配置文件",
+                browser.name()
+            ),
             CookieError::NoCookies => write!(f, "该域名下没有 Cookie"),
             CookieError::Other(msg) => write!(f, "{}", msg),
         }
@@ -44,19 +67,231 @@ impl std::fmt::Display for CookieError {
 
 impl std::error::Error for CookieError {}
 
-/// 获取所有 Chrome 浏览器配置文件列表（Tauri Command）
+/// 从 Cookie 中提取京东账号 pin（优先 pt_pin，其次 pin），已做 URL 解码
+pub fn extract_pin(cookies: &[Cookie]) -> Option<String> {
+    ["pt_pin", "pin"].iter().find_map(|name| {
+        cookies
+            .iter()
+            .find(|c| c.name == *name && !c.value.is_empty())
+            .map(|c| {
+                urlencoding::decode(&c.value)
+                    .map(|v| v.into_owned())
+                    .unwrap_or_else(|_| c.value.clone())
+            })
+    })
+}
+
+/// 京东登录会话 Cookie（PC 端为 thor，移动端为 pt_key）
+const JD_SESSION_COOKIE_NAMES: &[&str] = &["thor", "pt_key"];
+
+/// 判断 Cookie 中是否包含未过期的京东登录会话
+pub fn has_jd_session(cookies: &[Cookie]) -> bool {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    extract_pin(cookies).is_some()
+        && cookies.iter().any(|c| {
+            JD_SESSION_COOKIE_NAMES.contains(&c.name.as_str())
+                && !c.value.is_empty()
+                && c.expires.is_none_or(|exp| exp > now)
+        })
+}
+
+/// 京东登录会话 Cookie 中最早的过期时间（Unix 秒），均为会话 Cookie 时返回 None
+pub fn session_expires_at(cookies: &[Cookie]) -> Option<i64> {
+    cookies
+        .iter()
+        .filter(|c| JD_SESSION_COOKIE_NAMES.contains(&c.name.as_str()) && !c.value.is_empty())
+        .filter_map(|c| c.expires)
+        .min()
+}
+
+/// 解析浏览器开发者工具中复制的 Cookie 字符串（name1=value1; name2=value2）
+/// 值中可以包含等号；空条目、缺少等号或名称为空的条目会被跳过
+pub fn parse_cookie_header(raw: &str, domain: &str) -> Vec<Cookie> {
+    let raw = raw.trim();
+    let raw = raw
+        .strip_prefix("Cookie:")
+        .or_else(|| raw.strip_prefix("cookie:"))
+        .unwrap_or(raw);
+
+    raw.split(';')
+        .filter_map(|entry| {
+            let (name, value) = entry.split_once('=')?;
+            let name = name.trim();
+            if name.is_empty() {
+                return None;
+            }
+            Some(Cookie {
+                name: name.to_string(),
+                value: value.trim().to_string(),
+                domain: domain.to_string(),
+                path: "/".to_string(),
+                expires: None,
+                is_secure: false,
+                is_http_only: false,
+                same_site: None,
+            })
+        })
+        .collect()
+}
+
+/// 已登录京东的浏览器配置文件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JdLoggedInProfile {
+    pub profile_id: String,
+    pub profile_name: String,
+    pub pin: String,
+}
+
+/// 获取浏览器配置文件列表（Tauri Command），browser 为空时使用配置中的默认浏览器
 #[tauri::command]
-pub fn get_browser_profiles() -> Result<Vec<ChromeProfile>, String> {
-    get_chrome_profiles().map_err(|e| e.to_string())
+pub async fn get_browser_profiles(browser: Option<Browser>) -> Result<Vec<ChromeProfile>, String> {
+    load_profiles(browser.unwrap_or_else(|| config::current().default_browser)).await
 }
 
-/// 读取 Chrome Cookie 命令（使用 CDP 协议）
+/// 在阻塞线程池中读取配置文件列表（Local State 读取失败时会阻塞等待重试）
+async fn load_profiles(browser: Browser) -> Result<Vec<ChromeProfile>, String> {
+    tokio::task::spawn_blocking(move || get_chrome_profiles(browser))
+        .await
+        .map_err(|e| format!("读取配置文件任务失败: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// read_chrome_cookies 命令的可选参数（均可省略）
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReadCookiesArgs {
+    /// 浏览器，为空时使用配置中的默认浏览器
+    pub browser: Option<Browser>,
+    /// 配置文件，为空时使用配置中的默认配置文件
+    pub profile: Option<String>,
+    /// 只读取京东登录必需的 Cookie，减少后续每个请求携带的数据量
+    pub required_names: Option<Vec<String>>,
+    /// 按名称前缀过滤，与 required_names 同时设置时需同时满足
+    pub name_prefix: Option<String>,
+    /// 浏览器已运行时通过远程调试端口读取
+    pub debug_port: Option<u16>,
+    /// 直接读取 Cookies 数据库，不启动浏览器（仅 Windows，失败时回退到 CDP）
+    pub fast: Option<bool>,
+    /// 整个读取流程的超时（默认 30 秒），超时后关闭浏览器并返回错误
+    pub timeout_secs: Option<u64>,
+}
+
+/// 读取浏览器 Cookie 命令（使用 CDP 协议）
 #[tauri::command]
 pub async fn read_chrome_cookies(
     domain: String,
-    profile: Option<String>,
+    options: Option<ReadCookiesArgs>,
 ) -> Result<Vec<Cookie>, String> {
-    read_chrome_cookies_cdp(&domain, profile.as_deref())
+    let args = options.unwrap_or_default();
+    let config = config::current();
+    let mut options = ReadCookieOptions::new(args.browser.unwrap_or(config.default_browser));
+    options.profile = args.profile.or(config.default_profile);
+    options.filter = CookieNameFilter {
+        names: args.required_names,
+        prefix: args.name_prefix.filter(|p| !p.is_empty()),
+    };
+    options.debug_port = args.debug_port;
+    options.fast = args.fast.unwrap_or(false);
+    if let Some(secs) = args.timeout_secs.filter(|secs| *secs > 0) {
+        options.timeout = Duration::from_secs(secs);
+    }
+
+    read_chrome_cookies_cdp(&domain, &options)
         .await
         .map_err(|e| e.to_string())
 }
+
+/// 解析手动粘贴的 Cookie 字符串，结果可直接用于 verify_jd_login 等命令
+#[tauri::command]
+pub fn parse_cookie_string(raw: String, domain: String) -> Result<Vec<Cookie>, String> {
+    let domain = domain.trim();
+    if domain.is_empty() {
+        return Err("域名不能为空".to_string());
+    }
+
+    let cookies = parse_cookie_header(&raw, domain);
+    if cookies.is_empty() {
+        return Err("未解析到有效的 Cookie，格式应为 name1=value1; name2=value2".to_string());
+    }
+    info!("[Cookie 导入] 解析到 {} 个 Cookie", cookies.len());
+    Ok(cookies)
+}
+
+/// 获取 Cookie 对应的京东账号 pin
+#[tauri::command]
+pub fn get_account_pin(cookies: Vec<Cookie>) -> Option<String> {
+    extract_pin(&cookies)
+}
+
+/// 扫描浏览器所有配置文件（含 Default），找出已登录京东的配置文件
+/// 优先直接读取 Cookies 数据库；回退到 CDP 时每个配置文件需启动一次 headless 浏览器，
+/// 且同一用户数据目录不能并行启动，只能逐个扫描
+#[tauri::command]
+pub async fn find_jd_logged_in_profile(
+    browser: Option<Browser>,
+) -> Result<Vec<JdLoggedInProfile>, String> {
+    let browser = browser.unwrap_or_else(|| config::current().default_browser);
+    let mut candidates = vec![("Default".to_string(), "默认".to_string())];
+    candidates.extend(
+        load_profiles(browser)
+            .await?
+            .into_iter()
+            .map(|p| (p.id, p.name)),
+    );
+
+    // 只读取判断登录所需的 Cookie
+    let mut options = ReadCookieOptions::new(browser);
+    options.filter = CookieNameFilter {
+        names: Some(
+            ["pt_pin", "pin"]
+                .iter()
+                .chain(JD_SESSION_COOKIE_NAMES)
+                .map(|name| name.to_string())
+                .collect(),
+        ),
+        prefix: None,
+    };
+    options.fast = true;
+
+    let mut logged_in = Vec::new();
+    for (profile_id, profile_name) in candidates {
+        options.profile = Some(profile_id.clone());
+        match read_chrome_cookies_cdp("jd.com", &options).await {
+            Ok(cookies) if has_jd_session(&cookies) => {
+                if let Some(pin) = extract_pin(&cookies) {
+                    info!(
+                        "[登录扫描] {} 已登录京东: {}",
+                        profile_name,
+                        logging::mask_str(&pin)
+                    );
+                    logged_in.push(JdLoggedInProfile {
+                        profile_id,
+                        profile_name,
+                        pin,
+                    });
+                }
+            }
+            Ok(_) => info!("[登录扫描] {} 未登录京东", profile_name),
+            Err(e) => info!("[登录扫描] {} 读取 Cookie 失败: {}", profile_name, e),
+        }
+    }
+
+    Ok(logged_in)
+}
+
+/// 获取京东扫码登录二维码（无需本地浏览器）
+/// 前端展示二维码后用返回的 token 轮询 jd_qrcode_check
+#[tauri::command]
+pub async fn jd_qrcode_login() -> Result<QrCodeLogin, String> {
+    qrcode::request_qrcode().await
+}
+
+/// 查询扫码状态（waiting / scanned / expired / success），成功时返回登录 Cookie
+#[tauri::command]
+pub async fn jd_qrcode_check(token: String) -> Result<QrCodeStatus, String> {
+    qrcode::check_qrcode(&token).await
+}
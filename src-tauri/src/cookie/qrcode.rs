@@ -0,0 +1,363 @@
+// 京东扫码登录 - 无需本地浏览器即可获取登录 Cookie
+use crate::cookie::Cookie;
+use crate::http;
+use crate::logging;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+/// 京东 PC 登录页（扫码接口要求的 Referer）
+const LOGIN_PAGE_URL: &str = "https://passport.jd.com/new/login.aspx";
+
+/// 扫码接口 appid
+const QRCODE_APPID: &str = "133";
+
+/// 二维码 token 所在的 Cookie 名
+const QRCODE_TOKEN_COOKIE: &str = "wlfstk_smdl";
+
+/// 二维码信息
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QrCodeLogin {
+    /// 轮询扫码状态时使用的 token
+    pub token: String,
+    /// 二维码图片（data URL）
+    pub image: String,
+}
+
+/// 扫码状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrCodeState {
+    /// 等待扫描
+    Waiting,
+    /// 已扫描，等待手机确认
+    Scanned,
+    /// 二维码已过期或失效，需要重新获取
+    Expired,
+    /// 登录成功
+    Success,
+}
+
+/// 扫码状态查询结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QrCodeStatus {
+    pub state: QrCodeState,
+    pub message: String,
+    /// 登录成功时返回的 Cookie
+    pub cookies: Option<Vec<Cookie>>,
+}
+
+/// 错误信息中附带的响应片段最大字符数
+const ERROR_SNIPPET_CHARS: usize = 200;
+
+/// 扫码状态接口返回（JSONP 包裹）
+#[derive(Debug, Deserialize)]
+struct QrCodeCheckResponse {
+    code: i32,
+    msg: Option<String>,
+    ticket: Option<String>,
+}
+
+/// 登录凭证校验接口返回（returnCode 为 0 表示成功）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TicketValidationResponse {
+    return_code: i32,
+    msg: Option<String>,
+}
+
+fn timestamp_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// 公历日期转 Unix 天数
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// 解析 Expires 日期（如 Sat, 14-Nov-2026 08:00:00 GMT）为 Unix 秒
+fn parse_cookie_expires(value: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+
+    let date = value.split_once(',').map_or(value, |(_, rest)| rest);
+    let parts: Vec<&str> = date
+        .split(|c: char| c == ' ' || c == '-')
+        .filter(|p| !p.is_empty())
+        .collect();
+    let [day, month, year, time, ..] = parts.as_slice() else {
+        return None;
+    };
+
+    let month = MONTHS
+        .iter()
+        .position(|m| month.to_lowercase().starts_with(m))? as i64
+        + 1;
+    let mut year: i64 = year.parse().ok()?;
+    if year < 100 {
+        year += 2000;
+    }
+    let mut hms = time.split(':').map(|v| v.parse::<i64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+
+    let days = days_from_civil(year, month, day.parse().ok()?);
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// 解析 Set-Cookie 响应头，未指定 Domain 时使用请求主机
+fn parse_set_cookie(header: &str, default_domain: &str) -> Option<Cookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.trim().split_once('=')?;
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut cookie = Cookie {
+        name: name.to_string(),
+        value: value.to_string(),
+        domain: default_domain.to_string(),
+        path: "/".to_string(),
+        expires: None,
+        is_secure: false,
+        is_http_only: false,
+        same_site: None,
+    };
+
+    let mut max_age = None;
+    for attr in parts {
+        let (key, val) = attr.trim().split_once('=').unwrap_or((attr.trim(), ""));
+        match key.to_lowercase().as_str() {
+            "domain" if !val.is_empty() => cookie.domain = val.to_string(),
+            "path" if !val.is_empty() => cookie.path = val.to_string(),
+            "expires" => cookie.expires = parse_cookie_expires(val),
+            "max-age" => max_age = val.parse::<i64>().ok(),
+            "secure" => cookie.is_secure = true,
+            "httponly" => cookie.is_http_only = true,
+            "samesite" if !val.is_empty() => cookie.same_site = Some(val.to_string()),
+            _ => {}
+        }
+    }
+
+    // Max-Age 优先于 Expires
+    if let Some(max_age) = max_age {
+        cookie.expires = Some((timestamp_millis() / 1000) as i64 + max_age);
+    }
+
+    Some(cookie)
+}
+
+/// 提取响应中的全部 Set-Cookie
+fn response_cookies(response: &reqwest::Response) -> Vec<Cookie> {
+    let host = response.url().host_str().unwrap_or_default().to_string();
+    response
+        .headers()
+        .get_all(reqwest::header::SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .filter_map(|v| parse_set_cookie(v, &host))
+        .collect()
+}
+
+/// 去掉 JSONP 回调包裹，如 jQuery123({...}) -> {...}
+fn strip_jsonp(text: &str) -> &str {
+    match (text.find('('), text.rfind(')')) {
+        (Some(start), Some(end)) if start < end => &text[start + 1..end],
+        _ => text,
+    }
+}
+
+/// 请求登录二维码
+pub async fn request_qrcode() -> Result<QrCodeLogin, String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let url = format!(
+        "https://qr.m.jd.com/show?appid={}&size=147&t={}",
+        QRCODE_APPID,
+        timestamp_millis()
+    );
+
+    let response = http::client()
+        .get(&url)
+        .header(reqwest::header::USER_AGENT, http::user_agent())
+        .header(reqwest::header::REFERER, LOGIN_PAGE_URL)
+        .send()
+        .await
+        .map_err(|e| format!("获取二维码失败: {}", e))?;
+
+    let token = response_cookies(&response)
+        .into_iter()
+        .find(|c| c.name == QRCODE_TOKEN_COOKIE)
+        .map(|c| c.value)
+        .ok_or("获取二维码失败: 响应中没有二维码 token")?;
+
+    let image = response
+        .bytes()
+        .await
+        .map_err(|e| format!("读取二维码图片失败: {}", e))?;
+
+    info!("[扫码登录] 已获取二维码");
+    Ok(QrCodeLogin {
+        token,
+        image: format!("data:image/png;base64,{}", STANDARD.encode(image)),
+    })
+}
+
+/// 查询扫码状态，确认登录后换取登录 Cookie
+pub async fn check_qrcode(token: &str) -> Result<QrCodeStatus, String> {
+    let url = format!(
+        "https://qr.m.jd.com/check?appid={}&token={}&callback=jsonpCallback&_={}",
+        QRCODE_APPID,
+        urlencoding::encode(token),
+        timestamp_millis()
+    );
+
+    let text = http::client()
+        .get(&url)
+        .header(reqwest::header::USER_AGENT, http::user_agent())
+        .header(reqwest::header::REFERER, LOGIN_PAGE_URL)
+        .header(
+            reqwest::header::COOKIE,
+            format!("{}={}", QRCODE_TOKEN_COOKIE, token),
+        )
+        .send()
+        .await
+        .map_err(|e| format!("查询扫码状态失败: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+
+    let data: QrCodeCheckResponse = serde_json::from_str(strip_jsonp(&text)).map_err(|e| {
+        format!(
+            "解析扫码状态失败: {}，响应片段: {}",
+            e,
+            logging::redacted_snippet(&text, ERROR_SNIPPET_CHARS)
+        )
+    })?;
+    let message = data.msg.unwrap_or_default();
+
+    let state = match data.code {
+        200 => QrCodeState::Success,
+        201 => QrCodeState::Waiting,
+        202 => QrCodeState::Scanned,
+        203 | 205 | 257 => QrCodeState::Expired,
+        code => return Err(format!("扫码登录失败（{}）: {}", code, message)),
+    };
+
+    if state != QrCodeState::Success {
+        return Ok(QrCodeStatus {
+            state,
+            message,
+            cookies: None,
+        });
+    }
+
+    let ticket = data.ticket.ok_or("扫码登录失败: 缺少登录凭证")?;
+    let cookies = validate_ticket(&ticket).await?;
+    info!("[扫码登录] 登录成功，获取到 {} 个 Cookie", cookies.len());
+
+    Ok(QrCodeStatus {
+        state,
+        message,
+        cookies: Some(cookies),
+    })
+}
+
+/// 检查登录凭证校验结果，returnCode 非 0 或响应无法解析时视为失败
+fn check_ticket_response(text: &str) -> Result<(), String> {
+    let data: TicketValidationResponse = serde_json::from_str(strip_jsonp(text)).map_err(|e| {
+        format!(
+            "登录凭证校验失败: 解析响应失败: {}，响应片段: {}",
+            e,
+            logging::redacted_snippet(text, ERROR_SNIPPET_CHARS)
+        )
+    })?;
+    if data.return_code != 0 {
+        return Err(format!(
+            "登录凭证校验失败（{}）: {}",
+            data.return_code,
+            data.msg.unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+/// 用扫码凭证换取登录 Cookie（校验失败时京东也可能下发 Cookie，以响应体的 returnCode 为准）
+async fn validate_ticket(ticket: &str) -> Result<Vec<Cookie>, String> {
+    let url = format!(
+        "https://passport.jd.com/uc/qrCodeTicketValidation?t={}",
+        urlencoding::encode(ticket)
+    );
+
+    let response = http::client()
+        .get(&url)
+        .header(reqwest::header::USER_AGENT, http::user_agent())
+        .header(reqwest::header::REFERER, LOGIN_PAGE_URL)
+        .send()
+        .await
+        .map_err(|e| format!("登录凭证校验失败: {}", e))?;
+
+    let cookies = response_cookies(&response);
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("登录凭证校验失败: 读取响应失败: {}", e))?;
+    check_ticket_response(&text)?;
+    if cookies.is_empty() {
+        return Err("登录凭证校验失败: 未返回 Cookie".to_string());
+    }
+
+    Ok(cookies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_set_cookie() {
+        let cookie = parse_set_cookie(
+            "thor=ABC123; Domain=.jd.com; Path=/; Expires=Sat, 14-Nov-2026 08:00:00 GMT; HttpOnly; Secure",
+            "passport.jd.com",
+        )
+        .unwrap();
+
+        assert_eq!(cookie.name, "thor");
+        assert_eq!(cookie.value, "ABC123");
+        assert_eq!(cookie.domain, ".jd.com");
+        assert_eq!(cookie.expires, Some(1794643200));
+        assert!(cookie.is_http_only && cookie.is_secure);
+        assert_eq!(cookie.same_site, None);
+
+        let host_only = parse_set_cookie("pin=test", "passport.jd.com").unwrap();
+        assert_eq!(host_only.domain, "passport.jd.com");
+        assert_eq!(host_only.expires, None);
+    }
+
+    #[test]
+    fn test_strip_jsonp() {
+        assert_eq!(
+            strip_jsonp(r#"jsonpCallback({"code":201,"msg":"二维码未扫描"})"#),
+            r#"{"code":201,"msg":"二维码未扫描"}"#
+        );
+        assert_eq!(strip_jsonp(r#"{"code":201}"#), r#"{"code":201}"#);
+    }
+
+    #[test]
+    fn test_check_ticket_response() {
+        assert!(check_ticket_response(r#"{"returnCode":0,"url":"//www.jd.com"}"#).is_ok());
+
+        let err = check_ticket_response(r#"{"returnCode":1,"msg":"凭证已失效"}"#).unwrap_err();
+        assert!(err.contains("凭证已失效"), "{}", err);
+
+        let err = check_ticket_response("<html>ticket=secret-ticket</html>").unwrap_err();
+        assert!(err.contains("解析响应失败"), "{}", err);
+    }
+}
@@ -1,11 +1,182 @@
-// Chrome Cookie 读取器 - 使用 CDP 协议
-use crate::cookie::{Cookie, CookieError};
-use chromiumoxide::browser::{Browser, BrowserConfig};
+// Chrome Cookie 读取器 - 使用 CDP 协议（同样适用于 Edge、Brave 等 Chromium 内核浏览器）
+use crate::cookie::{sqlite, Cookie, CookieError};
+use chromiumoxide::browser::{Browser as CdpBrowser, BrowserConfig};
 use chromiumoxide::cdp::browser_protocol::storage::GetCookiesParams;
 use futures::StreamExt;
-use log::info;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 支持读取 Cookie 的浏览器（均为 Chromium 内核，可复用 CDP 流程）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Browser {
+    #[default]
+    Chrome,
+    Edge,
+    Brave,
+}
+
+impl Browser {
+    /// 显示名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Browser::Chrome => "Chrome",
+            Browser::Edge => "Edge",
+            Browser::Brave => "Brave",
+        }
+    }
+
+    /// 安装目录（相对于 LocalAppData 或 Program Files）
+    #[cfg(windows)]
+    fn vendor_dir(self) -> &'static [&'static str] {
+        match self {
+            Browser::Chrome => &["Google", "Chrome"],
+            Browser::Edge => &["Microsoft", "Edge"],
+            Browser::Brave => &["BraveSoftware", "Brave-Browser"],
+        }
+    }
+
+    /// 可执行文件名
+    #[cfg(windows)]
+    fn executable_name(self) -> &'static str {
+        match self {
+            Browser::Chrome => "chrome.exe",
+            Browser::Edge => "msedge.exe",
+            Browser::Brave => "brave.exe",
+        }
+    }
+
+    /// 用户数据目录（%LOCALAPPDATA%\Google\Chrome\User Data 等）
+    #[cfg(windows)]
+    fn user_data_candidate(self) -> Option<PathBuf> {
+        let local_app_data = dirs::data_local_dir()?;
+        Some(
+            self.vendor_dir()
+                .iter()
+                .fold(local_app_data, |path, dir| path.join(dir))
+                .join("User Data"),
+        )
+    }
+
+    /// 可执行文件候选路径（先用户安装路径，再系统安装路径）
+    #[cfg(windows)]
+    fn executable_candidates(self) -> Vec<PathBuf> {
+        let roots = [
+            dirs::data_local_dir(),
+            Some(PathBuf::from(r"C:\Program Files")),
+            Some(PathBuf::from(r"C:\Program Files (x86)")),
+        ];
+
+        roots
+            .into_iter()
+            .flatten()
+            .map(|root| {
+                self.vendor_dir()
+                    .iter()
+                    .fold(root, |path, dir| path.join(dir))
+                    .join("Application")
+                    .join(self.executable_name())
+            })
+            .collect()
+    }
+
+    /// 应用名（/Applications 下的 .app 包名，同时是包内可执行文件名）
+    #[cfg(target_os = "macos")]
+    fn app_name(self) -> &'static str {
+        match self {
+            Browser::Chrome => "Google Chrome",
+            Browser::Edge => "Microsoft Edge",
+            Browser::Brave => "Brave Browser",
+        }
+    }
+
+    /// 用户数据目录（~/Library/Application Support/Google/Chrome 等）
+    #[cfg(target_os = "macos")]
+    fn user_data_candidate(self) -> Option<PathBuf> {
+        let dir = match self {
+            Browser::Chrome => "Google/Chrome",
+            Browser::Edge => "Microsoft Edge",
+            Browser::Brave => "BraveSoftware/Brave-Browser",
+        };
+        Some(dirs::data_dir()?.join(dir))
+    }
+
+    /// 可执行文件候选路径（先系统 /Applications，再用户 ~/Applications）
+    #[cfg(target_os = "macos")]
+    fn executable_candidates(self) -> Vec<PathBuf> {
+        let roots = [
+            Some(PathBuf::from("/Applications")),
+            dirs::home_dir().map(|home| home.join("Applications")),
+        ];
+
+        roots
+            .into_iter()
+            .flatten()
+            .map(|root| {
+                root.join(format!("{}.app", self.app_name()))
+                    .join("Contents")
+                    .join("MacOS")
+                    .join(self.app_name())
+            })
+            .collect()
+    }
+
+    /// 用户数据目录（~/.config/google-chrome 等）
+    #[cfg(target_os = "linux")]
+    fn user_data_candidate(self) -> Option<PathBuf> {
+        let dir = match self {
+            Browser::Chrome => "google-chrome",
+            Browser::Edge => "microsoft-edge",
+            Browser::Brave => "BraveSoftware/Brave-Browser",
+        };
+        Some(dirs::config_dir()?.join(dir))
+    }
+
+    /// 可执行文件候选路径（在 PATH 中查找各发行版常用的命令名）
+    #[cfg(target_os = "linux")]
+    fn executable_candidates(self) -> Vec<PathBuf> {
+        let names: &[&str] = match self {
+            Browser::Chrome => &["google-chrome", "google-chrome-stable"],
+            Browser::Edge => &["microsoft-edge", "microsoft-edge-stable"],
+            Browser::Brave => &["brave-browser", "brave"],
+        };
+        let paths = std::env::var_os("PATH").unwrap_or_default();
+
+        std::env::split_paths(&paths)
+            .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+            .collect()
+    }
+
+    /// 不支持的平台
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    fn user_data_candidate(self) -> Option<PathBuf> {
+        None
+    }
+
+    /// 不支持的平台
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+    fn executable_candidates(self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// 获取用户数据目录
+    pub fn user_data_dir(self) -> Result<PathBuf, CookieError> {
+        self.user_data_candidate()
+            .filter(|path| path.exists())
+            .ok_or(CookieError::BrowserNotFound(self))
+    }
+
+    /// 查找可执行文件路径
+    pub fn find_executable(self) -> Result<PathBuf, CookieError> {
+        self.executable_candidates()
+            .into_iter()
+            .find(|path| path.is_file())
+            .ok_or(CookieError::BrowserNotFound(self))
+    }
+}
 
 /// Chrome 浏览器配置文件信息
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,18 +184,53 @@ pub struct ChromeProfile {
     pub id: String,
     pub name: String,
     pub profile_path: String,
+    /// 登录的 Google 账号邮箱（老版本或未登录时为 None）
+    pub email: Option<String>,
+    /// 头像图片路径（老版本或未设置头像时为 None）
+    pub avatar_path: Option<String>,
 }
 
-/// 获取所有 Chrome 浏览器配置文件列表
-pub fn get_chrome_profiles() -> Result<Vec<ChromeProfile>, CookieError> {
-    let user_data_dir = get_chrome_user_data_dir()?;
+/// Local State 读取次数（Chrome 正在写入时可能读到不完整的 JSON）
+const LOCAL_STATE_READ_ATTEMPTS: u32 = 3;
+
+/// Local State 重试间隔（毫秒）
+const LOCAL_STATE_RETRY_DELAY_MS: u64 = 100;
+
+/// 读取并解析 Local State 文件，失败时短暂等待后重试
+pub(super) fn read_local_state(path: &Path) -> Result<serde_json::Value, String> {
+    let mut last_error = String::new();
+
+    for attempt in 1..=LOCAL_STATE_READ_ATTEMPTS {
+        match std::fs::read_to_string(path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(json) => return Ok(json),
+                Err(e) => last_error = format!("解析失败: {}", e),
+            },
+            Err(e) => last_error = format!("读取失败: {}", e),
+        }
+
+        if attempt < LOCAL_STATE_READ_ATTEMPTS {
+            warn!(
+                "[配置文件] Local State 第 {} 次读取失败，稍后重试: {}",
+                attempt, last_error
+            );
+            std::thread::sleep(Duration::from_millis(LOCAL_STATE_RETRY_DELAY_MS));
+        }
+    }
+
+    Err(last_error)
+}
+
+/// 获取指定浏览器的所有配置文件列表
+pub fn get_chrome_profiles(browser: Browser) -> Result<Vec<ChromeProfile>, CookieError> {
+    let user_data_dir = browser.user_data_dir()?;
     let mut profiles = Vec::new();
 
     // 读取 Local State 文件获取配置文件信息
     let local_state_path = user_data_dir.join("Local State");
     if local_state_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(&local_state_path) {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+        match read_local_state(&local_state_path) {
+            Ok(json) => {
                 if let Some(profile_info) = json.get("profile").and_then(|p| p.get("info_cache")) {
                     if let Some(obj) = profile_info.as_object() {
                         for (profile_dir, info) in obj {
@@ -36,16 +242,37 @@ pub fn get_chrome_profiles() -> Result<Vec<ChromeProfile>, CookieError> {
 
                             let profile_path = user_data_dir.join(profile_dir);
                             if profile_path.exists() {
+                                let email = info
+                                    .get("user_name")
+                                    .and_then(|v| v.as_str())
+                                    .filter(|v| !v.is_empty())
+                                    .map(|v| v.to_string());
+                                let avatar_path = info
+                                    .get("gaia_picture_file_name")
+                                    .and_then(|v| v.as_str())
+                                    .filter(|v| !v.is_empty())
+                                    .map(|file| profile_path.join(file))
+                                    .filter(|path| path.exists())
+                                    .map(|path| path.to_string_lossy().to_string());
+
                                 profiles.push(ChromeProfile {
                                     id: profile_dir.clone(),
                                     name,
                                     profile_path: profile_path.to_string_lossy().to_string(),
+                                    email,
+                                    avatar_path,
                                 });
                             }
                         }
                     }
                 }
             }
+            Err(e) => {
+                warn!(
+                    "[配置文件] 无法读取配置文件列表（Local State {}），改为扫描目录",
+                    e
+                );
+            }
         }
     }
 
@@ -58,6 +285,8 @@ pub fn get_chrome_profiles() -> Result<Vec<ChromeProfile>, CookieError> {
                 id: "Default".to_string(),
                 name: "默认".to_string(),
                 profile_path: default_path.to_string_lossy().to_string(),
+                email: None,
+                avatar_path: None,
             });
         }
 
@@ -70,6 +299,8 @@ pub fn get_chrome_profiles() -> Result<Vec<ChromeProfile>, CookieError> {
                         id: name.clone(),
                         name: name.clone(),
                         profile_path: entry.path().to_string_lossy().to_string(),
+                        email: None,
+                        avatar_path: None,
                     });
                 }
             }
@@ -82,125 +313,312 @@ pub fn get_chrome_profiles() -> Result<Vec<ChromeProfile>, CookieError> {
     // 按名称排序
     profiles.sort_by(|a, b| a.name.cmp(&b.name));
 
-    info!("找到 {} 个 Chrome 配置文件", profiles.len());
+    info!("找到 {} 个 {} 配置文件", profiles.len(), browser.name());
     Ok(profiles)
 }
 
-/// 获取 Chrome 用户数据目录
-pub fn get_chrome_user_data_dir() -> Result<PathBuf, CookieError> {
-    let local_app_data =
-        dirs::data_local_dir().ok_or_else(|| CookieError::Other("无法获取 LocalAppData 目录".to_string()))?;
+/// 从 URL 提取域名
+pub fn extract_domain(url: &str) -> String {
+    let url = url.trim();
+    let url = url.strip_prefix("http://").unwrap_or(url);
+    let url = url.strip_prefix("https://").unwrap_or(url);
+    let url = url.strip_prefix("www.").unwrap_or(url);
 
-    let chrome_path = local_app_data.join("Google").join("Chrome").join("User Data");
+    url.split('/').next().unwrap_or(url).to_string()
+}
 
-    if chrome_path.exists() {
-        Ok(chrome_path)
-    } else {
-        Err(CookieError::ChromeNotFound)
-    }
+/// 规范化域名：去掉开头的点并转小写，空域名返回 None
+fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim_start_matches('.').to_lowercase();
+    (!domain.is_empty()).then_some(domain)
 }
 
-/// 查找 Chrome 可执行文件路径
-pub fn find_chrome_executable() -> Result<PathBuf, CookieError> {
-    // 用户安装路径
-    if let Some(local_app_data) = dirs::data_local_dir() {
-        let user_chrome = local_app_data
-            .join("Google")
-            .join("Chrome")
-            .join("Application")
-            .join("chrome.exe");
-        if user_chrome.exists() {
-            return Ok(user_chrome);
-        }
-    }
+/// host 是否为 parent 本身或以点分隔的子域名（notjd.com、jd.com.cn 不算 jd.com 的子域名）
+/// 单标签的 parent（如 com）只允许完全匹配
+fn is_same_or_subdomain(host: &str, parent: &str) -> bool {
+    host == parent
+        || (parent.contains('.')
+            && host
+                .strip_suffix(parent)
+                .is_some_and(|prefix| prefix.ends_with('.')))
+}
 
-    // 系统安装路径
-    let system_paths = [
-        r"C:\Program Files\Google\Chrome\Application\chrome.exe",
-        r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
-    ];
+/// 按 RFC 6265 5.1.3 检查 Cookie 是否会发送到目标域名：
+/// 完全匹配，或 Cookie 域名是目标域名以点分隔的后缀（.jd.com 的 Cookie 会发送到 drlives.jd.com）。
+/// 子域名的 Cookie 不会发送到父域名，a.jd.com 不匹配 jd.com
+pub(super) fn domain_matches(cookie_domain: &str, target_domain: &str) -> bool {
+    let (Some(cookie_domain), Some(target_domain)) = (
+        normalize_domain(cookie_domain),
+        normalize_domain(target_domain),
+    ) else {
+        return false;
+    };
+    is_same_or_subdomain(&target_domain, &cookie_domain)
+}
 
-    for path in system_paths {
-        let p = PathBuf::from(path);
-        if p.exists() {
-            return Ok(p);
+/// 读取目标域名下的全部 Cookie 时使用：除 domain_matches 外，还包含目标域名各子域名的 Cookie
+/// （读取 jd.com 时需要 drlives.jd.com、passport.jd.com 等子域名下的登录 Cookie）
+pub(super) fn domain_tree_matches(cookie_domain: &str, target_domain: &str) -> bool {
+    if domain_matches(cookie_domain, target_domain) {
+        return true;
+    }
+    match (
+        normalize_domain(cookie_domain),
+        normalize_domain(target_domain),
+    ) {
+        (Some(cookie_domain), Some(target_domain)) => {
+            is_same_or_subdomain(&cookie_domain, &target_domain)
         }
+        _ => false,
+    }
+}
+
+/// 判断用户数据目录是否被正在运行的浏览器占用
+/// Windows 下运行中的浏览器独占 lockfile，其他平台会创建 SingletonLock 链接
+fn is_profile_locked(user_data_dir: &Path) -> bool {
+    let lockfile = user_data_dir.join("lockfile");
+    if lockfile.exists() && OpenOptions::new().write(true).open(&lockfile).is_err() {
+        return true;
     }
 
-    Err(CookieError::ChromeNotFound)
+    user_data_dir
+        .join("SingletonLock")
+        .symlink_metadata()
+        .is_ok()
 }
 
-/// 从 URL 提取域名
-pub fn extract_domain(url: &str) -> String {
-    let url = url.trim();
-    let url = url.strip_prefix("http://").unwrap_or(url);
-    let url = url.strip_prefix("https://").unwrap_or(url);
-    let url = url.strip_prefix("www.").unwrap_or(url);
+/// Cookie 名称过滤条件（两者同时设置时需同时满足）
+#[derive(Debug, Clone, Default)]
+pub struct CookieNameFilter {
+    /// 只保留名称在列表中的 Cookie
+    pub names: Option<Vec<String>>,
+    /// 只保留名称以此开头的 Cookie
+    pub prefix: Option<String>,
+}
 
-    url.split('/').next().unwrap_or(url).to_string()
+impl CookieNameFilter {
+    fn matches(&self, name: &str) -> bool {
+        self.names
+            .as_ref()
+            .is_none_or(|names| names.iter().any(|n| n == name))
+            && self
+                .prefix
+                .as_deref()
+                .is_none_or(|prefix| name.starts_with(prefix))
+    }
 }
 
-/// 检查 Cookie 域名是否匹配目标域名
-/// 匹配规则：jd.com 应该匹配 jd.com、.jd.com、*.jd.com 等所有子域名
-fn domain_matches(cookie_domain: &str, target_domain: &str) -> bool {
-    let cookie_domain = cookie_domain.trim_start_matches('.').to_lowercase();
-    let target_domain = target_domain.trim_start_matches('.').to_lowercase();
+/// 先按域名、再按名称筛选 Cookie，结果为空时返回 NoCookies
+pub(super) fn select_cookies(
+    cookies: Vec<Cookie>,
+    target_domain: &str,
+    filter: &CookieNameFilter,
+) -> Result<Vec<Cookie>, CookieError> {
+    let mut cookies: Vec<Cookie> = cookies
+        .into_iter()
+        .filter(|c| domain_tree_matches(&c.domain, target_domain))
+        .filter(|c| {
+            let matched = filter.matches(&c.name);
+            if matched {
+                info!("[Cookie] 匹配: {} (域名: {})", c.name, c.domain);
+            }
+            matched
+        })
+        .collect();
 
-    // 完全匹配
-    if cookie_domain == target_domain {
-        return true;
+    if cookies.is_empty() {
+        return Err(CookieError::NoCookies);
     }
 
-    // Cookie 域名是目标域名的子域名（如 drlives.jd.com 匹配 jd.com）
-    if cookie_domain.ends_with(&format!(".{}", target_domain)) {
-        return true;
+    // 按名称排序
+    cookies.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(cookies)
+}
+
+/// 浏览器会话：无论从哪条路径返回都会关闭浏览器并停止事件处理任务
+/// 连接到用户已运行的浏览器时只断开连接，不关闭浏览器
+struct BrowserSession {
+    browser: Option<CdpBrowser>,
+    handler: tokio::task::JoinHandle<()>,
+    launched: bool,
+}
+
+impl BrowserSession {
+    fn browser(&self) -> &CdpBrowser {
+        self.browser.as_ref().expect("浏览器会话已关闭")
     }
 
-    // 目标域名是 Cookie 域名的子域名（如 jd.com 匹配 drlives.jd.com）
-    if target_domain.ends_with(&format!(".{}", cookie_domain)) {
-        return true;
+    /// 关闭浏览器并等待进程退出
+    async fn close(mut self) {
+        if let Some(mut browser) = self.browser.take().filter(|_| self.launched) {
+            let _ = browser.close().await;
+            let _ = browser.wait().await;
+        }
     }
+}
 
-    false
+impl Drop for BrowserSession {
+    fn drop(&mut self) {
+        // 提前返回时无法在 Drop 中 await，交给后台任务关闭
+        // 关闭命令需要事件处理任务转发，因此关闭完成后再停止该任务
+        match self.browser.take().filter(|_| self.launched) {
+            Some(mut browser) => {
+                let handler = self.handler.abort_handle();
+                tokio::spawn(async move {
+                    let _ = browser.close().await;
+                    let _ = browser.wait().await;
+                    handler.abort();
+                });
+            }
+            None => self.handler.abort(),
+        }
+    }
+}
+
+/// 读取 Cookie 的默认超时（含启动浏览器）
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 给读取流程加超时，超时后丢弃 future（BrowserSession 随之关闭浏览器）
+async fn with_read_timeout(
+    timeout: Duration,
+    read: impl std::future::Future<Output = Result<Vec<Cookie>, CookieError>>,
+) -> Result<Vec<Cookie>, CookieError> {
+    match tokio::time::timeout(timeout, read).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!("[Cookie] 读取超过 {} 秒未完成，已中止", timeout.as_secs());
+            Err(CookieError::Other("读取超时".to_string()))
+        }
+    }
+}
+
+/// 读取 Cookie 的选项
+#[derive(Debug, Clone)]
+pub struct ReadCookieOptions {
+    pub browser: Browser,
+    /// 配置文件目录名，为空时使用 Default
+    pub profile: Option<String>,
+    /// 只返回指定名称或前缀的 Cookie
+    pub filter: CookieNameFilter,
+    /// 已运行浏览器的远程调试端口（--remote-debugging-port），配置文件被占用时尝试连接
+    pub debug_port: Option<u16>,
+    /// 先直接读取 Cookies 数据库（不启动浏览器），失败时回退到 CDP
+    pub fast: bool,
+    /// 整个读取流程（含启动浏览器）的超时，超时返回「读取超时」并关闭浏览器
+    pub timeout: Duration,
 }
 
-/// 使用 CDP 协议读取 Chrome Cookie
+impl ReadCookieOptions {
+    /// 读取 Default 配置文件的全部 Cookie，不走快速读取
+    pub fn new(browser: Browser) -> Self {
+        Self {
+            browser,
+            profile: None,
+            filter: CookieNameFilter::default(),
+            debug_port: None,
+            fast: false,
+            timeout: DEFAULT_READ_TIMEOUT,
+        }
+    }
+}
+
+/// 使用 CDP 协议读取浏览器 Cookie
 pub async fn read_chrome_cookies_cdp(
     domain: &str,
-    profile: Option<&str>,
+    options: &ReadCookieOptions,
 ) -> Result<Vec<Cookie>, CookieError> {
+    with_read_timeout(options.timeout, read_cookies(domain, options)).await
+}
+
+/// 读取 Cookie（不含超时控制）
+async fn read_cookies(
+    domain: &str,
+    options: &ReadCookieOptions,
+) -> Result<Vec<Cookie>, CookieError> {
+    let browser = options.browser;
+    let filter = &options.filter;
     let target_domain = extract_domain(domain);
-    let user_data_dir = get_chrome_user_data_dir()?;
-    let chrome_exe = find_chrome_executable()?;
-    let profile_name = profile.unwrap_or("Default");
-
-    // 配置浏览器
-    let config = BrowserConfig::builder()
-        .chrome_executable(chrome_exe)
-        .user_data_dir(&user_data_dir)
-        .arg(format!("--profile-directory={}", profile_name))
-        .arg("--headless=new")
-        .arg("--disable-gpu")
-        .arg("--no-first-run")
-        .arg("--disable-extensions")
-        .arg("--disable-logging")
-        .arg("--log-level=3")
-        .build()
-        .map_err(|e| CookieError::BrowserLaunchFailed(format!("配置错误: {}", e)))?;
-
-    // 启动浏览器
-    let (mut browser, mut handler) = Browser::launch(config)
-        .await
-        .map_err(|e| CookieError::BrowserLaunchFailed(format!("启动失败: {}", e)))?;
+    let user_data_dir = browser.user_data_dir()?;
+    let profile_name = options.profile.as_deref().unwrap_or("Default");
+
+    if options.fast {
+        let (dir, profile, domain, filter) = (
+            user_data_dir.clone(),
+            profile_name.to_string(),
+            target_domain.clone(),
+            filter.clone(),
+        );
+        let task = tokio::task::spawn_blocking(move || {
+            sqlite::read_cookies_from_db(browser, &dir, &profile, &domain, &filter)
+        });
+        let result = match task.await {
+            Ok(result) => result,
+            Err(e) => Err(CookieError::Other(format!(
+                "读取 Cookies 数据库失败: {}",
+                e
+            ))),
+        };
+        match result {
+            // 数据库读取成功但没有匹配的 Cookie 时，CDP 也读不到，无需回退
+            Ok(cookies) => return Ok(cookies),
+            Err(CookieError::NoCookies) => return Err(CookieError::NoCookies),
+            Err(e) => warn!("[Cookie] 快速读取失败，改用 CDP: {}", e),
+        }
+    }
 
-    // 处理浏览器事件
-    let handle = tokio::spawn(async move {
-        while handler.next().await.is_some() {}
-    });
+    // 用户数据目录被占用时无法再启动新实例，只能连接已运行浏览器的远程调试端口
+    // 此时读取的是已运行实例当前使用的配置文件，profile 参数不生效
+    let (cdp_browser, mut handler, launched) = if is_profile_locked(&user_data_dir) {
+        let Some(port) = options.debug_port else {
+            return Err(CookieError::ProfileLocked(browser));
+        };
+
+        info!(
+            "[Cookie] {} 正在运行，尝试通过调试端口 {} 连接",
+            browser.name(),
+            port
+        );
+        let (cdp_browser, handler) = CdpBrowser::connect(format!("http://127.0.0.1:{}", port))
+            .await
+            .map_err(|e| {
+                warn!("[Cookie] 连接调试端口 {} 失败: {}", port, e);
+                CookieError::ProfileLocked(browser)
+            })?;
+        (cdp_browser, handler, false)
+    } else {
+        // 配置浏览器
+        let config = BrowserConfig::builder()
+            .chrome_executable(browser.find_executable()?)
+            .user_data_dir(&user_data_dir)
+            .arg(format!("--profile-directory={}", profile_name))
+            .arg("--headless=new")
+            .arg("--disable-gpu")
+            .arg("--no-first-run")
+            .arg("--disable-extensions")
+            .arg("--disable-logging")
+            .arg("--log-level=3")
+            .build()
+            .map_err(|e| CookieError::BrowserLaunchFailed(format!("配置错误: {}", e)))?;
+
+        // 启动浏览器
+        let (cdp_browser, handler) = CdpBrowser::launch(config)
+            .await
+            .map_err(|e| CookieError::BrowserLaunchFailed(format!("启动失败: {}", e)))?;
+        (cdp_browser, handler, true)
+    };
 
-    // 获取所有 Cookie
+    // 处理浏览器事件
+    let handle = tokio::spawn(async move { while handler.next().await.is_some() {} });
+    let session = BrowserSession {
+        browser: Some(cdp_browser),
+        handler: handle,
+        launched,
+    };
+
+    // 获取所有 Cookie（出错提前返回时由 session 负责关闭浏览器）
     let params = GetCookiesParams::builder().build();
-    let result = browser
+    let result = session
+        .browser()
         .execute(params)
         .await
         .map_err(|e| CookieError::Other(format!("获取 Cookie 失败: {}", e)))?;
@@ -217,39 +635,168 @@ pub async fn read_chrome_cookies_cdp(
         all_cookies.iter().map(|c| c.domain.clone()).collect();
     info!("[Cookie] 所有 Cookie 域名: {:?}", domains);
 
-    // 过滤匹配域名的 Cookie
-    let mut cookies: Vec<Cookie> = all_cookies
+    let all_cookies: Vec<Cookie> = all_cookies
         .into_iter()
-        .filter(|c| {
-            let matched = domain_matches(&c.domain, &target_domain);
-            if matched {
-                info!("[Cookie] 匹配: {} (域名: {})", c.name, c.domain);
-            }
-            matched
-        })
         .map(|c| Cookie {
             name: c.name,
             value: c.value,
             domain: c.domain,
             path: c.path,
-            expires: if c.expires > 0.0 { Some(c.expires as i64) } else { None },
+            expires: if c.expires > 0.0 {
+                Some(c.expires as i64)
+            } else {
+                None
+            },
             is_secure: c.secure,
             is_http_only: c.http_only,
+            same_site: c.same_site.map(|s| s.as_ref().to_string()),
         })
         .collect();
 
-    // 关闭浏览器
-    let _ = browser.close().await;
-    handle.abort();
+    // 先关闭浏览器再过滤，没有匹配的 Cookie 时也不会残留进程
+    session.close().await;
 
-    if cookies.is_empty() {
-        return Err(CookieError::NoCookies);
+    // 过滤匹配域名（及指定名称）的 Cookie
+    let cookies = select_cookies(all_cookies, &target_domain, filter)?;
+    info!("读取到 {} 个 {} 的 Cookie", cookies.len(), target_domain);
+
+    Ok(cookies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cookie(name: &str, domain: &str) -> Cookie {
+        Cookie {
+            name: name.to_string(),
+            value: "v".to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires: None,
+            is_secure: false,
+            is_http_only: false,
+            same_site: None,
+        }
     }
 
-    info!("读取到 {} 个 {} 的 Cookie", cookies.len(), target_domain);
+    #[test]
+    fn test_user_data_candidate_per_platform() {
+        let path = Browser::Brave.user_data_candidate().unwrap();
+        #[cfg(windows)]
+        assert!(
+            path.ends_with("BraveSoftware/Brave-Browser/User Data"),
+            "{:?}",
+            path
+        );
+        #[cfg(any(target_os = "macos", target_os = "linux"))]
+        assert!(path.ends_with("BraveSoftware/Brave-Browser"), "{:?}", path);
+
+        #[cfg(target_os = "linux")]
+        assert!(Browser::Chrome
+            .user_data_candidate()
+            .unwrap()
+            .ends_with("google-chrome"));
+    }
 
-    // 按名称排序
-    cookies.sort_by(|a, b| a.name.cmp(&b.name));
+    #[test]
+    fn test_select_cookies_by_name_and_prefix() {
+        let cookies = || {
+            vec![
+                cookie("pt_pin", ".jd.com"),
+                cookie("pt_key", ".jd.com"),
+                cookie("thor", ".jd.com"),
+                cookie("pt_key", ".taobao.com"),
+            ]
+        };
+
+        let by_prefix = CookieNameFilter {
+            names: None,
+            prefix: Some("pt_".to_string()),
+        };
+        let names: Vec<_> = select_cookies(cookies(), "jd.com", &by_prefix)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names, ["pt_key", "pt_pin"]);
+
+        let by_name = CookieNameFilter {
+            names: Some(vec!["thor".to_string()]),
+            prefix: None,
+        };
+        let thor = select_cookies(cookies(), "jd.com", &by_name).unwrap();
+        assert_eq!(thor.len(), 1);
+
+        // 域名匹配但名称全部被过滤掉时仍返回 NoCookies
+        let none = CookieNameFilter {
+            names: Some(vec!["thor".to_string()]),
+            prefix: Some("pt_".to_string()),
+        };
+        assert!(matches!(
+            select_cookies(cookies(), "jd.com", &none),
+            Err(CookieError::NoCookies)
+        ));
+    }
 
-    Ok(cookies)
+    #[test]
+    fn test_domain_matches_rfc6265() {
+        assert!(domain_matches("jd.com", "jd.com"));
+        assert!(domain_matches(".jd.com", "jd.com"));
+        assert!(domain_matches(".JD.com", "drlives.jd.com"));
+        // 子域名的 Cookie 不会发送到父域名
+        assert!(!domain_matches("a.jd.com", "jd.com"));
+        assert!(!domain_matches("drlives.jd.com", "passport.jd.com"));
+    }
+
+    #[test]
+    fn test_domain_tree_matches_subdomains() {
+        assert!(domain_tree_matches("a.jd.com", "jd.com"));
+        assert!(domain_tree_matches(".jd.com", "drlives.jd.com"));
+        assert!(!domain_tree_matches("drlives.jd.com", "passport.jd.com"));
+        assert!(!domain_tree_matches("notjd.com", "jd.com"));
+        assert!(!domain_tree_matches("jd.com.cn", "jd.com"));
+        assert!(!domain_tree_matches(".com", "jd.com"));
+    }
+
+    #[test]
+    fn test_domain_matches_rejects_lookalikes() {
+        assert!(!domain_matches("jd.company.com", "jd.com"));
+        assert!(!domain_matches("notjd.com", "jd.com"));
+        assert!(!domain_matches("evil-jd.com", "jd.com"));
+        assert!(!domain_matches(".evil-jd.com", "jd.com"));
+        assert!(!domain_matches("jd.com.cn", "jd.com"));
+        assert!(!domain_matches("jd.com", "evil-jd.com"));
+    }
+
+    #[test]
+    fn test_domain_matches_rejects_bare_tld() {
+        assert!(!domain_matches(".com", "jd.com"));
+        assert!(!domain_matches("", "jd.com"));
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_drops_stuck_launch() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        // 模拟浏览器启动卡住：future 永不完成，持有的会话在超时后应被释放
+        struct Session(Arc<AtomicBool>);
+        impl Drop for Session {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let closed = Arc::new(AtomicBool::new(false));
+        let session = Session(closed.clone());
+        let stuck = async move {
+            let _session = session;
+            std::future::pending::<Result<Vec<Cookie>, CookieError>>().await
+        };
+
+        let result = with_read_timeout(Duration::from_millis(50), stuck).await;
+        assert!(matches!(result, Err(CookieError::Other(ref msg)) if msg == "读取超时"));
+        assert!(closed.load(Ordering::SeqCst));
+    }
 }
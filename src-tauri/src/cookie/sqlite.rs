@@ -0,0 +1,394 @@
+// Chromium Cookie 快速读取 - 直接读取配置文件下的 Cookies 数据库，不启动浏览器
+// 仅支持 Windows：encrypted_value 为 v10/v11 前缀 + AES-256-GCM，密钥保存在 Local State 中并经 DPAPI 保护
+use crate::cookie::reader::{domain_tree_matches, read_local_state, select_cookies};
+use crate::cookie::{Browser, Cookie, CookieError, CookieNameFilter};
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use log::info;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Local State 中 encrypted_key 的前缀（其后为 DPAPI 加密的密钥）
+const DPAPI_KEY_PREFIX: &[u8] = b"DPAPI";
+
+/// 可解密的 encrypted_value 前缀（v20 为应用绑定加密，无法在浏览器外解密）
+const ENCRYPTED_VALUE_PREFIXES: &[&[u8]] = &[b"v10", b"v11"];
+
+/// AES-GCM Nonce 长度
+const NONCE_LENGTH: usize = 12;
+
+/// 数据库版本 24 起明文前附加 32 字节的 host_key SHA256
+const HOST_HASH_DB_VERSION: i64 = 24;
+const HOST_HASH_LENGTH: usize = 32;
+
+/// Windows FILETIME 纪元（1601-01-01）与 Unix 纪元相差的秒数
+const WINDOWS_EPOCH_OFFSET_SECS: i64 = 11_644_473_600;
+
+/// 解密 DPAPI 保护的数据（与当前 Windows 用户绑定）
+#[cfg(windows)]
+fn dpapi_unprotect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    struct DataBlob {
+        len: u32,
+        data: *mut u8,
+    }
+
+    /// 不弹出任何界面
+    const CRYPTPROTECT_UI_FORBIDDEN: u32 = 0x1;
+
+    #[link(name = "crypt32")]
+    extern "system" {
+        fn CryptUnprotectData(
+            data_in: *const DataBlob,
+            description: *mut *mut u16,
+            entropy: *const DataBlob,
+            reserved: *const c_void,
+            prompt: *const c_void,
+            flags: u32,
+            data_out: *mut DataBlob,
+        ) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn LocalFree(mem: *mut c_void) -> *mut c_void;
+    }
+
+    let input = DataBlob {
+        len: data.len() as u32,
+        data: data.as_ptr() as *mut u8,
+    };
+    let mut output = DataBlob {
+        len: 0,
+        data: std::ptr::null_mut(),
+    };
+
+    // SAFETY: input 指向有效切片且调用期间不被修改；成功时 output 由系统分配，复制后用 LocalFree 释放
+    unsafe {
+        let ok = CryptUnprotectData(
+            &input,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null(),
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        );
+        if ok == 0 {
+            return Err(format!(
+                "DPAPI 解密失败: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let plaintext = std::slice::from_raw_parts(output.data, output.len as usize).to_vec();
+        LocalFree(output.data.cast());
+        Ok(plaintext)
+    }
+}
+
+#[cfg(not(windows))]
+fn dpapi_unprotect(_data: &[u8]) -> Result<Vec<u8>, String> {
+    Err("快速读取仅支持 Windows".to_string())
+}
+
+/// 读取 Local State 中经 DPAPI 保护的 Cookie 加密密钥
+fn read_master_key(user_data_dir: &Path) -> Result<Vec<u8>, String> {
+    let local_state = read_local_state(&user_data_dir.join("Local State"))
+        .map_err(|e| format!("Local State {}", e))?;
+    let encoded = local_state
+        .pointer("/os_crypt/encrypted_key")
+        .and_then(|v| v.as_str())
+        .ok_or("Local State 中没有 encrypted_key")?;
+    let encrypted = BASE64
+        .decode(encoded)
+        .map_err(|e| format!("encrypted_key 解码失败: {}", e))?;
+    let encrypted = encrypted
+        .strip_prefix(DPAPI_KEY_PREFIX)
+        .ok_or("encrypted_key 格式不支持")?;
+    dpapi_unprotect(encrypted)
+}
+
+/// 解密 encrypted_value（v10/v11 + Nonce + 密文 + 认证标签）
+fn decrypt_value(key: &[u8], encrypted: &[u8], strip_host_hash: bool) -> Result<String, String> {
+    let payload = ENCRYPTED_VALUE_PREFIXES
+        .iter()
+        .find_map(|prefix| encrypted.strip_prefix(*prefix))
+        .ok_or_else(|| {
+            let prefix = &encrypted[..encrypted.len().min(3)];
+            format!("不支持的加密格式: {}", String::from_utf8_lossy(prefix))
+        })?;
+    if payload.len() < NONCE_LENGTH {
+        return Err(format!("加密数据长度异常: {} 字节", encrypted.len()));
+    }
+
+    let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| "密钥长度无效".to_string())?;
+    let mut plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "解密失败".to_string())?;
+    if strip_host_hash {
+        plaintext.drain(..HOST_HASH_LENGTH.min(plaintext.len()));
+    }
+    String::from_utf8(plaintext).map_err(|_| "解密结果不是有效的 UTF-8".to_string())
+}
+
+/// Chromium 时间（1601 年起的微秒数）转换为 Unix 秒，0 表示会话 Cookie
+fn chrome_time_to_unix(value: i64) -> Option<i64> {
+    (value > 0).then(|| value / 1_000_000 - WINDOWS_EPOCH_OFFSET_SECS)
+}
+
+/// samesite 列转换为与 CDP 一致的名称（-1 表示未指定）
+fn same_site_name(value: i64) -> Option<String> {
+    match value {
+        0 => Some("None".to_string()),
+        1 => Some("Lax".to_string()),
+        2 => Some("Strict".to_string()),
+        _ => None,
+    }
+}
+
+/// Cookies 数据库路径（新版本在 Network 子目录下）
+fn cookies_db_path(user_data_dir: &Path, profile: &str) -> Option<PathBuf> {
+    let profile_dir = user_data_dir.join(profile);
+    [
+        profile_dir.join("Network").join("Cookies"),
+        profile_dir.join("Cookies"),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
+}
+
+/// 数据库中的一行 Cookie
+struct CookieRow {
+    host_key: String,
+    name: String,
+    value: String,
+    encrypted_value: Vec<u8>,
+    path: String,
+    expires_utc: i64,
+    is_secure: bool,
+    is_httponly: bool,
+    samesite: i64,
+}
+
+/// 临时数据库文件序号（并发读取时避免文件名冲突）
+static TEMP_DB_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// WAL 模式下与数据库同目录的附属文件，浏览器运行时最新写入可能尚未合并到主文件
+const SQLITE_SIDECAR_SUFFIXES: &[&str] = &["-wal", "-shm"];
+
+/// 复制数据库及存在的 -wal/-shm 附属文件到 dest（附属文件与 dest 同名加后缀）
+fn copy_database(src: &Path, dest: &Path) -> std::io::Result<()> {
+    std::fs::copy(src, dest)?;
+    for suffix in SQLITE_SIDECAR_SUFFIXES {
+        let sidecar = crate::utils::path_with_suffix(src, suffix);
+        if sidecar.exists() {
+            std::fs::copy(&sidecar, crate::utils::path_with_suffix(dest, suffix))?;
+        }
+    }
+    Ok(())
+}
+
+/// 删除临时数据库及其附属文件
+fn remove_database(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    for suffix in SQLITE_SIDECAR_SUFFIXES {
+        let _ = std::fs::remove_file(crate::utils::path_with_suffix(path, suffix));
+    }
+}
+
+/// 读取数据库版本和所有 Cookie 行
+fn read_cookie_table(db_path: &Path) -> rusqlite::Result<(Vec<CookieRow>, i64)> {
+    use rusqlite::{Connection, OpenFlags};
+
+    // 打开的是临时副本，以读写方式打开 SQLite 才能回放 -wal 中尚未合并的写入
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+    let version = conn
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .map(|v| v.parse().unwrap_or(0))
+        .unwrap_or(0);
+
+    let mut stmt = conn.prepare(
+        "SELECT host_key, name, value, encrypted_value, path, expires_utc, \
+         is_secure, is_httponly, samesite FROM cookies",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(CookieRow {
+                host_key: row.get(0)?,
+                name: row.get(1)?,
+                value: row.get(2)?,
+                encrypted_value: row.get(3)?,
+                path: row.get(4)?,
+                expires_utc: row.get(5)?,
+                is_secure: row.get(6)?,
+                is_httponly: row.get(7)?,
+                samesite: row.get(8)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok((rows, version))
+}
+
+/// 读取数据库中匹配域名的 Cookie 行和数据库版本
+/// 浏览器运行时数据库可能被锁定，先连同 -wal/-shm 复制到临时文件再读取
+fn query_cookie_rows(db_path: &Path, target_domain: &str) -> Result<(Vec<CookieRow>, i64), String> {
+    let temp_path = std::env::temp_dir().join(format!(
+        "jd-cookies-{}-{}.db",
+        std::process::id(),
+        TEMP_DB_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    if let Err(e) = copy_database(db_path, &temp_path) {
+        remove_database(&temp_path);
+        return Err(format!("复制 Cookies 数据库失败: {}", e));
+    }
+
+    let result = read_cookie_table(&temp_path);
+    remove_database(&temp_path);
+
+    let (mut rows, version) = result.map_err(|e| format!("读取 Cookies 数据库失败: {}", e))?;
+    rows.retain(|row| domain_tree_matches(&row.host_key, target_domain));
+    Ok((rows, version))
+}
+
+/// 直接读取 Cookies 数据库（阻塞操作，调用方需放到 spawn_blocking 中执行）
+/// 数据库不存在、密钥无法解密或遇到无法解密的 Cookie 时返回错误，由调用方回退到 CDP
+pub fn read_cookies_from_db(
+    browser: Browser,
+    user_data_dir: &Path,
+    profile: &str,
+    target_domain: &str,
+    filter: &CookieNameFilter,
+) -> Result<Vec<Cookie>, CookieError> {
+    let db_path = cookies_db_path(user_data_dir, profile).ok_or_else(|| {
+        CookieError::Other(format!(
+            "{} 配置文件 {} 没有 Cookies 数据库",
+            browser.name(),
+            profile
+        ))
+    })?;
+    let (rows, version) = query_cookie_rows(&db_path, target_domain).map_err(CookieError::Other)?;
+
+    // 只有存在加密值时才需要解密密钥
+    let key = if rows.iter().any(|r| !r.encrypted_value.is_empty()) {
+        Some(read_master_key(user_data_dir).map_err(CookieError::Other)?)
+    } else {
+        None
+    };
+
+    let strip_host_hash = version >= HOST_HASH_DB_VERSION;
+    let cookies = rows
+        .into_iter()
+        .map(|row| {
+            let value = match &key {
+                Some(key) if !row.encrypted_value.is_empty() => {
+                    decrypt_value(key, &row.encrypted_value, strip_host_hash).map_err(|e| {
+                        CookieError::Other(format!("Cookie {} 解密失败: {}", row.name, e))
+                    })?
+                }
+                _ => row.value,
+            };
+            Ok(Cookie {
+                name: row.name,
+                value,
+                domain: row.host_key,
+                path: row.path,
+                expires: chrome_time_to_unix(row.expires_utc),
+                is_secure: row.is_secure,
+                is_http_only: row.is_httponly,
+                same_site: same_site_name(row.samesite),
+            })
+        })
+        .collect::<Result<Vec<_>, CookieError>>()?;
+
+    info!(
+        "[Cookie] 快速读取 {} 数据库（版本 {}），{} 的 Cookie {} 个",
+        browser.name(),
+        version,
+        target_domain,
+        cookies.len()
+    );
+    select_cookies(cookies, target_domain, filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypt(key: &[u8], prefix: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let cipher = Aes256Gcm::new_from_slice(key).unwrap();
+        let nonce = [7u8; NONCE_LENGTH];
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .unwrap();
+        [prefix, &nonce, &ciphertext].concat()
+    }
+
+    #[test]
+    fn test_decrypt_value_formats() {
+        let key = [1u8; 32];
+
+        let v10 = encrypt(&key, b"v10", b"abc");
+        assert_eq!(decrypt_value(&key, &v10, false).unwrap(), "abc");
+
+        // 新版数据库在明文前附加 host_key 哈希
+        let hashed = [[9u8; HOST_HASH_LENGTH].as_slice(), b"abc"].concat();
+        let v11 = encrypt(&key, b"v11", &hashed);
+        assert_eq!(decrypt_value(&key, &v11, true).unwrap(), "abc");
+
+        let v20 = encrypt(&key, b"v20", b"abc");
+        assert!(decrypt_value(&key, &v20, false)
+            .unwrap_err()
+            .contains("v20"));
+        assert!(decrypt_value(&[2u8; 32], &v10, false).is_err());
+    }
+
+    #[test]
+    fn test_chrome_time_to_unix() {
+        assert_eq!(chrome_time_to_unix(0), None);
+        // 2024-01-01 00:00:00 UTC
+        assert_eq!(
+            chrome_time_to_unix(13_348_540_800_000_000),
+            Some(1_704_067_200)
+        );
+    }
+
+    #[test]
+    fn test_query_cookie_rows_reads_wal() {
+        use rusqlite::Connection;
+
+        let dir = std::env::temp_dir().join(format!("jd-cookies-wal-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("Cookies");
+
+        // 保持连接不关闭，写入只存在于 -wal 中（模拟浏览器运行时）
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA wal_autocheckpoint = 0;
+             CREATE TABLE meta (key TEXT, value TEXT);
+             INSERT INTO meta VALUES ('version', '24');
+             CREATE TABLE cookies (host_key TEXT, name TEXT, value TEXT, encrypted_value BLOB,
+                 path TEXT, expires_utc INTEGER, is_secure INTEGER, is_httponly INTEGER,
+                 samesite INTEGER);
+             INSERT INTO cookies VALUES ('.jd.com', 'pt_key', 'v', x'', '/', 0, 1, 1, -1);",
+        )
+        .unwrap();
+        assert!(crate::utils::path_with_suffix(&db_path, "-wal").exists());
+
+        let (rows, version) = query_cookie_rows(&db_path, "jd.com").unwrap();
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(version, 24);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].name, "pt_key");
+    }
+}
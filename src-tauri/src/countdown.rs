@@ -0,0 +1,213 @@
+//! 投屏倒计时计时源模块
+//!
+//! 倒计时窗口被 OBS 捕获或页面卡顿时，前端 JS 计时会漂移。计时改由后端按截止时间计算，
+//! 每秒推送 countdown-tick 事件，结束时推送 countdown-finished，前端只负责渲染。
+//! 倒计时按窗口 label 区分，多个窗口互不影响。
+
+use log::info;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::tasks::{TaskId, TaskManager};
+
+/// 倒计时任务类型
+const COUNTDOWN_TASK: &str = "countdown";
+
+/// 检查剩余时间的间隔（只在整秒变化时推送事件）
+const COUNTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 倒计时时钟
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CountdownClock {
+    /// 计时中，到截止时间结束
+    Running { deadline: Instant },
+    /// 已暂停，保存暂停时的剩余时间
+    Paused { remaining: Duration },
+}
+
+impl CountdownClock {
+    fn remaining(&self, now: Instant) -> Duration {
+        match *self {
+            CountdownClock::Running { deadline } => deadline.saturating_duration_since(now),
+            CountdownClock::Paused { remaining } => remaining,
+        }
+    }
+
+    fn pause(&mut self, now: Instant) {
+        *self = CountdownClock::Paused {
+            remaining: self.remaining(now),
+        };
+    }
+
+    fn resume(&mut self, now: Instant) {
+        if let CountdownClock::Paused { remaining } = *self {
+            *self = CountdownClock::Running {
+                deadline: now + remaining,
+            };
+        }
+    }
+}
+
+/// 剩余秒数（不足一秒按一秒计，与倒计时显示一致）
+fn remaining_secs(remaining: Duration) -> u64 {
+    remaining.as_millis().div_ceil(1000) as u64
+}
+
+/// 进行中的倒计时
+struct CountdownEntry {
+    task_id: TaskId,
+    clock: Arc<Mutex<CountdownClock>>,
+}
+
+/// 各窗口的倒计时（以窗口 label 为键）
+static COUNTDOWNS: LazyLock<Mutex<HashMap<String, CountdownEntry>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// countdown-tick / countdown-finished 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CountdownEvent {
+    pub label: String,
+    pub remaining_secs: u64,
+}
+
+/// 查找倒计时并修改时钟，返回修改后的剩余秒数
+fn update_clock(label: &str, update: impl FnOnce(&mut CountdownClock)) -> Result<u64, String> {
+    let countdowns = COUNTDOWNS.lock().unwrap();
+    let entry = countdowns
+        .get(label)
+        .ok_or_else(|| format!("倒计时不存在: {}", label))?;
+    let mut clock = entry.clock.lock().unwrap();
+    update(&mut clock);
+    Ok(remaining_secs(clock.remaining(Instant::now())))
+}
+
+/// 开始倒计时，返回任务 id（同一窗口已有倒计时时先停止旧的）
+/// 每秒推送 countdown-tick 事件，结束时推送 countdown-finished 事件
+#[tauri::command]
+pub fn start_countdown(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, TaskManager>,
+    label: String,
+    total_secs: u64,
+) -> TaskId {
+    let clock = Arc::new(Mutex::new(CountdownClock::Running {
+        deadline: Instant::now() + Duration::from_secs(total_secs),
+    }));
+
+    // 持锁到登记完成，避免任务立即结束时先于登记执行清理
+    let mut countdowns = COUNTDOWNS.lock().unwrap();
+
+    let task_clock = clock.clone();
+    let task_label = label.clone();
+    let description = format!("窗口 {} 倒计时 {} 秒", label, total_secs);
+    let task_id = manager.spawn_task(COUNTDOWN_TASK, description, |task_id, _| async move {
+        let mut ticker = tokio::time::interval(COUNTDOWN_POLL_INTERVAL);
+        let mut last_secs = None;
+
+        loop {
+            ticker.tick().await;
+
+            let remaining = task_clock.lock().unwrap().remaining(Instant::now());
+            let secs = remaining_secs(remaining);
+            if last_secs == Some(secs) {
+                continue;
+            }
+            last_secs = Some(secs);
+
+            let event = CountdownEvent {
+                label: task_label.clone(),
+                remaining_secs: secs,
+            };
+            if secs > 0 {
+                let _ = app.emit("countdown-tick", event);
+                continue;
+            }
+
+            let _ = app.emit("countdown-finished", event);
+            let mut countdowns = COUNTDOWNS.lock().unwrap();
+            if countdowns
+                .get(&task_label)
+                .is_some_and(|entry| entry.task_id == task_id)
+            {
+                countdowns.remove(&task_label);
+            }
+            info!("[倒计时] 窗口 {} 倒计时结束", task_label);
+            break;
+        }
+    });
+
+    let previous = countdowns.insert(label.clone(), CountdownEntry { task_id, clock });
+    drop(countdowns);
+    if let Some(previous) = previous {
+        manager.cancel(previous.task_id);
+    }
+    info!("[倒计时] 窗口 {} 开始倒计时 {} 秒", label, total_secs);
+
+    task_id
+}
+
+/// 暂停倒计时，返回剩余秒数
+#[tauri::command]
+pub fn pause_countdown(label: String) -> Result<u64, String> {
+    let secs = update_clock(&label, |clock| clock.pause(Instant::now()))?;
+    info!("[倒计时] 窗口 {} 暂停，剩余 {} 秒", label, secs);
+    Ok(secs)
+}
+
+/// 继续倒计时，返回剩余秒数
+#[tauri::command]
+pub fn resume_countdown(label: String) -> Result<u64, String> {
+    let secs = update_clock(&label, |clock| clock.resume(Instant::now()))?;
+    info!("[倒计时] 窗口 {} 继续，剩余 {} 秒", label, secs);
+    Ok(secs)
+}
+
+/// 停止倒计时（不推送 countdown-finished 事件）
+#[tauri::command]
+pub fn stop_countdown(manager: tauri::State<'_, TaskManager>, label: String) -> Result<(), String> {
+    let entry = COUNTDOWNS
+        .lock()
+        .unwrap()
+        .remove(&label)
+        .ok_or_else(|| format!("倒计时不存在: {}", label))?;
+    manager.cancel(entry.task_id);
+    info!("[倒计时] 窗口 {} 已停止", label);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_countdown_clock_pause_resume() {
+        let start = Instant::now();
+        let mut clock = CountdownClock::Running {
+            deadline: start + Duration::from_secs(10),
+        };
+        assert_eq!(
+            remaining_secs(clock.remaining(start + Duration::from_millis(2500))),
+            8
+        );
+
+        // 暂停期间剩余时间不变，继续后从暂停时的剩余时间接着计时
+        clock.pause(start + Duration::from_secs(3));
+        assert_eq!(
+            clock.remaining(start + Duration::from_secs(60)),
+            Duration::from_secs(7)
+        );
+        clock.resume(start + Duration::from_secs(60));
+        assert_eq!(
+            remaining_secs(clock.remaining(start + Duration::from_secs(66))),
+            1
+        );
+        assert_eq!(
+            remaining_secs(clock.remaining(start + Duration::from_secs(70))),
+            0
+        );
+    }
+}
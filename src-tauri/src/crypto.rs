@@ -7,8 +7,18 @@ use aes_gcm::{
     Aes256Gcm, Nonce,
 };
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::ChaCha20Poly1305;
+use log::debug;
 use rand::RngCore;
+use serde::Serialize;
 use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::utils;
 
 /// Nonce 长度（12 字节，GCM 推荐值）
 const NONCE_LENGTH: usize = 12;
@@ -37,6 +47,20 @@ pub enum CryptoError {
     DecryptionFailed,
     /// 加密失败
     EncryptionFailed,
+    /// 密钥格式错误（不是 64 个十六进制字符）
+    InvalidKeyFormat,
+    /// 未找到密钥环境变量
+    KeyNotFound,
+    /// 运行时密钥已注入，不能重复设置
+    KeyAlreadyInitialized,
+    /// 文件读写失败
+    Io(String),
+    /// 不是分块加密文件格式
+    InvalidFileFormat,
+    /// 指定序号的数据块校验失败（损坏、篡改或顺序错乱）
+    ChunkCorrupted(u64),
+    /// 文件不完整（缺少结束块或结束块后还有数据）
+    Truncated,
 }
 
 impl fmt::Display for CryptoError {
@@ -47,14 +71,64 @@ impl fmt::Display for CryptoError {
             CryptoError::InvalidCiphertext => write!(f, "密文格式错误：长度不足"),
             CryptoError::DecryptionFailed => write!(f, "解密失败：数据可能被篡改"),
             CryptoError::EncryptionFailed => write!(f, "加密失败"),
+            CryptoError::InvalidKeyFormat => write!(f, "密钥必须是 64 个十六进制字符"),
+            CryptoError::KeyNotFound => write!(f, "未找到密钥环境变量"),
+            CryptoError::KeyAlreadyInitialized => write!(f, "密钥已初始化，不能重复设置"),
+            CryptoError::Io(msg) => write!(f, "文件读写失败: {}", msg),
+            CryptoError::InvalidFileFormat => write!(f, "不是有效的加密文件"),
+            CryptoError::ChunkCorrupted(index) => {
+                write!(f, "第 {} 块数据校验失败：文件可能已损坏或被篡改", index + 1)
+            }
+            CryptoError::Truncated => write!(f, "加密文件不完整"),
         }
     }
 }
 
 impl std::error::Error for CryptoError {}
 
+/// 运行时注入的密钥，未注入时使用默认密钥 ENCRYPTION_KEY
+static RUNTIME_KEY: OnceLock<[u8; 32]> = OnceLock::new();
+
+/// 当前生效的密钥
+fn active_key() -> &'static [u8; 32] {
+    RUNTIME_KEY.get().unwrap_or(ENCRYPTION_KEY)
+}
+
+/// 解析 64 个十六进制字符的密钥
+fn parse_hex_key(hex: &str) -> Result<[u8; 32], CryptoError> {
+    let hex = hex.trim();
+    if hex.len() != 64 || !hex.is_ascii() {
+        return Err(CryptoError::InvalidKeyFormat);
+    }
+
+    let mut key = [0u8; 32];
+    for (i, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| CryptoError::InvalidKeyFormat)?;
+    }
+
+    Ok(key)
+}
+
+/// 从十六进制字符串注入运行时密钥（只能注入一次）
+/// 注意：注入后用默认密钥加密的数据（如已保存的 Cookie 文件）将无法解密
+pub fn init_key_from_hex(hex: &str) -> Result<(), CryptoError> {
+    let key = parse_hex_key(hex)?;
+    RUNTIME_KEY
+        .set(key)
+        .map_err(|_| CryptoError::KeyAlreadyInitialized)
+}
+
+/// 启动时读取的密钥环境变量
+pub const KEY_ENV_VAR: &str = "JD_LIVE_ENCRYPTION_KEY";
+
+/// 从环境变量读取十六进制密钥并注入
+pub fn init_key_from_env(var_name: &str) -> Result<(), CryptoError> {
+    let hex = std::env::var(var_name).map_err(|_| CryptoError::KeyNotFound)?;
+    init_key_from_hex(&hex)
+}
 
-/// AES-256-GCM 加密
+/// AES-256-GCM 加密（使用当前生效的密钥）
 ///
 /// 输入：明文字符串
 /// 输出：Base64(Nonce + Ciphertext + AuthTag)
@@ -64,7 +138,7 @@ impl std::error::Error for CryptoError {}
 /// let encrypted = encrypt("hello world").unwrap();
 /// ```
 pub fn encrypt(plaintext: &str) -> Result<String, CryptoError> {
-    encrypt_with_key(plaintext, ENCRYPTION_KEY)
+    encrypt_with_key(plaintext, active_key())
 }
 
 /// 使用指定密钥进行 AES-256-GCM 加密
@@ -91,7 +165,7 @@ pub fn encrypt_with_key(plaintext: &str, key: &[u8; 32]) -> Result<String, Crypt
     Ok(BASE64.encode(&combined))
 }
 
-/// AES-256-GCM 解密
+/// AES-256-GCM 解密（使用当前生效的密钥）
 ///
 /// 输入：Base64 编码的密文
 /// 输出：明文字符串
@@ -101,35 +175,309 @@ pub fn encrypt_with_key(plaintext: &str, key: &[u8; 32]) -> Result<String, Crypt
 /// let decrypted = decrypt(encrypted_text).unwrap();
 /// ```
 pub fn decrypt(ciphertext_b64: &str) -> Result<String, CryptoError> {
-    decrypt_with_key(ciphertext_b64, ENCRYPTION_KEY)
+    decrypt_with_key(ciphertext_b64, active_key())
 }
 
 /// 使用指定密钥进行 AES-256-GCM 解密
+/// 失败原因只输出 debug 日志（不含密钥），便于区分存储文件是被截断还是内容被破坏
 pub fn decrypt_with_key(ciphertext_b64: &str, key: &[u8; 32]) -> Result<String, CryptoError> {
     // Base64 解码
-    let combined = BASE64
-        .decode(ciphertext_b64)
-        .map_err(|_| CryptoError::Base64DecodeError)?;
+    let combined = BASE64.decode(ciphertext_b64).map_err(|e| {
+        debug!(
+            "[解密] Base64 解码失败（输入 {} 字符）: {}",
+            ciphertext_b64.len(),
+            e
+        );
+        CryptoError::Base64DecodeError
+    })?;
 
     // 检查最小长度（Nonce + AuthTag）
     let min_length = NONCE_LENGTH + AUTH_TAG_LENGTH;
     if combined.len() < min_length {
+        let region = if combined.len() < NONCE_LENGTH {
+            "Nonce"
+        } else {
+            "认证标签"
+        };
+        debug!(
+            "[解密] 密文整体长度异常: {} 字节，至少需要 {} 字节（Nonce {} + 认证标签 {}），{}区域被截断",
+            combined.len(),
+            min_length,
+            NONCE_LENGTH,
+            AUTH_TAG_LENGTH,
+            region
+        );
         return Err(CryptoError::InvalidCiphertext);
     }
 
     // 提取 Nonce 和密文
-    let nonce = Nonce::from_slice(&combined[..NONCE_LENGTH]);
-    let ciphertext = &combined[NONCE_LENGTH..];
+    let (nonce_bytes, ciphertext) = combined.split_at(NONCE_LENGTH);
+    debug!(
+        "[解密] 总长度 {} 字节，Nonce: {}，密文 {} 字节（含认证标签 {} 字节）",
+        combined.len(),
+        nonce_bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>(),
+        ciphertext.len(),
+        AUTH_TAG_LENGTH
+    );
+    let nonce = Nonce::from_slice(nonce_bytes);
 
     // 创建解密器
     let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::InvalidKeyLength)?;
 
     // 解密
-    let plaintext = cipher
-        .decrypt(nonce, ciphertext)
-        .map_err(|_| CryptoError::DecryptionFailed)?;
+    let plaintext = cipher.decrypt(nonce, ciphertext).map_err(|_| {
+        debug!("[解密] 认证标签校验失败：密钥不匹配，或 Nonce / 密文 / 认证标签被修改");
+        CryptoError::DecryptionFailed
+    })?;
+
+    String::from_utf8(plaintext).map_err(|e| {
+        debug!("[解密] 认证通过但明文不是合法 UTF-8: {}", e);
+        CryptoError::DecryptionFailed
+    })
+}
+
+impl From<std::io::Error> for CryptoError {
+    fn from(e: std::io::Error) -> Self {
+        CryptoError::Io(e.to_string())
+    }
+}
+
+// ============ 分块文件加密 ============
+//
+// 文件格式：MAGIC + 若干数据块
+// 数据块：标志(1 字节) + 明文长度(u32 LE) + Nonce(12 字节) + 密文(含 AuthTag)
+// 每块使用独立 Nonce，附加数据（AAD）为块序号和标志，
+// 块被替换、调换顺序或结束标志被篡改时都会校验失败；缺少结束块时判定为不完整
+
+/// 分块加密文件头
+const FILE_MAGIC: &[u8; 8] = b"JDLVENC1";
+
+/// 每块明文大小（64KB）
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 普通数据块标志
+const CHUNK_FLAG_DATA: u8 = 0;
+
+/// 结束块标志（最后一块，空文件时为长度 0 的块）
+const CHUNK_FLAG_FINAL: u8 = 1;
+
+/// 数据块附加数据：块序号 + 标志
+fn chunk_aad(index: u64, flag: u8) -> [u8; 9] {
+    let mut aad = [0u8; 9];
+    aad[..8].copy_from_slice(&index.to_le_bytes());
+    aad[8] = flag;
+    aad
+}
 
-    String::from_utf8(plaintext).map_err(|_| CryptoError::DecryptionFailed)
+/// 尽量读满缓冲区，返回实际读取的字节数（小于缓冲区长度表示已到文件末尾）
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// 分块加密文件（使用当前生效的密钥），适用于较大的文件，不会一次性读入内存
+pub fn encrypt_file(src: &Path, dst: &Path) -> Result<(), CryptoError> {
+    encrypt_file_with_key(src, dst, active_key())
+}
+
+/// 使用指定密钥分块加密文件
+pub fn encrypt_file_with_key(src: &Path, dst: &Path, key: &[u8; 32]) -> Result<(), CryptoError> {
+    use aes_gcm::aead::Payload;
+
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::InvalidKeyLength)?;
+    let mut reader = BufReader::new(File::open(src)?);
+    let mut writer = BufWriter::new(File::create(dst)?);
+    writer.write_all(FILE_MAGIC)?;
+
+    let mut buf = vec![0u8; FILE_CHUNK_SIZE];
+    let mut index = 0u64;
+    loop {
+        let len = read_full(&mut reader, &mut buf)?;
+        let is_final = len < FILE_CHUNK_SIZE || reader.fill_buf()?.is_empty();
+        let flag = if is_final {
+            CHUNK_FLAG_FINAL
+        } else {
+            CHUNK_FLAG_DATA
+        };
+
+        let mut nonce_bytes = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let aad = chunk_aad(index, flag);
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                Payload {
+                    msg: &buf[..len],
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| CryptoError::EncryptionFailed)?;
+
+        writer.write_all(&[flag])?;
+        writer.write_all(&(len as u32).to_le_bytes())?;
+        writer.write_all(&nonce_bytes)?;
+        writer.write_all(&ciphertext)?;
+
+        if is_final {
+            break;
+        }
+        index += 1;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// 解密 encrypt_file 生成的文件（使用当前生效的密钥）
+pub fn decrypt_file(src: &Path, dst: &Path) -> Result<(), CryptoError> {
+    decrypt_file_with_key(src, dst, active_key())
+}
+
+/// 使用指定密钥解密分块加密文件
+///
+/// 先解密到同目录的临时文件，全部块认证通过后再替换 dst；
+/// 失败时删除临时文件，dst 不会留下未经认证的部分明文
+pub fn decrypt_file_with_key(src: &Path, dst: &Path, key: &[u8; 32]) -> Result<(), CryptoError> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| CryptoError::InvalidKeyLength)?;
+    let mut reader = BufReader::new(File::open(src)?);
+
+    let mut magic = [0u8; 8];
+    if read_full(&mut reader, &mut magic)? < magic.len() || &magic != FILE_MAGIC {
+        return Err(CryptoError::InvalidFileFormat);
+    }
+
+    let tmp_path = utils::path_with_suffix(dst, ".tmp");
+    let result = File::create(&tmp_path)
+        .map_err(CryptoError::from)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            decrypt_chunks(&cipher, &mut reader, &mut writer)?;
+            writer
+                .into_inner()
+                .map_err(|e| e.into_error())?
+                .sync_all()?;
+            Ok(())
+        })
+        .and_then(|_| std::fs::rename(&tmp_path, dst).map_err(CryptoError::from));
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// 逐块解密并写入 writer（写入的每块明文均已通过认证）
+fn decrypt_chunks(
+    cipher: &Aes256Gcm,
+    reader: &mut BufReader<File>,
+    writer: &mut impl Write,
+) -> Result<(), CryptoError> {
+    use aes_gcm::aead::Payload;
+
+    let mut buf = vec![0u8; FILE_CHUNK_SIZE + AUTH_TAG_LENGTH];
+    let mut index = 0u64;
+    loop {
+        // 块头：标志 + 明文长度 + Nonce
+        let mut header = [0u8; 1 + 4 + NONCE_LENGTH];
+        if read_full(reader, &mut header)? < header.len() {
+            return Err(CryptoError::Truncated);
+        }
+        let flag = header[0];
+        let len = u32::from_le_bytes([header[1], header[2], header[3], header[4]]) as usize;
+        if flag > CHUNK_FLAG_FINAL || len > FILE_CHUNK_SIZE {
+            return Err(CryptoError::ChunkCorrupted(index));
+        }
+
+        let ciphertext = &mut buf[..len + AUTH_TAG_LENGTH];
+        if read_full(reader, ciphertext)? < ciphertext.len() {
+            return Err(CryptoError::Truncated);
+        }
+
+        let aad = chunk_aad(index, flag);
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&header[5..]),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| CryptoError::ChunkCorrupted(index))?;
+        writer.write_all(&plaintext)?;
+
+        if flag == CHUNK_FLAG_FINAL {
+            break;
+        }
+        index += 1;
+    }
+
+    // 结束块之后不应再有数据
+    if !reader.fill_buf()?.is_empty() {
+        return Err(CryptoError::Truncated);
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// 加密吞吐量测试结果（MB/s）
+#[derive(Debug, Serialize)]
+pub struct CryptoBenchmark {
+    pub aes_mb_per_sec: f64,
+    pub chacha_mb_per_sec: f64,
+}
+
+/// 根据数据量和耗时计算吞吐量（MB/s）
+fn throughput_mb_per_sec(sample_size: usize, iterations: u32, elapsed: Duration) -> f64 {
+    let total_mb = sample_size as f64 * iterations as f64 / (1024.0 * 1024.0);
+    total_mb / elapsed.as_secs_f64().max(f64::EPSILON)
+}
+
+/// 对比 AES-256-GCM 与 ChaCha20-Poly1305 的加密吞吐量
+///
+/// 仅用于计时，固定 Nonce 且丢弃密文，不可用于实际加密
+pub fn benchmark(sample_size: usize, iterations: u32) -> Result<CryptoBenchmark, CryptoError> {
+    let mut data = vec![0u8; sample_size];
+    rand::thread_rng().fill_bytes(&mut data);
+    let nonce_bytes = [0u8; NONCE_LENGTH];
+
+    let aes = Aes256Gcm::new_from_slice(active_key()).map_err(|_| CryptoError::InvalidKeyLength)?;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let ciphertext = aes
+            .encrypt(Nonce::from_slice(&nonce_bytes), data.as_slice())
+            .map_err(|_| CryptoError::EncryptionFailed)?;
+        std::hint::black_box(ciphertext);
+    }
+    let aes_elapsed = start.elapsed();
+
+    let chacha = ChaCha20Poly1305::new_from_slice(active_key())
+        .map_err(|_| CryptoError::InvalidKeyLength)?;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let ciphertext = chacha
+            .encrypt(
+                chacha20poly1305::Nonce::from_slice(&nonce_bytes),
+                data.as_slice(),
+            )
+            .map_err(|_| CryptoError::EncryptionFailed)?;
+        std::hint::black_box(ciphertext);
+    }
+    let chacha_elapsed = start.elapsed();
+
+    Ok(CryptoBenchmark {
+        aes_mb_per_sec: throughput_mb_per_sec(sample_size, iterations, aes_elapsed),
+        chacha_mb_per_sec: throughput_mb_per_sec(sample_size, iterations, chacha_elapsed),
+    })
 }
 
 #[cfg(test)]
@@ -178,6 +526,25 @@ mod tests {
         assert!(matches!(result, Err(CryptoError::InvalidCiphertext)));
     }
 
+    /// 截断在认证标签区域 / 篡改 Nonce 测试
+    #[test]
+    fn test_decrypt_truncated_and_nonce_tampered() {
+        let bytes = BASE64.decode(encrypt("test").unwrap()).unwrap();
+
+        let truncated = BASE64.encode(&bytes[..NONCE_LENGTH + AUTH_TAG_LENGTH - 1]);
+        assert!(matches!(
+            decrypt(&truncated),
+            Err(CryptoError::InvalidCiphertext)
+        ));
+
+        let mut nonce_tampered = bytes.clone();
+        nonce_tampered[0] ^= 0xFF;
+        assert!(matches!(
+            decrypt(&BASE64.encode(&nonce_tampered)),
+            Err(CryptoError::DecryptionFailed)
+        ));
+    }
+
     /// 篡改密文测试
     #[test]
     fn test_decrypt_tampered() {
@@ -200,4 +567,87 @@ mod tests {
         let encrypted2 = encrypt(plaintext).unwrap();
         assert_ne!(encrypted1, encrypted2, "相同明文应产生不同密文");
     }
+
+    /// 十六进制密钥长度必须为 64
+    #[test]
+    fn test_parse_hex_key() {
+        let hex = "5ee88f388e79950a48e7f84f42676d5fa9701549844354427374f20cf1e35d63";
+        assert_eq!(&parse_hex_key(hex).unwrap(), ENCRYPTION_KEY);
+        assert!(matches!(
+            parse_hex_key(&hex[..62]),
+            Err(CryptoError::InvalidKeyFormat)
+        ));
+        assert!(matches!(
+            parse_hex_key(&"zz".repeat(32)),
+            Err(CryptoError::InvalidKeyFormat)
+        ));
+    }
+
+    /// 用其他密钥加密的数据无法用默认密钥解密
+    #[test]
+    fn test_decrypt_with_wrong_key() {
+        let other_key = parse_hex_key(&"ab".repeat(32)).unwrap();
+        let encrypted = encrypt_with_key("secret", &other_key).unwrap();
+
+        let result = decrypt_with_key(&encrypted, ENCRYPTION_KEY);
+        assert!(matches!(result, Err(CryptoError::DecryptionFailed)));
+        assert_eq!(decrypt_with_key(&encrypted, &other_key).unwrap(), "secret");
+    }
+
+    /// 分块文件加密往返（几 MB 随机数据，末块不满 64KB）
+    #[test]
+    fn test_encrypt_file_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("jd-crypto-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (plain, encrypted, decrypted) = (
+            dir.join("plain.bin"),
+            dir.join("plain.bin.enc"),
+            dir.join("decrypted.bin"),
+        );
+
+        let mut data = vec![0u8; 3 * 1024 * 1024 + 123];
+        rand::thread_rng().fill_bytes(&mut data);
+        std::fs::write(&plain, &data).unwrap();
+
+        encrypt_file(&plain, &encrypted).unwrap();
+        decrypt_file(&encrypted, &decrypted).unwrap();
+        assert_eq!(std::fs::read(&decrypted).unwrap(), data);
+
+        // 篡改第 2 块密文
+        let mut bytes = std::fs::read(&encrypted).unwrap();
+        let second_chunk =
+            FILE_MAGIC.len() + 1 + 4 + NONCE_LENGTH + FILE_CHUNK_SIZE + AUTH_TAG_LENGTH;
+        bytes[second_chunk + 40] ^= 0xFF;
+        std::fs::write(&encrypted, &bytes).unwrap();
+        assert!(matches!(
+            decrypt_file(&encrypted, &decrypted),
+            Err(CryptoError::ChunkCorrupted(1))
+        ));
+        // 解密失败不覆盖已有文件，也不残留临时文件
+        assert_eq!(std::fs::read(&decrypted).unwrap(), data);
+        assert!(!dir.join("decrypted.bin.tmp").exists());
+
+        // 截掉结束块
+        std::fs::write(&encrypted, &bytes[..second_chunk]).unwrap();
+        assert!(matches!(
+            decrypt_file(&encrypted, &decrypted),
+            Err(CryptoError::Truncated)
+        ));
+
+        // 空文件
+        std::fs::write(&plain, b"").unwrap();
+        encrypt_file(&plain, &encrypted).unwrap();
+        decrypt_file(&encrypted, &decrypted).unwrap();
+        assert!(std::fs::read(&decrypted).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// 吞吐量测试结果为正数
+    #[test]
+    fn test_benchmark() {
+        let result = benchmark(64 * 1024, 4).unwrap();
+        assert!(result.aes_mb_per_sec > 0.0);
+        assert!(result.chacha_mb_per_sec > 0.0);
+    }
 }
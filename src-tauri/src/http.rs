@@ -0,0 +1,277 @@
+//! 共享 HTTP 客户端模块
+//!
+//! 所有命令复用同一个 reqwest::Client，避免每次请求重建连接池和 TLS 配置。
+//! reqwest::Client 内部为 Arc，克隆开销很小，可在多线程间安全共享。
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use crate::config;
+
+/// 每个主机最多保留的空闲连接数
+const POOL_MAX_IDLE_PER_HOST: usize = 8;
+/// 空闲连接保留时长
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// TCP keep-alive 间隔
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+/// 建立连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// 默认请求超时（含读取响应体）
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// 支持的代理协议
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+
+/// 代理配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// 代理地址，如 http://127.0.0.1:7890、socks5://127.0.0.1:1080
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl ProxyConfig {
+    /// 校验地址并转换为 reqwest::Proxy
+    fn to_proxy(&self) -> Result<reqwest::Proxy, String> {
+        let url =
+            reqwest::Url::parse(self.url.trim()).map_err(|e| format!("代理地址无效: {}", e))?;
+        if !PROXY_SCHEMES.contains(&url.scheme()) {
+            return Err(format!(
+                "代理地址无效: 不支持的协议 {}（仅支持 http、https、socks5）",
+                url.scheme()
+            ));
+        }
+        if url.host_str().is_none() {
+            return Err("代理地址无效: 缺少主机名".to_string());
+        }
+
+        let mut proxy = reqwest::Proxy::all(url).map_err(|e| format!("代理地址无效: {}", e))?;
+        if let Some(username) = self.username.as_deref().filter(|u| !u.is_empty()) {
+            proxy = proxy.basic_auth(username, self.password.as_deref().unwrap_or_default());
+        }
+        Ok(proxy)
+    }
+}
+
+/// 默认 User-Agent（京东风控升级时统一在此修改）
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36";
+
+/// 默认 Accept
+const DEFAULT_ACCEPT: &str = "application/json, text/plain, */*";
+
+/// 所有京东请求共用的请求头配置（各接口的 Referer 等差异由调用方决定）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderProfile {
+    pub user_agent: String,
+    pub accept: String,
+}
+
+impl Default for HeaderProfile {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            accept: DEFAULT_ACCEPT.to_string(),
+        }
+    }
+}
+
+static HEADER_PROFILE: LazyLock<RwLock<HeaderProfile>> =
+    LazyLock::new(|| RwLock::new(HeaderProfile::default()));
+
+/// 当前请求头配置
+pub fn header_profile() -> HeaderProfile {
+    HEADER_PROFILE.read().unwrap().clone()
+}
+
+/// 当前 User-Agent
+pub fn user_agent() -> String {
+    HEADER_PROFILE.read().unwrap().user_agent.clone()
+}
+
+/// 覆盖 User-Agent（None 或空字符串恢复默认值）
+pub fn apply_user_agent(user_agent: Option<&str>) -> Result<(), String> {
+    let user_agent = match user_agent.map(str::trim) {
+        Some(ua) if !ua.is_empty() => {
+            reqwest::header::HeaderValue::from_str(ua)
+                .map_err(|_| "User-Agent 包含非法字符".to_string())?;
+            ua.to_string()
+        }
+        _ => DEFAULT_USER_AGENT.to_string(),
+    };
+
+    info!("[HTTP] User-Agent: {}", user_agent);
+    HEADER_PROFILE.write().unwrap().user_agent = user_agent;
+    Ok(())
+}
+
+/// 运行时覆盖 User-Agent 并保存到配置（None 或空字符串恢复默认值）
+#[tauri::command]
+pub fn set_user_agent(user_agent: Option<String>) -> Result<(), String> {
+    let user_agent = user_agent.filter(|ua| !ua.trim().is_empty());
+    apply_user_agent(user_agent.as_deref())?;
+    config::update(|config| config.user_agent = user_agent)
+}
+
+/// 当前共享客户端及其使用的代理、超时
+struct SharedClient {
+    client: reqwest::Client,
+    proxy: Option<ProxyConfig>,
+    timeout_secs: u64,
+}
+
+/// 客户端构建次数（用于确认复用）
+static BUILD_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+static SHARED_CLIENT: LazyLock<RwLock<SharedClient>> = LazyLock::new(|| {
+    let client = build_client(None, DEFAULT_TIMEOUT_SECS).expect("创建 HTTP 客户端失败");
+    RwLock::new(SharedClient {
+        client,
+        proxy: None,
+        timeout_secs: DEFAULT_TIMEOUT_SECS,
+    })
+});
+
+/// 按统一配置创建客户端
+fn build_client(proxy: Option<&ProxyConfig>, timeout_secs: u64) -> Result<reqwest::Client, String> {
+    if timeout_secs == 0 {
+        return Err("请求超时必须大于 0 秒".to_string());
+    }
+
+    let mut builder = reqwest::Client::builder()
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(Duration::from_secs(timeout_secs));
+
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy.to_proxy()?);
+    }
+
+    let client = builder
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+    BUILD_COUNT.fetch_add(1, Ordering::Relaxed);
+    Ok(client)
+}
+
+/// 获取共享客户端
+pub fn client() -> reqwest::Client {
+    SHARED_CLIENT.read().unwrap().client.clone()
+}
+
+/// 当前客户端使用的代理（None 表示未单独配置）
+pub fn current_proxy() -> Option<String> {
+    SHARED_CLIENT
+        .read()
+        .unwrap()
+        .proxy
+        .as_ref()
+        .map(|c| c.url.trim().to_string())
+}
+
+/// 按代理和超时配置重建共享客户端，已发出的请求不受影响
+/// 配置无效时返回错误并保留原客户端
+pub fn configure_client(config: Option<ProxyConfig>, timeout_secs: u64) -> Result<(), String> {
+    let config = config.filter(|c| !c.url.trim().is_empty());
+    let client = build_client(config.as_ref(), timeout_secs)?;
+
+    let mut shared = SHARED_CLIENT.write().unwrap();
+    shared.client = client;
+    shared.proxy = config;
+    shared.timeout_secs = timeout_secs;
+
+    info!(
+        "[HTTP] 客户端已重建，代理: {:?}，超时: {} 秒",
+        shared.proxy.as_ref().map(|c| c.url.trim()),
+        timeout_secs
+    );
+    Ok(())
+}
+
+/// 按代理配置重建共享客户端（保留当前超时）
+pub fn set_client_proxy(config: Option<ProxyConfig>) -> Result<(), String> {
+    let timeout_secs = SHARED_CLIENT.read().unwrap().timeout_secs;
+    configure_client(config, timeout_secs)
+}
+
+/// 重建共享客户端（如启用或更换代理后）
+pub fn rebuild_client(proxy: Option<String>) -> Result<(), String> {
+    set_client_proxy(proxy.map(|url| ProxyConfig {
+        url,
+        username: None,
+        password: None,
+    }))
+}
+
+/// 设置全局代理（None 清除代理），所有京东请求随之走代理，设置会保存到配置
+#[tauri::command]
+pub fn set_proxy(config: Option<ProxyConfig>) -> Result<(), String> {
+    let config = config.filter(|c| !c.url.trim().is_empty());
+    set_client_proxy(config.clone())?;
+    config::update(|app_config| app_config.proxy = config)
+}
+
+/// 重建 HTTP 客户端（proxy 为空时直连），代理会保存到配置
+#[tauri::command]
+pub fn rebuild_http_client(proxy: Option<String>) -> Result<(), String> {
+    let proxy = proxy.filter(|url| !url.trim().is_empty());
+    rebuild_client(proxy.clone())?;
+    config::update(|config| {
+        config.proxy = proxy.map(|url| ProxyConfig {
+            url,
+            username: None,
+            password: None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_is_reused() {
+        let _ = client();
+        let builds = BUILD_COUNT.load(Ordering::Relaxed);
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| std::thread::spawn(|| (0..10).for_each(|_| drop(client()))))
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(BUILD_COUNT.load(Ordering::Relaxed), builds);
+
+        rebuild_client(None).unwrap();
+        assert_eq!(BUILD_COUNT.load(Ordering::Relaxed), builds + 1);
+        let _ = client();
+        assert_eq!(BUILD_COUNT.load(Ordering::Relaxed), builds + 1);
+    }
+
+    #[test]
+    fn test_proxy_config_validation() {
+        let config = |url: &str| ProxyConfig {
+            url: url.to_string(),
+            username: Some("user".to_string()),
+            password: Some("pass".to_string()),
+        };
+
+        for url in [
+            "http://127.0.0.1:7890",
+            "https://proxy.example.com:8443",
+            "socks5://127.0.0.1:1080",
+        ] {
+            assert!(config(url).to_proxy().is_ok(), "{}", url);
+        }
+        for url in ["ftp://127.0.0.1:21", "127.0.0.1:7890", "not a url"] {
+            let err = config(url).to_proxy().unwrap_err();
+            assert!(err.starts_with("代理地址无效"), "{}: {}", url, err);
+        }
+    }
+}
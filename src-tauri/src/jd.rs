@@ -1,9 +1,24 @@
 //! 京东直播相关功能模块
 
-use log::info;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
-
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::Emitter;
+
+use crate::cache::TtlCache;
+use crate::config;
 use crate::cookie::Cookie;
+use crate::http;
+use crate::logging;
+use crate::screen;
+use crate::sign;
+use crate::tasks::{TaskId, TaskManager};
+use crate::utils;
 
 // ============ 通用响应结构 ============
 
@@ -24,12 +39,49 @@ pub struct JdAuthorInfo {
     pub pin: String,
 }
 
-/// 验证京东登录状态结果
+/// 主播信息原始响应（权限字段不固定，按原始 JSON 读取）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorInfoRawResponse {
+    pub success: bool,
+    pub code: i32,
+    pub error_msg: Option<String>,
+    pub author_info: Option<serde_json::Value>,
+}
+
+/// 账号身份
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorIdentity {
+    /// 商家（店铺账号）
+    Merchant,
+    /// 达人
+    Talent,
+    /// 接口未返回身份信息
+    Unknown,
+}
+
+/// 主播权限（京东未返回的权限为 None，前端应按「未知」处理，不直接禁用）
 #[derive(Debug, Serialize, Deserialize)]
+pub struct AuthorPermissions {
+    pub pin: Option<String>,
+    pub identity: AuthorIdentity,
+    /// 能否创建直播
+    pub can_create_live: Option<bool>,
+    /// 能否带货（添加商品到购物袋）
+    pub can_sell: Option<bool>,
+    /// 接口未返回任何权限字段，结果为降级后的默认值
+    pub degraded: bool,
+}
+
+/// 验证京东登录状态结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JdLoginResult {
     pub is_logged_in: bool,
     pub nickname: Option<String>,
     pub avatar: Option<String>,
+    /// 账号唯一标识（昵称可能重复或修改，关联账号时应使用 pin）
+    pub pin: Option<String>,
 }
 
 // ============ 直播间相关 ============
@@ -62,6 +114,17 @@ pub struct RecentUsedIndexData {
     pub live_list: Option<Vec<RecentLiveRoom>>,
 }
 
+/// 分页后的直播间列表
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentLiveRoomPage {
+    /// 过滤后的总数
+    pub total: usize,
+    pub page: u32,
+    pub page_size: u32,
+    pub items: Vec<RecentLiveRoom>,
+}
+
 /// 创建直播间请求
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -98,6 +161,98 @@ pub struct CreateLiveResponse {
     pub dd_msg: Option<String>,
 }
 
+/// 修改直播间请求（所有字段可选，只提交非 None 字段）
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateLiveRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index_image: Option<String>, // 封面图（4:3）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resize_index_image: Option<String>, // 封面图（2:1）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub square_index_image: Option<String>, // 封面图（1:1）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub portrait_index_image: Option<String>, // 封面图（3:4）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>, // 描述
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub welcome: Option<String>, // 欢迎语
+}
+
+/// 修改失败的字段
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RejectedField {
+    pub field: String,
+    pub reason: String,
+}
+
+/// 修改直播间结果（字段名与 UpdateLiveRequest 提交的 JSON 字段名一致）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateLiveResult {
+    pub updated_fields: Vec<String>,
+    /// 直播进行中被拒绝修改的字段
+    pub rejected_fields: Vec<RejectedField>,
+}
+
+/// 开播结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartLiveResult {
+    pub live_id: String,
+    /// 开播后查询到的直播间状态（查询详情失败时为空）
+    pub status: Option<i32>,
+    pub start_time: Option<String>,
+}
+
+/// 开播响应（实验性接口，字段按 live-create 响应的通用字段读取）
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartLiveResponse {
+    pub success: bool,
+    pub code: Option<i32>,
+    pub subcode: Option<i32>,
+    pub error_msg: Option<String>,
+}
+
+/// 下播响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndLiveResponse {
+    pub success: bool,
+    pub code: Option<i32>,
+    pub error_msg: Option<String>,
+}
+
+/// 直播间详情
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveDetail {
+    pub live_id: Option<i64>,
+    pub title: Option<String>,
+    pub status: Option<i32>,
+    pub index_image: Option<String>,          // 封面图（4:3）
+    pub resize_index_image: Option<String>,   // 封面图（2:1）
+    pub square_index_image: Option<String>,   // 封面图（1:1）
+    pub portrait_index_image: Option<String>, // 封面图（3:4）
+    pub screen: Option<i32>,                  // 横竖屏，0=竖屏
+    pub can_explain: Option<i32>,             // 是否可讲解，1=是
+    pub publish_time: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub sku_num: Option<i32>, // 购物袋商品数量
+}
+
+/// 直播间详情响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveDetailResponse {
+    pub success: bool,
+    pub code: Option<i32>,
+    pub error_msg: Option<String>,
+    pub data: Option<LiveDetail>,
+}
+
 // ============ 商品相关 ============
 
 /// 上传商品请求
@@ -108,14 +263,40 @@ pub struct UploadSkuRequest {
     pub sku_id: String,
 }
 
-/// 添加商品到购物袋请求
+/// 从购物袋删除商品请求
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveSkuRequest {
+    pub live_id: String,
+    pub sku_ids: Vec<String>,
+}
+
+/// 商品置顶请求
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSkuTopRequest {
+    pub live_id: String,
+    pub sku_id: String,
+    pub top: i32, // 1=置顶，0=取消置顶
+}
+
+/// 购物袋商品排序请求（sku_ids 为完整的新顺序）
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct AddSkuRequest {
+pub struct SortSkuRequest {
     pub live_id: String,
     pub sku_ids: Vec<String>,
 }
 
+/// 商品排序结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderSkuResult {
+    /// 实际提交的完整顺序
+    pub sku_ids: Vec<String>,
+    /// 排序列表中遗漏、按原顺序追加到末尾的商品
+    pub appended: Vec<String>,
+}
+
 /// 商品操作响应
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -137,7 +318,22 @@ pub struct LiveGeneralData {
     pub comment_count: Option<i64>,
     pub share_count: Option<i64>,
     pub order_count: Option<i64>,
+    /// 成交金额（GMV），返回前端前已由分换算为元，保留两位小数
     pub order_amount: Option<f64>,
+    /// order_amount 的单位（固定为 yuan）
+    #[serde(default)]
+    pub amount_unit: String,
+}
+
+/// 实时数据接口 orderAmount 的原始单位为分，统一换算后的单位
+const ORDER_AMOUNT_UNIT: &str = "yuan";
+
+impl LiveGeneralData {
+    /// 将接口返回的金额（分）换算为元
+    fn normalize_amount(&mut self) {
+        self.order_amount = self.order_amount.map(|fen| fen.round() / 100.0);
+        self.amount_unit = ORDER_AMOUNT_UNIT.to_string();
+    }
 }
 
 /// 实时数据响应
@@ -150,6 +346,68 @@ pub struct GeneralDataResponse {
     pub data: Option<LiveGeneralData>,
 }
 
+impl GeneralDataResponse {
+    /// 接口失败时返回错误信息，成功时金额换算为元
+    fn into_result(self) -> Result<LiveGeneralData, JdApiError> {
+        match self.data {
+            Some(mut data) if self.success => {
+                data.normalize_amount();
+                Ok(data)
+            }
+            _ => Err(JdApiError::business(
+                Some(self.code),
+                None,
+                self.error_msg,
+                "获取失败",
+            )),
+        }
+    }
+}
+
+// ============ 评论相关 ============
+
+/// 每次拉取的评论条数
+const COMMENT_PAGE_SIZE: u32 = 50;
+
+/// 直播评论
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveComment {
+    pub comment_id: Option<String>,
+    pub nick_name: Option<String>,
+    pub content: Option<String>,
+    pub create_time: Option<i64>, // 评论时间（Unix 毫秒）
+}
+
+/// 评论列表数据
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentListData {
+    pub list: Option<Vec<LiveComment>>,
+    pub last_id: Option<String>,
+}
+
+/// 评论列表响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentListResponse {
+    pub success: bool,
+    pub code: i32,
+    pub error_msg: Option<String>,
+    pub data: Option<CommentListData>,
+}
+
+/// 评论拉取结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LiveCommentPage {
+    /// 新评论（按时间先后，已去掉空评论和命中屏蔽词的评论）
+    pub comments: Vec<LiveComment>,
+    /// 下次增量拉取时传入的游标（没有新评论时与本次传入的相同）
+    pub last_id: Option<String>,
+    /// 命中屏蔽词被过滤的评论数
+    pub filtered_count: usize,
+}
+
 // ============ 讲解相关 ============
 
 /// 讲解操作请求
@@ -169,606 +427,3559 @@ pub struct ExplainResponse {
     pub error_msg: Option<String>,
 }
 
-// ============ H5 页面相关 ============
+/// 当前正在讲解的商品
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CurrentExplain {
+    pub sku_id: String,
+    /// 开始讲解时间（Unix 毫秒）
+    pub started_at: u64,
+    pub elapsed_ms: u64,
+}
 
-/// H5 页面响应
+/// 结束讲解结果
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct H5Response {
-    pub success: bool,
-    pub code: i32,
-    pub error_msg: Option<String>,
-    pub url: Option<String>,
+pub struct ExplainResult {
+    /// 本次讲解时长（没有对应的开始记录时为 0）
+    pub duration_secs: u64,
 }
 
-// ============ HTTP 客户端辅助函数 ============
-
-/// 将 Cookie 数组转换为请求头格式
-fn cookies_to_string(cookies: &[Cookie]) -> String {
-    cookies
-        .iter()
-        .map(|c| format!("{}={}", c.name, c.value))
-        .collect::<Vec<_>>()
-        .join("; ")
+/// 讲解状态跟踪（按直播间记录正在讲解的商品及开始时间）
+/// 仅保存在内存中，应用重启后清空
+#[derive(Default)]
+pub struct ExplainTracker {
+    active: Mutex<HashMap<String, (String, u64)>>,
 }
 
-/// 构建通用请求头
-fn build_headers(cookie_str: &str) -> reqwest::header::HeaderMap {
-    let mut headers = reqwest::header::HeaderMap::new();
-    if let Ok(value) = cookie_str.parse() {
-        headers.insert(reqwest::header::COOKIE, value);
+impl ExplainTracker {
+    /// 记录开始讲解（同一直播间已有讲解记录时覆盖，重新计时）
+    fn begin(&self, live_id: &str, sku_id: &str) {
+        let previous = self
+            .active
+            .lock()
+            .unwrap()
+            .insert(live_id.to_string(), (sku_id.to_string(), now_millis()));
+        if let Some((previous, _)) = previous {
+            warn!(
+                "[讲解] 直播间 {} 的讲解记录被覆盖: {} -> {}",
+                live_id, previous, sku_id
+            );
+        }
+    }
+
+    /// 记录结束讲解（只清除同一商品的讲解记录），返回讲解时长（秒）
+    fn finish(&self, live_id: &str, sku_id: &str) -> u64 {
+        let mut active = self.active.lock().unwrap();
+        match active.get(live_id) {
+            Some((current, started_at)) if current == sku_id => {
+                let duration = now_millis().saturating_sub(*started_at) / 1000;
+                active.remove(live_id);
+                duration
+            }
+            _ => 0,
+        }
+    }
+
+    /// 清除直播间的讲解记录（下播时调用）
+    fn clear(&self, live_id: &str) {
+        self.active.lock().unwrap().remove(live_id);
+    }
+
+    /// 商品是否正在某个直播间讲解
+    pub(crate) fn is_explaining(&self, sku_id: &str) -> bool {
+        self.active
+            .lock()
+            .unwrap()
+            .values()
+            .any(|(current, _)| current == sku_id)
+    }
+
+    /// 查询直播间当前讲解的商品
+    fn current(&self, live_id: &str) -> Option<CurrentExplain> {
+        let active = self.active.lock().unwrap();
+        let (sku_id, started_at) = active.get(live_id)?;
+        Some(CurrentExplain {
+            sku_id: sku_id.clone(),
+            started_at: *started_at,
+            elapsed_ms: now_millis().saturating_sub(*started_at),
+        })
     }
-    headers.insert(
-        reqwest::header::USER_AGENT,
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36"
-            .parse()
-            .unwrap(),
-    );
-    headers.insert(
-        reqwest::header::REFERER,
-        "https://drlives.jd.com/".parse().unwrap(),
-    );
-    headers.insert(
-        reqwest::header::ACCEPT,
-        "application/json, text/plain, */*".parse().unwrap(),
-    );
-    headers.insert(
-        reqwest::header::CONTENT_TYPE,
-        "application/json".parse().unwrap(),
-    );
-    headers
 }
 
-// ============ Tauri Commands ============
+/// 当前 Unix 时间戳（毫秒）
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
 
-/// 验证京东登录状态（通过后端发起请求）
-#[tauri::command]
-pub async fn verify_jd_login(cookies: Vec<Cookie>) -> Result<JdLoginResult, String> {
-    info!("[验证登录] 开始验证京东登录状态");
-    info!("[验证登录] 收到 {} 个 Cookie", cookies.len());
+// ============ 会话 Cookie ============
 
-    let cookie_str = cookies_to_string(&cookies);
-    info!("[验证登录] Cookie 字符串长度: {} 字符", cookie_str.len());
+/// 当前账号的 Cookie 缓存（登录或切换账号后由前端调用 set_active_cookies 设置）
+/// 轮询类命令的 *_cached 变体直接使用缓存，不必每次通过 IPC 传整份 Cookie 列表
+#[derive(Default)]
+pub struct ActiveCookies {
+    cookies: Mutex<Option<Arc<Vec<Cookie>>>>,
+    /// 过期提醒任务 id（切换 Cookie 时取消旧任务）
+    expiry_task: Mutex<Option<TaskId>>,
+}
 
-    let url = "https://drlives.jd.com/console/homePage/newGetAuthorInfo";
-    info!("[验证登录] 请求 URL: {}", url);
+impl ActiveCookies {
+    /// 当前缓存的 Cookie，未设置时返回未登录错误
+    fn get(&self) -> Result<Arc<Vec<Cookie>>, JdApiError> {
+        self.cookies
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| JdApiError {
+                kind: JdError::NotLoggedIn,
+                code: None,
+                subcode: None,
+                message: "未设置当前账号 Cookie，请先调用 set_active_cookies".to_string(),
+            })
+    }
+}
 
-    let client = reqwest::Client::new();
-    let headers = build_headers(&cookie_str);
+/// 距登录 Cookie 过期不足该时间（秒）时推送 cookies-expiring-soon 事件
+const COOKIE_EXPIRY_WARNING_SECS: i64 = 3600;
 
-    let response = client
-        .get(url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| {
-            let err_msg = format!("请求失败: {}", e);
-            info!("[验证登录] {}", err_msg);
-            err_msg
-        })?;
-
-    info!("[验证登录] 响应状态码: {}", response.status());
-
-    let response_text = response.text().await.map_err(|e| {
-        let err_msg = format!("读取响应失败: {}", e);
-        info!("[验证登录] {}", err_msg);
-        err_msg
-    })?;
+/// Cookie 过期提醒任务类型
+const COOKIE_EXPIRY_TASK: &str = "cookie_expiry_watch";
 
-    info!("[验证登录] 响应内容: {}", response_text);
+/// cookies-expiring-soon / cookies-expired 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieExpiryEvent {
+    /// 最早过期的登录 Cookie 的过期时间（Unix 秒）
+    pub expires_at: i64,
+    /// 剩余秒数（已过期时为 0）
+    pub remaining_secs: i64,
+}
 
-    let data: JdAuthorResponse = serde_json::from_str(&response_text).map_err(|e| {
-        let err_msg = format!("解析响应失败: {}", e);
-        info!("[验证登录] {}", err_msg);
-        err_msg
-    })?;
+/// 过期检查的下一步
+#[derive(Debug, PartialEq)]
+enum ExpiryStep {
+    /// 已过期
+    Expired,
+    /// 进入提醒阈值，附剩余秒数
+    ExpiringSoon(i64),
+    /// 继续等待指定秒数后再检查
+    Wait(i64),
+}
 
-    if data.success {
-        if let Some(author) = data.author_info {
-            info!("[验证登录] 登录成功，用户: {}", author.name);
-            return Ok(JdLoginResult {
-                is_logged_in: true,
-                nickname: Some(author.name),
-                avatar: Some(author.pic),
-            });
-        }
+/// 根据过期时间和当前时间决定下一步（已提醒过时等到过期再检查）
+fn next_expiry_step(expires_at: i64, now: i64, warned: bool) -> ExpiryStep {
+    let remaining = expires_at - now;
+    if remaining <= 0 {
+        ExpiryStep::Expired
+    } else if warned {
+        ExpiryStep::Wait(remaining)
+    } else if remaining <= COOKIE_EXPIRY_WARNING_SECS {
+        ExpiryStep::ExpiringSoon(remaining)
+    } else {
+        ExpiryStep::Wait(remaining - COOKIE_EXPIRY_WARNING_SECS)
     }
+}
 
-    info!("[验证登录] 未登录: {:?}", data.error_msg);
-    Ok(JdLoginResult {
-        is_logged_in: false,
-        nickname: None,
-        avatar: None,
+/// 启动过期提醒任务：临近过期推送 cookies-expiring-soon，过期后推送 cookies-expired 并结束
+fn spawn_cookie_expiry_watch(
+    app: tauri::AppHandle,
+    manager: &TaskManager,
+    expires_at: i64,
+) -> TaskId {
+    let description = format!("登录 Cookie 过期提醒（{}）", expires_at);
+    manager.spawn_task(COOKIE_EXPIRY_TASK, description, move |_, _| async move {
+        let mut warned = false;
+        loop {
+            let now = (now_millis() / 1000) as i64;
+            match next_expiry_step(expires_at, now, warned) {
+                ExpiryStep::Expired => {
+                    info!("[会话] 登录 Cookie 已过期");
+                    let event = CookieExpiryEvent {
+                        expires_at,
+                        remaining_secs: 0,
+                    };
+                    let _ = app.emit("cookies-expired", event);
+                    break;
+                }
+                ExpiryStep::ExpiringSoon(remaining_secs) => {
+                    info!("[会话] 登录 Cookie 将在 {} 秒后过期", remaining_secs);
+                    let event = CookieExpiryEvent {
+                        expires_at,
+                        remaining_secs,
+                    };
+                    let _ = app.emit("cookies-expiring-soon", event);
+                    warned = true;
+                }
+                ExpiryStep::Wait(secs) => {
+                    tokio::time::sleep(Duration::from_secs(secs as u64)).await;
+                }
+            }
+        }
     })
 }
 
-/// 获取最近使用的直播间列表
+/// 设置当前账号的 Cookie（传空列表时清除缓存）
+/// 登录 Cookie 带过期时间时启动过期提醒任务；均为会话 Cookie（无 expires）时不检查
 #[tauri::command]
-pub async fn get_recent_live_rooms(cookies: Vec<Cookie>) -> Result<Vec<RecentLiveRoom>, String> {
-    info!("[最近直播间] 开始获取最近使用的直播间");
+pub fn set_active_cookies(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ActiveCookies>,
+    manager: tauri::State<'_, TaskManager>,
+    cookies: Vec<Cookie>,
+) {
+    info!("[会话] 缓存当前账号 Cookie: {} 个", cookies.len());
 
-    let cookie_str = cookies_to_string(&cookies);
+    let mut expiry_task = state.expiry_task.lock().unwrap();
+    if let Some(id) = expiry_task.take() {
+        manager.cancel(id);
+    }
+    if let Some(expires_at) = crate::cookie::session_expires_at(&cookies) {
+        *expiry_task = Some(spawn_cookie_expiry_watch(app, &manager, expires_at));
+    }
 
-    // 使用新的 API 接口
-    let body_json = serde_json::json!({
-        "pageId": "jlive.jd.com",
-        "PRICE_COLOR_API_TAG": true
-    });
+    *state.cookies.lock().unwrap() = (!cookies.is_empty()).then(|| Arc::new(cookies));
+}
 
-    let url = format!(
-        "https://api.m.jd.com/live_pc_recentUsedIndex?appid=plat-live-operate&functionId=live_pc_recentUsedIndex&body={}",
-        urlencoding::encode(&body_json.to_string())
-    );
+// ============ H5 页面相关 ============
 
-    let client = reqwest::Client::new();
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("Cookie", cookie_str.parse().unwrap());
-    headers.insert("Referer", "https://jlive.jd.com/".parse().unwrap());
-    headers.insert(
-        "User-Agent",
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36"
-            .parse()
-            .unwrap(),
-    );
+/// H5 页面响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct H5Response {
+    pub success: bool,
+    pub code: i32,
+    pub error_msg: Option<String>,
+    pub url: Option<String>,
+}
 
-    let response = client
-        .post(&url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+// ============ 推流信息相关 ============
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+/// 推流信息（推流码为敏感信息，日志中只输出掩码）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamInfo {
+    /// 推流服务器地址（OBS 中的「服务器」）
+    pub push_url: String,
+    /// 推流码（OBS 中的「串流密钥」）
+    pub stream_key: String,
+    pub h5_url: String,
+}
 
-    info!("[最近直播间] 响应: {}", response_text);
+/// 推流信息接口返回的数据
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushInfo {
+    /// 完整推流地址，如 rtmp://push.jd.com/live/xxx?auth_key=yyy
+    pub push_url: Option<String>,
+    /// 推流码（部分直播间单独返回，否则从完整推流地址中拆分）
+    pub stream_key: Option<String>,
+}
 
-    let data: RecentUsedIndexResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+/// 推流信息响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushInfoResponse {
+    pub success: bool,
+    pub code: Option<i32>,
+    pub error_msg: Option<String>,
+    pub data: Option<PushInfo>,
+}
 
-    if data.success {
-        if let Some(d) = data.data {
-            return Ok(d.live_list.unwrap_or_default());
-        }
-    }
+// ============ 主播模板相关 ============
 
-    Err(data.error_msg.unwrap_or_else(|| "获取失败".to_string()))
+/// 主播在京东预设的欢迎语和话术模板
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AuthorTemplates {
+    pub welcomes: Vec<String>,
+    pub scripts: Vec<String>,
 }
 
+/// 主播模板响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorTemplatesResponse {
+    pub success: bool,
+    pub code: i32,
+    pub error_msg: Option<String>,
+    pub data: Option<AuthorTemplatesData>,
+}
 
-/// 构建创建直播间专用请求头
-fn build_create_live_headers(cookie_str: &str) -> reqwest::header::HeaderMap {
-    let mut headers = reqwest::header::HeaderMap::new();
-    if let Ok(value) = cookie_str.parse() {
-        headers.insert(reqwest::header::COOKIE, value);
-    }
-    headers.insert(
-        reqwest::header::USER_AGENT,
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36 Edg/134.0.0.0"
-            .parse()
-            .unwrap(),
-    );
-    headers.insert(
-        reqwest::header::REFERER,
-        "https://jlive.jd.com/".parse().unwrap(),
-    );
-    headers.insert(
-        reqwest::header::CONTENT_TYPE,
-        "application/json; charset=UTF-8".parse().unwrap(),
-    );
-    headers
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorTemplatesData {
+    pub welcome_list: Option<Vec<String>>,
+    pub script_list: Option<Vec<String>>,
 }
 
-/// 创建直播间
-#[tauri::command]
-pub async fn create_live_room(
-    cookies: Vec<Cookie>,
-    request: CreateLiveRequest,
-) -> Result<i64, String> {
-    info!("[创建直播间] 开始创建直播间: {}", request.title);
-    info!("[创建直播间] 发布时间: {}", request.publish_time);
+/// 主播模板缓存有效期
+const AUTHOR_TEMPLATE_TTL: Duration = Duration::from_secs(10 * 60);
 
-    let cookie_str = cookies_to_string(&cookies);
-    let url = "https://drlives.jd.com/live/live-create";
+/// 主播模板缓存（按账号 Cookie 区分）
+pub static AUTHOR_TEMPLATE_CACHE: TtlCache<AuthorTemplates> =
+    TtlCache::new("author_templates", AUTHOR_TEMPLATE_TTL);
 
-    let client = reqwest::Client::new();
-    let headers = build_create_live_headers(&cookie_str);
+// ============ 图片地址规范化 ============
 
-    let response = client
-        .post(url)
-        .headers(headers)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+/// 京东图片服务器（补全相对路径用）
+const JD_IMAGE_HOST: &str = "https://img30.360buyimg.com";
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+/// 规范化京东返回的图片地址：协议相对地址（//img...）补 https:，相对路径补全图片域名
+pub fn normalize_image_url(raw: &str) -> String {
+    let url = raw.trim();
+    if url.is_empty() || url.contains("://") || url.starts_with("data:") {
+        return url.to_string();
+    }
+    if let Some(rest) = url.strip_prefix("//") {
+        return format!("https://{}", rest);
+    }
+    format!("{}/{}", JD_IMAGE_HOST, url.trim_start_matches('/'))
+}
 
-    info!("[创建直播间] 响应: {}", response_text);
+/// 规范化可选的图片地址字段
+fn normalize_image_field(url: &mut Option<String>) {
+    if let Some(url) = url {
+        *url = normalize_image_url(url);
+    }
+}
 
-    let data: CreateLiveResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
-
-    if data.success {
-        if let Some(live_id) = data.live_id {
-            info!("[创建直播间] 创建成功，直播间 ID: {}", live_id);
-            return Ok(live_id);
-        }
+impl RecentLiveRoom {
+    fn normalize_image_urls(&mut self) {
+        normalize_image_field(&mut self.cover_url);
     }
-
-    Err(data.error_msg.unwrap_or_else(|| "创建失败".to_string()))
 }
 
-/// 上传商品到直播间
-#[tauri::command]
-pub async fn upload_sku(
-    cookies: Vec<Cookie>,
-    live_id: String,
-    sku_id: String,
-) -> Result<(), String> {
-    info!("[上传商品] 直播间: {}, 商品: {}", live_id, sku_id);
+impl LiveDetail {
+    fn normalize_image_urls(&mut self) {
+        normalize_image_field(&mut self.index_image);
+        normalize_image_field(&mut self.resize_index_image);
+        normalize_image_field(&mut self.square_index_image);
+        normalize_image_field(&mut self.portrait_index_image);
+    }
+}
 
-    let cookie_str = cookies_to_string(&cookies);
-    let url = "https://drlives.jd.com/live-shopping-bag/sku/uploadSku";
+// ============ HTTP 客户端辅助函数 ============
 
-    let client = reqwest::Client::new();
-    let headers = build_headers(&cookie_str);
+/// Cookie 名称和值是否可以安全拼接到 Cookie 请求头
+/// 分号会截断后续 Cookie，换行等控制字符会导致请求头无效
+fn is_valid_cookie_pair(cookie: &Cookie) -> bool {
+    let valid_char = |c: char| c != ';' && !c.is_control();
+    !cookie.name.is_empty()
+        && cookie
+            .name
+            .chars()
+            .all(|c| valid_char(c) && c != '=' && !c.is_whitespace())
+        && cookie.value.chars().all(valid_char)
+}
 
-    let request = UploadSkuRequest { live_id, sku_id };
+/// 将 Cookie 数组转换为请求头格式，跳过含非法字符的 Cookie
+fn cookies_to_string(cookies: &[Cookie]) -> String {
+    let (valid, invalid): (Vec<&Cookie>, Vec<&Cookie>) =
+        cookies.iter().partition(|c| is_valid_cookie_pair(c));
+    if !invalid.is_empty() {
+        let names: Vec<&str> = invalid.iter().map(|c| c.name.as_str()).collect();
+        warn!("[Cookie] 跳过含非法字符的 Cookie: {:?}", names);
+    }
 
-    let response = client
-        .post(url)
-        .headers(headers)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+    valid
+        .iter()
+        .map(|c| format!("{}={}", c.name, c.value))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+/// 生成 Cookie 请求头，无法作为请求头时返回错误而不是静默丢弃
+fn cookie_header_value(cookie_str: &str) -> Result<reqwest::header::HeaderValue, String> {
+    cookie_str
+        .parse()
+        .map_err(|e| format!("Cookie 请求头无效: {}", e))
+}
 
-    info!("[上传商品] 响应: {}", response_text);
+/// 生成 Cookie 指纹，用作按账号区分的缓存 key
+fn cookie_cache_key(cookies: &[Cookie]) -> String {
+    let mut hasher = DefaultHasher::new();
+    cookies_to_string(cookies).hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
 
-    let data: SkuOperationResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+/// 各类接口请求头的差异部分（User-Agent、Accept 统一取自 http::HeaderProfile）
+struct HeaderSpec {
+    referer: &'static str,
+    content_type: Option<&'static str>,
+    host: Option<&'static str>,
+}
 
-    if data.success {
-        return Ok(());
+/// drlives.jd.com 通用接口
+const DRLIVES_HEADERS: HeaderSpec = HeaderSpec {
+    referer: "https://drlives.jd.com/",
+    content_type: Some("application/json"),
+    host: None,
+};
+
+/// 创建直播间等 jlive 后台接口
+const CREATE_LIVE_HEADERS: HeaderSpec = HeaderSpec {
+    referer: "https://jlive.jd.com/",
+    content_type: Some("application/json; charset=UTF-8"),
+    host: None,
+};
+
+/// api.m.jd.com 网关
+const API_HEADERS: HeaderSpec = HeaderSpec {
+    referer: "https://jlive.jd.com/",
+    content_type: None,
+    host: None,
+};
+
+/// api.m.jd.com 表单接口
+const FORM_HEADERS: HeaderSpec = HeaderSpec {
+    content_type: Some("application/x-www-form-urlencoded"),
+    ..API_HEADERS
+};
+
+/// 文件上传（Content-Type 由 multipart 自动生成）
+const UPLOAD_HEADERS: HeaderSpec = HeaderSpec {
+    host: Some("drlives.jd.com"),
+    ..API_HEADERS
+};
+
+/// 按接口类型构建请求头
+fn headers_for(spec: &HeaderSpec, cookie_str: &str) -> Result<reqwest::header::HeaderMap, String> {
+    use reqwest::header::{self, HeaderValue};
+
+    let profile = http::header_profile();
+    let mut headers = header::HeaderMap::new();
+    headers.insert(header::COOKIE, cookie_header_value(cookie_str)?);
+    headers.insert(
+        header::USER_AGENT,
+        HeaderValue::from_str(&profile.user_agent)
+            .map_err(|e| format!("User-Agent 无效: {}", e))?,
+    );
+    headers.insert(
+        header::ACCEPT,
+        HeaderValue::from_str(&profile.accept).map_err(|e| format!("Accept 无效: {}", e))?,
+    );
+    headers.insert(header::REFERER, HeaderValue::from_static(spec.referer));
+    if let Some(content_type) = spec.content_type {
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
     }
-
-    Err(data.error_msg.unwrap_or_else(|| "上传失败".to_string()))
+    if let Some(host) = spec.host {
+        headers.insert(header::HOST, HeaderValue::from_static(host));
+    }
+    Ok(headers)
 }
 
-/// 添加商品到购物袋
-#[tauri::command]
-pub async fn add_sku_to_bag(
-    cookies: Vec<Cookie>,
-    live_id: String,
-    sku_ids: Vec<String>,
-) -> Result<(), String> {
-    info!("[添加商品] 直播间: {}, 商品数量: {}", live_id, sku_ids.len());
+/// 构建通用请求头
+fn build_headers(cookie_str: &str) -> Result<reqwest::header::HeaderMap, String> {
+    headers_for(&DRLIVES_HEADERS, cookie_str)
+}
 
-    let cookie_str = cookies_to_string(&cookies);
-    let url = "https://drlives.jd.com/live-shopping-bag/sku/add";
+/// 构建创建直播间专用请求头
+fn build_create_live_headers(cookie_str: &str) -> Result<reqwest::header::HeaderMap, String> {
+    headers_for(&CREATE_LIVE_HEADERS, cookie_str)
+}
 
-    let client = reqwest::Client::new();
-    let headers = build_headers(&cookie_str);
+/// 构建 api.m.jd.com 网关请求头
+fn build_api_headers(cookie_str: &str) -> Result<reqwest::header::HeaderMap, String> {
+    headers_for(&API_HEADERS, cookie_str)
+}
 
-    let request = AddSkuRequest { live_id, sku_ids };
+/// 构建表单提交请求头（api.m.jd.com 表单接口）
+fn build_form_headers(cookie_str: &str) -> Result<reqwest::header::HeaderMap, String> {
+    headers_for(&FORM_HEADERS, cookie_str)
+}
 
-    let response = client
-        .post(url)
-        .headers(headers)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+/// 构建文件上传请求头（Content-Type 由 multipart 自动生成）
+fn build_upload_headers(cookie_str: &str) -> Result<reqwest::header::HeaderMap, String> {
+    headers_for(&UPLOAD_HEADERS, cookie_str)
+}
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+// ============ 响应解析 ============
 
-    info!("[添加商品] 响应: {}", response_text);
+/// 解析失败时错误信息中附带的响应字符数
+const PARSE_ERROR_SNIPPET_CHARS: usize = 500;
 
-    let data: SkuOperationResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+/// 调试模式：解析失败时返回完整响应（已脱敏），并允许使用 debug_jd_request
+static DEBUG_MODE: AtomicBool = AtomicBool::new(false);
 
-    if data.success {
-        return Ok(());
+/// 解析失败的错误信息：默认只附带响应长度，日志级别为 Full 时附带脱敏后的响应片段，调试模式附带脱敏后的完整响应
+fn parse_error_message(error: &serde_json::Error, response_text: &str) -> String {
+    if DEBUG_MODE.load(Ordering::Relaxed) {
+        return format!(
+            "解析响应失败: {}，完整响应: {}",
+            error,
+            logging::redact(response_text)
+        );
+    }
+    if !logging::full_response_log() {
+        return format!(
+            "解析响应失败: {}，响应长度: {} 字节",
+            error,
+            response_text.len()
+        );
     }
 
-    Err(data.error_msg.unwrap_or_else(|| "添加失败".to_string()))
+    format!(
+        "解析响应失败: {}，响应片段: {}",
+        error,
+        logging::redacted_snippet(response_text, PARSE_ERROR_SNIPPET_CHARS)
+    )
 }
 
-/// 获取直播实时数据
-#[tauri::command]
-pub async fn get_live_general_data(
-    cookies: Vec<Cookie>,
-    live_id: String,
-) -> Result<LiveGeneralData, String> {
-    info!("[实时数据] 获取直播间 {} 的实时数据", live_id);
-
-    let cookie_str = cookies_to_string(&cookies);
-    let url = format!(
-        "https://drlives.jd.com/liveRealTimeGeneralData/generalData?liveId={}",
-        live_id
-    );
-
-    let client = reqwest::Client::new();
-    let headers = build_headers(&cookie_str);
+/// 解析京东接口响应，失败时按日志级别在错误信息中附带响应内容便于排查
+fn parse_response<T: serde::de::DeserializeOwned>(response_text: &str) -> Result<T, String> {
+    serde_json::from_str(response_text).map_err(|e| parse_error_message(&e, response_text))
+}
 
-    let response = client
-        .get(&url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+/// 读取响应文本，按日志级别记录（默认只记录状态码和长度，完整响应会脱敏）
+/// 非 2xx 状态码直接返回错误，不再解析响应体（此时多为 HTML 错误页）
+async fn read_response_text(response: reqwest::Response, tag: &str) -> Result<String, JdApiError> {
+    let status = response.status();
+    if !status.is_success() {
+        warn!("[{}] 响应状态码异常: {}", tag, status);
+        return Err(JdApiError::http_status(status));
+    }
 
-    let response_text = response
+    let text = response
         .text()
         .await
         .map_err(|e| format!("读取响应失败: {}", e))?;
+    logging::log_response(tag, status.as_u16(), &text);
+    Ok(text)
+}
 
-    info!("[实时数据] 响应: {}", response_text);
+/// 开启或关闭调试模式
+#[tauri::command]
+pub fn set_debug_mode(enabled: bool) {
+    DEBUG_MODE.store(enabled, Ordering::Relaxed);
+    info!("[调试] 调试模式: {}", if enabled { "开启" } else { "关闭" });
+}
 
-    let data: GeneralDataResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+// ============ 业务错误分类 ============
+
+/// 京东接口业务错误分类，前端据此决定提示重新登录还是稍后重试
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JdError {
+    /// 未登录或登录已失效
+    NotLoggedIn,
+    /// 请求过于频繁
+    RateLimited,
+    /// 无权限访问
+    Forbidden,
+    /// 参数错误
+    InvalidParam,
+    /// 网络、超时或响应解析等请求本身的错误
+    Request,
+    /// 未归类的业务错误（附原始错误码）
+    Unknown(Option<i32>),
+}
 
-    if data.success {
-        if let Some(d) = data.data {
-            return Ok(d);
-        }
-    }
+/// 已知错误码与错误信息关键词（新发现的错误码在此补充）
+const JD_ERROR_RULES: &[(JdError, &[i32], &[&str])] = &[
+    (
+        JdError::NotLoggedIn,
+        &[3, 401],
+        &["未登录", "请登录", "请先登录", "登录失效", "登录已过期"],
+    ),
+    (
+        JdError::RateLimited,
+        &[429],
+        &["频繁", "限流", "稍后再试", "稍后重试"],
+    ),
+    (
+        JdError::Forbidden,
+        &[403],
+        &["无权", "没有权限", "权限不足"],
+    ),
+    (
+        JdError::InvalidParam,
+        &[400],
+        &["参数错误", "参数不合法", "参数异常"],
+    ),
+];
+
+/// 根据错误码和错误信息对京东业务错误分类（错误码优先，其次匹配错误信息关键词）
+pub fn classify(code: Option<i32>, subcode: Option<i32>, error_msg: Option<&str>) -> JdError {
+    let codes = [code, subcode];
+    JD_ERROR_RULES
+        .iter()
+        .find(|(_, rule_codes, _)| codes.iter().flatten().any(|c| rule_codes.contains(c)))
+        .or_else(|| {
+            let error_msg = error_msg?;
+            JD_ERROR_RULES
+                .iter()
+                .find(|(_, _, keywords)| keywords.iter().any(|k| error_msg.contains(k)))
+        })
+        .map(|(kind, _, _)| *kind)
+        .unwrap_or(JdError::Unknown(code))
+}
 
-    Err(data.error_msg.unwrap_or_else(|| "获取失败".to_string()))
+/// 命令失败时返回的结构化错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JdApiError {
+    pub kind: JdError,
+    pub code: Option<i32>,
+    pub subcode: Option<i32>,
+    /// 原始错误信息（京东未返回时为默认提示）
+    pub message: String,
 }
 
-/// 获取 H5 页面 URL
-#[tauri::command]
-pub async fn get_h5_url(cookies: Vec<Cookie>, live_id: String) -> Result<String, String> {
-    info!("[H5页面] 获取直播间 {} 的 H5 页面", live_id);
+impl JdApiError {
+    /// 由京东接口的业务失败响应生成错误
+    fn business(
+        code: Option<i32>,
+        subcode: Option<i32>,
+        error_msg: Option<String>,
+        fallback: &str,
+    ) -> Self {
+        Self {
+            kind: classify(code, subcode, error_msg.as_deref()),
+            code,
+            subcode,
+            message: error_msg
+                .filter(|m| !m.is_empty())
+                .unwrap_or_else(|| fallback.to_string()),
+        }
+    }
+}
 
-    let cookie_str = cookies_to_string(&cookies);
-    let url = format!("https://drlives.jd.com/h5?liveId={}", live_id);
+impl JdApiError {
+    /// 由非 2xx 的 HTTP 状态码生成错误（429 标记为限流）
+    fn http_status(status: reqwest::StatusCode) -> Self {
+        let (kind, message) = if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            (
+                JdError::RateLimited,
+                format!("请求过于频繁，已被京东限流（HTTP {}）", status.as_u16()),
+            )
+        } else {
+            (
+                JdError::Request,
+                format!("京东接口返回异常状态: HTTP {}", status),
+            )
+        };
+        Self {
+            kind,
+            code: None,
+            subcode: None,
+            message,
+        }
+    }
 
-    let client = reqwest::Client::new();
-    let headers = build_headers(&cookie_str);
+    /// 命令参数缺失或无效（未请求京东）
+    fn invalid_param(message: impl Into<String>) -> Self {
+        Self {
+            kind: JdError::InvalidParam,
+            code: None,
+            subcode: None,
+            message: message.into(),
+        }
+    }
+}
 
-    let response = client
-        .get(&url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+impl From<String> for JdApiError {
+    fn from(message: String) -> Self {
+        Self {
+            kind: JdError::Request,
+            code: None,
+            subcode: None,
+            message,
+        }
+    }
+}
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+/// 返回 String 错误的命令沿用错误信息
+impl From<JdApiError> for String {
+    fn from(error: JdApiError) -> Self {
+        error.message
+    }
+}
 
-    info!("[H5页面] 响应: {}", response_text);
+impl std::fmt::Display for JdApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
 
-    let data: H5Response =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+// ============ 超时与重试 ============
+
+/// 请求超时与重试配置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RequestConfig {
+    /// 单次请求超时（毫秒），默认取应用配置的请求超时
+    pub timeout_ms: u64,
+    /// 最大重试次数（不含首次请求）
+    pub max_retries: u32,
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    pub retry_delay_ms: u64,
+}
 
-    if data.success {
-        if let Some(url) = data.url {
-            return Ok(url);
+impl Default for RequestConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: config::current().request_timeout_secs.saturating_mul(1000),
+            max_retries: 2,
+            retry_delay_ms: 500,
         }
     }
+}
 
-    Err(data.error_msg.unwrap_or_else(|| "获取失败".to_string()))
+/// 第 attempt 次失败后的退避等待时间
+fn retry_backoff(config: &RequestConfig, attempt: u32) -> Duration {
+    let factor = 1u64.checked_shl(attempt - 1).unwrap_or(u64::MAX);
+    Duration::from_millis(config.retry_delay_ms.saturating_mul(factor))
 }
 
-/// 开始讲解商品
-#[tauri::command]
-pub async fn start_explain(
-    cookies: Vec<Cookie>,
-    live_id: String,
-    sku_id: String,
-) -> Result<(), String> {
-    info!("[开始讲解] 直播间: {}, 商品: {}", live_id, sku_id);
+/// 发送请求，对 5xx、连接失败和超时做指数退避重试，4xx 不重试
+/// 请求体无法复制（如文件流）时只发送一次
+async fn send_with_retry(
+    request: reqwest::RequestBuilder,
+    config: RequestConfig,
+) -> Result<reqwest::Response, String> {
+    let request = request.timeout(Duration::from_millis(config.timeout_ms));
+    let mut attempt = 1;
+
+    while attempt <= config.max_retries {
+        let Some(builder) = request.try_clone() else {
+            break;
+        };
+
+        match builder.send().await {
+            Ok(response) if !response.status().is_server_error() => return Ok(response),
+            Ok(response) => {
+                warn!(
+                    "[重试] 第 {} 次请求返回 {}，准备重试",
+                    attempt,
+                    response.status()
+                );
+            }
+            Err(e) if e.is_timeout() || e.is_connect() => {
+                warn!("[重试] 第 {} 次请求失败: {}，准备重试", attempt, e);
+            }
+            Err(e) => return Err(format!("请求失败（共尝试 {} 次）: {}", attempt, e)),
+        }
 
-    let cookie_str = cookies_to_string(&cookies);
+        tokio::time::sleep(retry_backoff(&config, attempt)).await;
+        attempt += 1;
+    }
 
-    // 构建请求体 JSON
-    let body_json = serde_json::json!({
-        "liveId": live_id,
-        "type": 0,
-        "skuId": sku_id,
-        "pageId": "jlive.jd.com",
-        "PRICE_COLOR_API_TAG": true
-    });
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("请求失败（共尝试 {} 次）: {}", attempt, e))?;
+
+    if response.status().is_server_error() {
+        return Err(format!(
+            "请求失败（共尝试 {} 次）: HTTP {}",
+            attempt,
+            response.status()
+        ));
+    }
 
-    let url = format!(
-        "https://api.m.jd.com/live_pc_explainBegin?appid=plat-live-operate&functionId=live_pc_explainBegin&body={}",
-        urlencoding::encode(&body_json.to_string())
-    );
+    Ok(response)
+}
 
-    let client = reqwest::Client::new();
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("Cookie", cookie_str.parse().unwrap());
-    headers.insert("Referer", "https://jlive.jd.com/".parse().unwrap());
-    headers.insert(
-        "User-Agent",
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36"
-            .parse()
-            .unwrap(),
-    );
+// ============ 接口配置 ============
 
-    let response = client
-        .post(&url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+/// 接口地址
+#[derive(Debug, Clone, Copy)]
+enum EndpointUrl {
+    /// 固定地址，{} 依次替换为 URL 编码后的运行时参数（如 liveId）
+    Direct(&'static str),
+    /// api.m.jd.com 网关接口（functionId），body 与 t、h5st 在请求时按 sign::gateway_url 生成
+    Gateway(&'static str),
+}
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+/// 京东接口配置（命令按此构建请求地址与请求头）
+struct EndpointSpec {
+    method: &'static str,
+    url: EndpointUrl,
+    build_headers: fn(&str) -> Result<reqwest::header::HeaderMap, String>,
+    /// 实验性接口：地址和参数按已抓包接口（live-create、sku/add 等）的命名规则推测，
+    /// 未经抓包核实，京东不存在该接口或参数不符时命令会直接返回错误
+    experimental: bool,
+}
 
-    info!("[开始讲解] 响应: {}", response_text);
+impl EndpointSpec {
+    /// 固定地址接口的请求地址
+    fn direct_url(&self, args: &[&str]) -> Result<String, String> {
+        match self.url {
+            EndpointUrl::Direct(template) => {
+                Ok(args.iter().fold(template.to_string(), |url, arg| {
+                    url.replacen("{}", &urlencoding::encode(arg), 1)
+                }))
+            }
+            EndpointUrl::Gateway(function_id) => Err(format!("接口 {} 为网关接口", function_id)),
+        }
+    }
 
-    let data: ExplainResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+    /// 网关接口的请求地址（有签名时附加 t、h5st）
+    fn gateway_url(&self, body: &serde_json::Value) -> Result<String, String> {
+        match self.url {
+            EndpointUrl::Gateway(function_id) => sign::gateway_url(function_id, body),
+            EndpointUrl::Direct(url) => Err(format!("接口 {} 不是网关接口", url)),
+        }
+    }
 
-    if data.success {
-        return Ok(());
+    /// 接口主机名
+    fn host(&self) -> &'static str {
+        match self.url {
+            EndpointUrl::Direct(url) => endpoint_host(url),
+            EndpointUrl::Gateway(_) => endpoint_host(sign::GATEWAY_URL),
+        }
     }
 
-    Err(data.error_msg.unwrap_or_else(|| "开始讲解失败".to_string()))
+    /// 按配置的请求方法和请求头构建请求
+    fn request(&self, url: &str, cookie_str: &str) -> Result<reqwest::RequestBuilder, String> {
+        let method = reqwest::Method::from_bytes(self.method.as_bytes())
+            .map_err(|e| format!("无效的请求方法 {}: {}", self.method, e))?;
+        let headers = (self.build_headers)(cookie_str)?;
+        Ok(http::client().request(method, url).headers(headers))
+    }
 }
 
-/// 结束讲解商品
-#[tauri::command]
-pub async fn end_explain(
-    cookies: Vec<Cookie>,
-    live_id: String,
-    sku_id: String,
-) -> Result<(), String> {
-    info!("[结束讲解] 直播间: {}, 商品: {}", live_id, sku_id);
+const AUTHOR_INFO_API: EndpointSpec = EndpointSpec {
+    method: "GET",
+    url: EndpointUrl::Direct("https://drlives.jd.com/console/homePage/newGetAuthorInfo"),
+    build_headers,
+    experimental: false,
+};
+
+const LIVE_DETAIL_API: EndpointSpec = EndpointSpec {
+    method: "GET",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live/live-detail?liveId={}"),
+    build_headers: build_create_live_headers,
+    experimental: true,
+};
+
+const RECENT_USED_INDEX_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Gateway("live_pc_recentUsedIndex"),
+    build_headers: build_api_headers,
+    experimental: false,
+};
+
+const LIVE_CREATE_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live/live-create"),
+    build_headers: build_create_live_headers,
+    experimental: false,
+};
+
+const LIVE_EDIT_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live/live-edit"),
+    build_headers: build_create_live_headers,
+    experimental: true,
+};
+
+const LIVE_START_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live/live-start"),
+    build_headers: build_create_live_headers,
+    experimental: true,
+};
+
+const LIVE_STOP_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live/live-stop"),
+    build_headers: build_create_live_headers,
+    experimental: true,
+};
+
+const AUTHOR_TEMPLATES_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Gateway("live_pc_getAuthorTemplates"),
+    build_headers: build_api_headers,
+    experimental: true,
+};
+
+const UPLOAD_SKU_FILE_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live-shopping-bag/sku/uploadSku"),
+    build_headers: build_upload_headers,
+    experimental: false,
+};
+
+const UPLOAD_SKU_JSON_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live-shopping-bag/sku/uploadSku"),
+    build_headers,
+    experimental: true,
+};
+
+const SKU_ADD_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live-shopping-bag/sku/add"),
+    build_headers: build_create_live_headers,
+    experimental: false,
+};
+
+const SKU_DELETE_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live-shopping-bag/sku/delete"),
+    build_headers,
+    experimental: true,
+};
+
+const SKU_TOP_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live-shopping-bag/sku/top"),
+    build_headers,
+    experimental: true,
+};
+
+const SKU_SORT_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live-shopping-bag/sku/sort"),
+    build_headers,
+    experimental: true,
+};
+
+const SKU_LIST_API: EndpointSpec = EndpointSpec {
+    method: "GET",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live-shopping-bag/sku/list?liveId={}"),
+    build_headers: build_create_live_headers,
+    experimental: true,
+};
+
+const GENERAL_DATA_API: EndpointSpec = EndpointSpec {
+    method: "GET",
+    url: EndpointUrl::Direct(
+        "https://drlives.jd.com/liveRealTimeGeneralData/generalData?liveId={}",
+    ),
+    build_headers,
+    experimental: false,
+};
+
+const H5_URL_API: EndpointSpec = EndpointSpec {
+    method: "GET",
+    url: EndpointUrl::Direct("https://drlives.jd.com/h5?liveId={}"),
+    build_headers,
+    experimental: false,
+};
+
+const PUSH_INFO_API: EndpointSpec = EndpointSpec {
+    method: "GET",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live/live-push-info?liveId={}"),
+    build_headers: build_create_live_headers,
+    experimental: true,
+};
+
+const COMMENT_LIST_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Gateway("live_pc_getCommentList"),
+    build_headers: build_api_headers,
+    experimental: true,
+};
+
+const EXPLAIN_BEGIN_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Gateway("live_pc_explainBegin"),
+    build_headers: build_api_headers,
+    experimental: false,
+};
+
+const EXPLAIN_END_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Gateway("live_pc_explainEnd"),
+    build_headers: build_api_headers,
+    experimental: false,
+};
+
+const UPLOAD_IMAGE_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://drlives.jd.com/live/uploadImage"),
+    build_headers: build_upload_headers,
+    experimental: true,
+};
+
+const COVER_IMAGES_API: EndpointSpec = EndpointSpec {
+    method: "POST",
+    url: EndpointUrl::Direct("https://api.m.jd.com/live_pc_recentUsedIndex?appid=plat-live-operate&functionId=live_pc_recentUsedIndex&PRICE_COLOR_API_TAG=true&use_color_api=true"),
+    build_headers: build_form_headers,
+    experimental: false,
+};
+
+/// 各命令请求的京东接口（以命令名标识，多个命令可共用同一接口）
+const ENDPOINTS: &[(&str, &EndpointSpec)] = &[
+    ("verify_jd_login", &AUTHOR_INFO_API),
+    ("get_author_permissions", &AUTHOR_INFO_API),
+    ("get_live_detail", &LIVE_DETAIL_API),
+    ("get_recent_live_rooms", &RECENT_USED_INDEX_API),
+    ("create_live_room", &LIVE_CREATE_API),
+    ("update_live_room", &LIVE_EDIT_API),
+    ("start_live", &LIVE_START_API),
+    ("end_live_room", &LIVE_STOP_API),
+    ("set_room_cover", &LIVE_EDIT_API),
+    ("get_author_templates", &AUTHOR_TEMPLATES_API),
+    ("upload_sku", &UPLOAD_SKU_FILE_API),
+    ("upload_sku_json", &UPLOAD_SKU_JSON_API),
+    ("add_sku_to_bag", &SKU_ADD_API),
+    ("remove_sku_from_bag", &SKU_DELETE_API),
+    ("set_sku_top", &SKU_TOP_API),
+    ("reorder_skus", &SKU_SORT_API),
+    ("get_current_explaining", &SKU_LIST_API),
+    ("add_sku_to_bag_batch", &SKU_ADD_API),
+    ("get_sku_info_by_file", &UPLOAD_SKU_FILE_API),
+    ("get_live_general_data", &GENERAL_DATA_API),
+    ("get_h5_url", &H5_URL_API),
+    ("get_stream_info", &PUSH_INFO_API),
+    ("get_live_comments", &COMMENT_LIST_API),
+    ("start_explain", &EXPLAIN_BEGIN_API),
+    ("end_explain", &EXPLAIN_END_API),
+    ("upload_cover_image", &UPLOAD_IMAGE_API),
+    ("get_cover_images", &COVER_IMAGES_API),
+];
+
+/// 查找命令对应的接口配置
+fn find_endpoint(name: &str) -> Result<&'static EndpointSpec, String> {
+    ENDPOINTS
+        .iter()
+        .find(|(command, _)| *command == name)
+        .map(|(_, spec)| *spec)
+        .ok_or_else(|| format!("未知接口: {}", name))
+}
 
-    let cookie_str = cookies_to_string(&cookies);
+/// 接口 URL 中的主机名
+fn endpoint_host(url: &str) -> &str {
+    url.trim_start_matches("https://")
+        .split(['/', '?'])
+        .next()
+        .unwrap_or_default()
+}
 
-    // 构建请求体 JSON
-    let body_json = serde_json::json!({
-        "liveId": live_id,
-        "type": 0,
-        "skuId": sku_id,
-        "pageId": "jlive.jd.com",
-        "PRICE_COLOR_API_TAG": true
-    });
+/// 请求配置描述
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequestConfigDescription {
+    pub url: String,
+    pub method: String,
+    pub headers: BTreeMap<String, String>,
+    /// 未传 config 时的请求超时（毫秒），取自应用配置的 request_timeout_secs
+    pub timeout_ms: u64,
+    /// 生效的代理（手动配置优先，其次为系统代理环境变量），None 表示直连
+    pub proxy: Option<String>,
+    /// 是否为未经抓包核实的实验性接口
+    pub experimental: bool,
+}
 
-    let url = format!(
-        "https://api.m.jd.com/live_pc_explainEnd?appid=plat-live-operate&functionId=live_pc_explainEnd&body={}",
-        urlencoding::encode(&body_json.to_string())
-    );
+/// 读取系统代理环境变量（reqwest 默认会使用）
+fn env_proxy_for(url: &str) -> Option<String> {
+    let vars: &[&str] = if url.starts_with("https://") {
+        &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+    } else {
+        &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+    };
 
-    let client = reqwest::Client::new();
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert("Cookie", cookie_str.parse().unwrap());
-    headers.insert("Referer", "https://jlive.jd.com/".parse().unwrap());
-    headers.insert(
-        "User-Agent",
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36"
-            .parse()
-            .unwrap(),
-    );
+    vars.iter()
+        .find_map(|name| std::env::var(name).ok())
+        .filter(|v| !v.is_empty())
+}
 
-    let response = client
-        .post(&url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+/// 查看指定命令实际使用的请求配置（不发送请求），便于排查问题
+/// Cookie 头以占位符代替，不会输出真实 Cookie；网关接口以空 body 生成地址（含当前签名）
+#[tauri::command]
+pub fn describe_request_config(endpoint: String) -> Result<RequestConfigDescription, String> {
+    let spec = find_endpoint(&endpoint)?;
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+    let url = match spec.url {
+        EndpointUrl::Direct(url) => url.to_string(),
+        EndpointUrl::Gateway(_) => spec.gateway_url(&serde_json::json!({}))?,
+    };
+    let headers = (spec.build_headers)("<cookies>")?
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                value.to_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+
+    Ok(RequestConfigDescription {
+        proxy: http::current_proxy().or_else(|| env_proxy_for(&url)),
+        url,
+        method: spec.method.to_string(),
+        headers,
+        timeout_ms: RequestConfig::default().timeout_ms,
+        experimental: spec.experimental,
+    })
+}
+
+// ============ 上传大小限制 ============
+
+/// 商品上传接口标识
+const UPLOAD_SKU_ENDPOINT: &str = "uploadSku";
+
+/// 封面图上传接口
+const UPLOAD_IMAGE_ENDPOINT: &str = "uploadImage";
+
+/// 各上传接口的默认大小限制（字节）
+const DEFAULT_UPLOAD_LIMITS: &[(&str, u64)] = &[
+    (UPLOAD_SKU_ENDPOINT, 5 * 1024 * 1024),
+    (UPLOAD_IMAGE_ENDPOINT, 5 * 1024 * 1024),
+];
+
+/// 当前生效的上传大小限制（可通过 set_upload_size_limit 调整）
+static UPLOAD_LIMITS: LazyLock<Mutex<HashMap<String, u64>>> = LazyLock::new(|| {
+    Mutex::new(
+        DEFAULT_UPLOAD_LIMITS
+            .iter()
+            .map(|(endpoint, limit)| (endpoint.to_string(), *limit))
+            .collect(),
+    )
+});
+
+/// 发送前检查上传内容大小，避免整个请求体发出后才被京东拒绝
+fn check_upload_size(endpoint: &str, size: usize) -> Result<(), String> {
+    let limit = UPLOAD_LIMITS.lock().unwrap().get(endpoint).copied();
+
+    if let Some(limit) = limit {
+        if size as u64 > limit {
+            return Err(format!(
+                "文件超出大小限制: {} 字节，上限 {} 字节",
+                size, limit
+            ));
+        }
+    }
 
-    info!("[结束讲解] 响应: {}", response_text);
+    Ok(())
+}
 
-    let data: ExplainResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+/// 设置指定上传接口的大小限制（字节）
+#[tauri::command]
+pub fn set_upload_size_limit(endpoint: String, max_bytes: u64) -> Result<(), String> {
+    let mut limits = UPLOAD_LIMITS.lock().unwrap();
 
-    if data.success {
-        return Ok(());
+    if !limits.contains_key(&endpoint) {
+        return Err(format!("未知上传接口: {}", endpoint));
     }
 
-    Err(data.error_msg.unwrap_or_else(|| "结束讲解失败".to_string()))
+    info!("[上传限制] {} 上限设置为 {} 字节", endpoint, max_bytes);
+    limits.insert(endpoint, max_bytes);
+    Ok(())
 }
 
-// ============ 封面图片相关 ============
+// ============ Cookie 覆盖检查 ============
 
-/// 封面图片项
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CoverImage {
-    pub four_to_three: Option<String>,
-    pub two_to_one: Option<String>,
-    pub one_to_one: Option<String>,
-    pub three_to_four: Option<String>,
+/// 判断 Cookie 是否会随请求发送到指定主机
+/// 以点开头的 Cookie 域名对其子域名生效，否则只对完全相同的主机生效
+fn cookie_applies_to_host(cookie_domain: &str, host: &str) -> bool {
+    let cookie_domain = cookie_domain.to_lowercase();
+    let host = host.to_lowercase();
+
+    match cookie_domain.strip_prefix('.') {
+        Some(parent) => host == parent || host.ends_with(&cookie_domain),
+        None => host == cookie_domain,
+    }
 }
 
-/// 封面图片响应
+/// Cookie 覆盖检查结果
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CoverImagesResponse {
-    pub success: bool,
-    pub code: i32,
-    pub error_msg: Option<String>,
-    pub data: Option<Vec<CoverImage>>,
+pub struct CookieCoverage {
+    pub operation: String,
+    pub required_domains: Vec<String>,
+    pub missing_domains: Vec<String>,
+    pub message: Option<String>,
 }
 
-/// 获取封面图片列表
+/// 检查 Cookie 是否覆盖指定操作所需的全部京东域名
+/// operation 为命令名，如 create_live_room
 #[tauri::command]
-pub async fn get_cover_images(cookies: Vec<Cookie>) -> Result<Vec<CoverImage>, String> {
-    info!("[封面图片] 开始获取封面图片列表");
+pub fn check_cookie_coverage(
+    cookies: Vec<Cookie>,
+    operation: String,
+) -> Result<CookieCoverage, String> {
+    let spec = find_endpoint(&operation)?;
+    let domains = [spec.host()];
 
-    let cookie_str = cookies_to_string(&cookies);
-    let url = "https://api.m.jd.com/live_pc_recentUsedIndex?appid=plat-live-operate&functionId=live_pc_recentUsedIndex&PRICE_COLOR_API_TAG=true&use_color_api=true";
+    let missing_domains: Vec<String> = domains
+        .iter()
+        .filter(|host| {
+            !cookies
+                .iter()
+                .any(|c| cookie_applies_to_host(&c.domain, host))
+        })
+        .map(|host| host.to_string())
+        .collect();
+
+    let message = if missing_domains.is_empty() {
+        None
+    } else {
+        Some(format!("缺少 {} 的 Cookie", missing_domains.join("、")))
+    };
 
-    let client = reqwest::Client::new();
-    let mut headers = reqwest::header::HeaderMap::new();
-    if let Ok(value) = cookie_str.parse() {
-        headers.insert(reqwest::header::COOKIE, value);
-    }
-    headers.insert(
-        reqwest::header::USER_AGENT,
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36"
-            .parse()
-            .unwrap(),
-    );
-    headers.insert(
-        reqwest::header::REFERER,
-        "https://jlive.jd.com/".parse().unwrap(),
-    );
-    headers.insert(
-        reqwest::header::CONTENT_TYPE,
-        "application/x-www-form-urlencoded".parse().unwrap(),
-    );
+    Ok(CookieCoverage {
+        operation,
+        required_domains: domains.iter().map(|d| d.to_string()).collect(),
+        missing_domains,
+        message,
+    })
+}
 
-    let body = "appid=plat-live-operate&functionId=live_pc_recentUsedIndex&body={}";
+// ============ Tauri Commands ============
 
-    let response = client
-        .post(url)
-        .headers(headers)
-        .body(body)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+/// 登录验证结果缓存有效期
+const LOGIN_VERIFY_TTL: Duration = Duration::from_secs(10);
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+/// 同时进行的登录验证请求数上限
+const LOGIN_VERIFY_CONCURRENCY: usize = 2;
 
-    info!("[封面图片] 响应: {}", response_text);
+/// 登录验证结果缓存（按登录会话 Cookie 区分），短时间内重复验证直接返回上次结果
+pub static LOGIN_VERIFY_CACHE: TtlCache<JdLoginResult> =
+    TtlCache::new("login_verify", LOGIN_VERIFY_TTL);
 
-    let data: CoverImagesResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+/// 限制登录验证并发，避免多个页面同时触发时集中请求京东接口
+static LOGIN_VERIFY_SEMAPHORE: LazyLock<tokio::sync::Semaphore> =
+    LazyLock::new(|| tokio::sync::Semaphore::new(LOGIN_VERIFY_CONCURRENCY));
 
-    if data.success {
-        return Ok(data.data.unwrap_or_default());
+/// 登录验证缓存 key：登录会话 Cookie（pt_key / thor）的哈希，没有时使用全部 Cookie 的指纹
+fn login_cache_key(cookies: &[Cookie]) -> String {
+    let session: Vec<&Cookie> = cookies
+        .iter()
+        .filter(|c| ["pt_key", "thor"].contains(&c.name.as_str()) && !c.value.is_empty())
+        .collect();
+    if session.is_empty() {
+        return cookie_cache_key(cookies);
     }
 
-    Err(data.error_msg.unwrap_or_else(|| "获取封面图片失败".to_string()))
+    let mut hasher = DefaultHasher::new();
+    for cookie in session {
+        (&cookie.name, &cookie.value).hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
 }
 
-// ============ 商品详情相关（购物袋功能）============
+/// 验证京东登录状态（通过后端发起请求）
+/// 10 秒内同一登录会话的重复验证直接返回缓存结果，force 为 true 时跳过缓存
+#[tauri::command]
+pub async fn verify_jd_login(
+    cookies: Vec<Cookie>,
+    config: Option<RequestConfig>,
+    force: Option<bool>,
+) -> Result<JdLoginResult, String> {
+    let cache_key = login_cache_key(&cookies);
+    let force = force.unwrap_or(false);
+    if !force {
+        if let Some(result) = LOGIN_VERIFY_CACHE.get(&cache_key) {
+            info!("[验证登录] 使用缓存结果");
+            return Ok(result);
+        }
+    }
 
-/// 商品详情（从京东接口返回的完整数据）
-#[derive(Debug, Serialize, Deserialize, Clone)]
+    let _permit = LOGIN_VERIFY_SEMAPHORE
+        .acquire()
+        .await
+        .map_err(|e| format!("验证登录失败: {}", e))?;
+
+    // 等待期间同一会话的其他请求可能已完成验证
+    if !force {
+        if let Some(result) = LOGIN_VERIFY_CACHE.get(&cache_key) {
+            info!("[验证登录] 使用缓存结果");
+            return Ok(result);
+        }
+    }
+
+    let result = request_jd_login(&cookies, config).await?;
+    LOGIN_VERIFY_CACHE.insert(cache_key, result.clone());
+    Ok(result)
+}
+
+/// 请求京东接口验证登录状态
+async fn request_jd_login(
+    cookies: &[Cookie],
+    config: Option<RequestConfig>,
+) -> Result<JdLoginResult, String> {
+    info!("[验证登录] 开始验证京东登录状态");
+    info!("[验证登录] 收到 {} 个 Cookie", cookies.len());
+
+    let cookie_str = cookies_to_string(cookies);
+    info!("[验证登录] Cookie 字符串长度: {} 字符", cookie_str.len());
+
+    let url = AUTHOR_INFO_API.direct_url(&[])?;
+    info!("[验证登录] 请求 URL: {}", url);
+
+    let builder = AUTHOR_INFO_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default())
+        .await
+        .inspect_err(|e| info!("[验证登录] {}", e))?;
+
+    let response_text = read_response_text(response, "验证登录")
+        .await
+        .inspect_err(|e| info!("[验证登录] {}", e))?;
+
+    let data: JdAuthorResponse =
+        parse_response(&response_text).inspect_err(|e| info!("[验证登录] {}", e))?;
+
+    if data.success {
+        if let Some(author) = data.author_info {
+            info!(
+                "[验证登录] 登录成功，用户: {}",
+                logging::mask_str(&author.name)
+            );
+            return Ok(JdLoginResult {
+                is_logged_in: true,
+                nickname: Some(author.name),
+                avatar: Some(author.pic),
+                pin: Some(author.pin).filter(|pin| !pin.is_empty()),
+            });
+        }
+    }
+
+    info!("[验证登录] 未登录: {:?}", data.error_msg);
+    Ok(JdLoginResult {
+        is_logged_in: false,
+        nickname: None,
+        avatar: None,
+        pin: None,
+    })
+}
+
+/// 创建直播权限字段（不同账号类型返回的字段名不同，按顺序取第一个存在的）
+const CAN_CREATE_LIVE_KEYS: &[&str] =
+    &["canCreateLive", "liveAuth", "hasLiveAuth", "livePermission"];
+
+/// 带货权限字段
+const CAN_SELL_KEYS: &[&str] = &["canSale", "goodsAuth", "hasGoodsAuth", "salePermission"];
+
+/// 身份类型字段
+const IDENTITY_KEYS: &[&str] = &["authorType", "userType", "identity"];
+
+/// 商家账号才有的店铺字段（身份字段缺失时据此判断）
+const MERCHANT_ID_KEYS: &[&str] = &["shopId", "venderId", "vendorId"];
+
+/// 读取权限标记（兼容 bool、数字和 "1"/"true" 字符串）
+fn permission_flag(info: &serde_json::Value, keys: &[&str]) -> Option<bool> {
+    use serde_json::Value;
+
+    keys.iter().find_map(|key| match info.get(*key)? {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => n.as_i64().map(|n| n != 0),
+        Value::String(s) => match s.trim().to_lowercase().as_str() {
+            "1" | "true" | "y" => Some(true),
+            "0" | "false" | "n" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// 判断账号身份：优先身份字段，缺失时有店铺 ID 视为商家
+fn author_identity(info: &serde_json::Value) -> AuthorIdentity {
+    let identity = IDENTITY_KEYS.iter().find_map(|key| match info.get(*key)? {
+        serde_json::Value::String(s) => Some(s.to_lowercase()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    });
+    match identity.as_deref() {
+        Some("merchant" | "shop" | "vender" | "1") => return AuthorIdentity::Merchant,
+        Some("talent" | "daren" | "author" | "2") => return AuthorIdentity::Talent,
+        _ => {}
+    }
+
+    let has_shop = MERCHANT_ID_KEYS.iter().any(|key| {
+        info.get(*key)
+            .is_some_and(|v| !v.is_null() && v.as_str() != Some("") && v.as_i64() != Some(0))
+    });
+    if has_shop {
+        AuthorIdentity::Merchant
+    } else {
+        AuthorIdentity::Unknown
+    }
+}
+
+/// 从主播信息中提取权限
+fn parse_author_permissions(info: &serde_json::Value) -> AuthorPermissions {
+    let can_create_live = permission_flag(info, CAN_CREATE_LIVE_KEYS);
+    let can_sell = permission_flag(info, CAN_SELL_KEYS);
+    let identity = author_identity(info);
+
+    AuthorPermissions {
+        pin: info
+            .get("pin")
+            .and_then(|v| v.as_str())
+            .filter(|pin| !pin.is_empty())
+            .map(str::to_string),
+        identity,
+        can_create_live,
+        can_sell,
+        degraded: can_create_live.is_none()
+            && can_sell.is_none()
+            && identity == AuthorIdentity::Unknown,
+    }
+}
+
+/// 由主播信息响应得到权限
+/// 业务失败按错误码和错误信息分类；成功但没有 authorInfo 时返回降级结果，不视为未登录
+fn author_permissions_from_response(
+    data: AuthorInfoRawResponse,
+) -> Result<AuthorPermissions, JdApiError> {
+    if !data.success {
+        return Err(JdApiError::business(
+            Some(data.code),
+            None,
+            data.error_msg,
+            "查询主播权限失败",
+        ));
+    }
+    Ok(parse_author_permissions(
+        data.author_info
+            .as_ref()
+            .unwrap_or(&serde_json::Value::Null),
+    ))
+}
+
+/// 查询账号身份和权限（能否创建直播、能否带货、商家还是达人）
+/// 接口返回业务失败时按错误码分类（未登录为 NotLoggedIn）；接口不返回权限字段时对应项为 None 并标记 degraded
+#[tauri::command]
+pub async fn get_author_permissions(
+    cookies: Vec<Cookie>,
+    config: Option<RequestConfig>,
+) -> Result<AuthorPermissions, JdApiError> {
+    info!("[主播权限] 开始查询");
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = AUTHOR_INFO_API.direct_url(&[])?;
+
+    let builder = AUTHOR_INFO_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "主播权限").await?;
+
+    let data: AuthorInfoRawResponse = parse_response(&response_text)?;
+
+    let permissions = author_permissions_from_response(data)?;
+    if permissions.degraded {
+        warn!("[主播权限] 接口未返回权限字段，按未知处理");
+    }
+    info!("[主播权限] {:?}", permissions);
+    Ok(permissions)
+}
+
+/// Cookie 预检查结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CookieCheckStatus {
+    /// 已登录
+    Valid,
+    /// 凭证无效或已过期，需要重新登录
+    Invalid,
+    /// 检查本身失败（网络错误等），无法判断凭证是否有效
+    CheckFailed,
+}
+
+/// Cookie 预检查结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CookieValidity {
+    pub status: CookieCheckStatus,
+    pub pin: Option<String>,
+    /// 登录会话 Cookie 最早的过期时间（Unix 秒）
+    pub expires_at: Option<i64>,
+    /// 距离过期的剩余秒数（估计值）
+    pub expires_in_secs: Option<i64>,
+    /// 检查失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 批量操作前预检查 Cookie 是否仍然有效
+/// 会话 Cookie 已过期时直接判定无效，不再请求京东
+#[tauri::command]
+pub async fn check_cookies_valid(
+    cookies: Vec<Cookie>,
+    config: Option<RequestConfig>,
+) -> Result<CookieValidity, String> {
+    let pin = crate::cookie::extract_pin(&cookies);
+    let expires_at = crate::cookie::session_expires_at(&cookies);
+    let expires_in_secs = expires_at.map(|exp| exp - (now_millis() / 1000) as i64);
+
+    let mut result = CookieValidity {
+        status: CookieCheckStatus::Invalid,
+        pin,
+        expires_at,
+        expires_in_secs,
+        error: None,
+    };
+
+    if expires_in_secs.is_some_and(|secs| secs <= 0) {
+        info!("[Cookie 检查] 登录会话已过期");
+        return Ok(result);
+    }
+
+    match verify_jd_login(cookies, config, None).await {
+        Ok(login) if login.is_logged_in => result.status = CookieCheckStatus::Valid,
+        Ok(_) => {}
+        Err(e) => {
+            warn!("[Cookie 检查] 检查失败: {}", e);
+            result.status = CookieCheckStatus::CheckFailed;
+            result.error = Some(e);
+        }
+    }
+
+    info!("[Cookie 检查] 结果: {:?}", result.status);
+    Ok(result)
+}
+
+/// 获取最近使用的直播间列表
+#[tauri::command]
+pub async fn get_recent_live_rooms(
+    cookies: Vec<Cookie>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    status_filter: Option<i32>,
+    config: Option<RequestConfig>,
+) -> Result<RecentLiveRoomPage, JdApiError> {
+    info!("[最近直播间] 开始获取最近使用的直播间");
+
+    let cookie_str = cookies_to_string(&cookies);
+
+    // 使用新的 API 接口
+    let body_json = serde_json::json!({
+        "pageId": "jlive.jd.com",
+        "PRICE_COLOR_API_TAG": true
+    });
+
+    let url = RECENT_USED_INDEX_API.gateway_url(&body_json)?;
+
+    let builder = RECENT_USED_INDEX_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "最近直播间").await?;
+
+    let data: RecentUsedIndexResponse = parse_response(&response_text)?;
+
+    if data.success {
+        if let Some(d) = data.data {
+            let mut rooms = d.live_list.unwrap_or_default();
+            rooms
+                .iter_mut()
+                .for_each(RecentLiveRoom::normalize_image_urls);
+            return Ok(paginate_live_rooms(rooms, page, page_size, status_filter));
+        }
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "获取失败",
+    ))
+}
+
+/// 获取 H5 页面 URL
+#[tauri::command]
+pub async fn get_h5_url(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<String, JdApiError> {
+    info!("[H5页面] 获取直播间 {} 的 H5 页面", live_id);
+
+    let cookie_str = cookies_to_string(&cookies);
+    fetch_h5_url(&cookie_str, &live_id, config.unwrap_or_default()).await
+}
+
+/// 京东推流信息接口在直播间未到可推流状态时返回的错误信息关键词
+const NOT_PUSHABLE_ERROR_KEYWORDS: &[&str] =
+    &["未到", "未开始", "审核", "已结束", "不可推流", "暂不支持"];
+
+/// 把完整推流地址拆分为服务器地址和推流码（最后一段路径及其参数为推流码）
+fn split_push_url(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let (server, key) = url.rsplit_once('/')?;
+    if key.is_empty() || !server.contains("://") || server.ends_with('/') {
+        return None;
+    }
+    Some((server.to_string(), key.to_string()))
+}
+
+/// 将推流信息接口的返回转换为服务器地址和推流码
+fn parse_push_info(live_id: &str, data: PushInfoResponse) -> Result<(String, String), JdApiError> {
+    let not_pushable = |error_msg: Option<String>| JdApiError {
+        message: format!(
+            "直播间 {} 暂不可推流，请确认直播间已审核通过且已到开播时间",
+            live_id
+        ),
+        ..JdApiError::business(data.code, None, error_msg, "")
+    };
+
+    if !data.success {
+        let error_msg = data.error_msg.clone().unwrap_or_default();
+        if NOT_PUSHABLE_ERROR_KEYWORDS
+            .iter()
+            .any(|keyword| error_msg.contains(keyword))
+        {
+            return Err(not_pushable(Some(error_msg)));
+        }
+        let fallback = format!("获取推流信息失败（错误码: {:?}）", data.code);
+        return Err(JdApiError::business(
+            data.code,
+            None,
+            Some(error_msg),
+            &fallback,
+        ));
+    }
+
+    let info = data.data.as_ref();
+    let push_url = info
+        .and_then(|info| info.push_url.as_deref())
+        .filter(|url| !url.trim().is_empty());
+    let stream_key = info
+        .and_then(|info| info.stream_key.as_deref())
+        .filter(|key| !key.trim().is_empty());
+
+    match (push_url, stream_key) {
+        (Some(url), Some(key)) => Ok((url.trim().to_string(), key.trim().to_string())),
+        (Some(url), None) => split_push_url(url).ok_or_else(|| not_pushable(None)),
+        _ => Err(not_pushable(None)),
+    }
+}
+
+/// 获取直播间推流信息（推流服务器地址、推流码和 H5 观看链接），用于在 OBS 中开播
+/// 实验性：PUSH_INFO_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn get_stream_info(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<StreamInfo, JdApiError> {
+    info!("[推流信息] 直播间: {}", live_id);
+
+    let config = config.unwrap_or_default();
+    let cookie_str = cookies_to_string(&cookies);
+    let url = PUSH_INFO_API.direct_url(&[&live_id])?;
+
+    let builder = PUSH_INFO_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config).await?;
+
+    let response_text = read_response_text(response, "推流信息").await?;
+
+    let data: PushInfoResponse = parse_response(&response_text)?;
+    let (push_url, stream_key) = parse_push_info(&live_id, data)?;
+    let h5_url = fetch_h5_url(&cookie_str, &live_id, config).await?;
+
+    info!(
+        "[推流信息] 推流地址: {}，推流码: {}",
+        push_url,
+        logging::mask_str(&stream_key)
+    );
+    Ok(StreamInfo {
+        push_url,
+        stream_key,
+        h5_url,
+    })
+}
+
+/// 按状态过滤后内存分页（recentUsedIndex 接口不支持分页参数）
+/// page 从 1 开始，page_size 为空时返回全部，页码越界时返回空列表
+fn paginate_live_rooms(
+    rooms: Vec<RecentLiveRoom>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    status_filter: Option<i32>,
+) -> RecentLiveRoomPage {
+    let filtered: Vec<RecentLiveRoom> = rooms
+        .into_iter()
+        .filter(|room| status_filter.is_none_or(|status| room.status == Some(status)))
+        .collect();
+    let total = filtered.len();
+
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size
+        .filter(|size| *size > 0)
+        .unwrap_or(total.max(1) as u32);
+    let skip = (page as usize - 1).saturating_mul(page_size as usize);
+
+    RecentLiveRoomPage {
+        total,
+        page,
+        page_size,
+        items: filtered
+            .into_iter()
+            .skip(skip)
+            .take(page_size as usize)
+            .collect(),
+    }
+}
+
+/// 创建直播间
+#[tauri::command]
+pub async fn create_live_room(
+    cookies: Vec<Cookie>,
+    mut request: CreateLiveRequest,
+    config: Option<RequestConfig>,
+) -> Result<i64, JdApiError> {
+    info!("[创建直播间] 开始创建直播间: {}", request.title);
+    info!("[创建直播间] 发布时间: {}", request.publish_time);
+
+    // 未填写欢迎语时使用主播在京东预设的第一条
+    if request.welcome.trim().is_empty() {
+        match fetch_author_templates(&cookies, config).await {
+            Ok(templates) => {
+                if let Some(welcome) = templates.welcomes.into_iter().next() {
+                    info!("[创建直播间] 使用预设欢迎语: {}", welcome);
+                    request.welcome = welcome;
+                }
+            }
+            Err(e) => info!("[创建直播间] 获取预设欢迎语失败，保持为空: {}", e),
+        }
+    }
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = LIVE_CREATE_API.direct_url(&[])?;
+
+    let builder = LIVE_CREATE_API.request(&url, &cookie_str)?.json(&request);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "创建直播间").await?;
+
+    let data: CreateLiveResponse = parse_response(&response_text)?;
+
+    if data.success {
+        if let Some(live_id) = data.live_id {
+            info!("[创建直播间] 创建成功，直播间 ID: {}", live_id);
+            return Ok(live_id);
+        }
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        data.subcode,
+        data.error_msg,
+        "创建失败",
+    ))
+}
+
+/// 京东编辑接口在直播进行中拒绝修改部分字段时返回的错误信息关键词
+const LIVE_LOCKED_ERROR_KEYWORDS: &[&str] = &["直播中", "进行中", "不允许修改", "不可修改"];
+
+/// 把修改内容转换为待提交的字段表（None 字段不出现）
+fn update_live_fields(
+    patch: &UpdateLiveRequest,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    if patch.title.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        return Err("直播间标题不能为空".to_string());
+    }
+
+    match serde_json::to_value(patch) {
+        Ok(serde_json::Value::Object(fields)) if !fields.is_empty() => Ok(fields),
+        Ok(_) => Err("没有需要修改的字段".to_string()),
+        Err(e) => Err(format!("序列化修改内容失败: {}", e)),
+    }
+}
+
+/// 向编辑接口提交一组字段
+async fn submit_live_edit(
+    cookie_str: &str,
+    live_id: &str,
+    mut fields: serde_json::Map<String, serde_json::Value>,
+    config: RequestConfig,
+) -> Result<(), JdApiError> {
+    fields.insert("liveId".to_string(), serde_json::json!(live_id));
+    fields.insert("pcVersion".to_string(), serde_json::json!(1));
+
+    let url = LIVE_EDIT_API.direct_url(&[])?;
+
+    let builder = LIVE_EDIT_API.request(&url, cookie_str)?.json(&fields);
+    let response = send_with_retry(builder, config).await?;
+
+    let response_text = read_response_text(response, "修改直播间").await?;
+
+    let data: EditLiveResponse = parse_response(&response_text)?;
+
+    if data.success {
+        return Ok(());
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "修改直播间失败",
+    ))
+}
+
+/// 修改直播间标题、封面、描述、欢迎语（只提交非 None 字段）
+/// 直播进行中整体提交被拒绝时逐个字段重试，返回成功和被拒绝的字段
+/// 实验性：LIVE_EDIT_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn update_live_room(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    patch: UpdateLiveRequest,
+    config: Option<RequestConfig>,
+) -> Result<UpdateLiveResult, JdApiError> {
+    info!("[修改直播间] 直播间: {}", live_id);
+
+    let fields = update_live_fields(&patch)?;
+    let cookie_str = cookies_to_string(&cookies);
+    let config = config.unwrap_or_default();
+
+    let error = match submit_live_edit(&cookie_str, &live_id, fields.clone(), config).await {
+        Ok(()) => {
+            info!("[修改直播间] 修改成功: {:?}", fields.keys());
+            return Ok(UpdateLiveResult {
+                updated_fields: fields.keys().cloned().collect(),
+                rejected_fields: Vec::new(),
+            });
+        }
+        Err(e) => e,
+    };
+
+    let locked = LIVE_LOCKED_ERROR_KEYWORDS
+        .iter()
+        .any(|keyword| error.message.contains(keyword));
+    if !locked || fields.len() == 1 {
+        return Err(error);
+    }
+
+    // 直播中部分字段不可修改，逐个提交找出可修改的字段
+    info!("[修改直播间] 整体修改被拒绝，逐项重试: {}", error);
+    let mut result = UpdateLiveResult::default();
+    for (field, value) in fields {
+        let single = serde_json::Map::from_iter([(field.clone(), value)]);
+        match submit_live_edit(&cookie_str, &live_id, single, config).await {
+            Ok(()) => result.updated_fields.push(field),
+            Err(e) => result.rejected_fields.push(RejectedField {
+                field,
+                reason: e.message,
+            }),
+        }
+    }
+
+    info!(
+        "[修改直播间] 成功: {:?}，被拒绝: {}",
+        result.updated_fields,
+        result.rejected_fields.len()
+    );
+    Ok(result)
+}
+
+/// 京东下播接口在直播间未开播或已结束时返回的错误信息关键词
+const NOT_LIVE_ERROR_KEYWORDS: &[&str] = &["未开播", "未开始", "已结束", "已下播", "不在直播"];
+
+/// 结束直播（下播）
+/// 实验性：LIVE_STOP_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn end_live_room(
+    tracker: tauri::State<'_, ExplainTracker>,
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<(), JdApiError> {
+    info!("[结束直播] 直播间: {}", live_id);
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = LIVE_STOP_API.direct_url(&[])?;
+
+    let body = serde_json::json!({ "liveId": live_id });
+
+    let builder = LIVE_STOP_API.request(&url, &cookie_str)?.json(&body);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "结束直播").await?;
+
+    let data: EndLiveResponse = parse_response(&response_text)?;
+
+    if data.success {
+        tracker.clear(&live_id);
+        info!("[结束直播] 已下播: {}", live_id);
+        return Ok(());
+    }
+
+    let error_msg = data.error_msg.unwrap_or_default();
+    if NOT_LIVE_ERROR_KEYWORDS
+        .iter()
+        .any(|keyword| error_msg.contains(keyword))
+    {
+        tracker.clear(&live_id);
+        return Err(JdApiError {
+            message: format!("直播间 {} 当前不在直播中，无需下播", live_id),
+            ..JdApiError::business(data.code, None, Some(error_msg), "")
+        });
+    }
+
+    let fallback = format!("下播失败（错误码: {:?}）", data.code);
+    Err(JdApiError::business(
+        data.code,
+        None,
+        Some(error_msg),
+        &fallback,
+    ))
+}
+
+/// 京东开播接口在直播间已开播时返回的错误信息关键词
+const ALREADY_LIVE_ERROR_KEYWORDS: &[&str] = &["已开播", "直播中", "正在直播", "重复开播"];
+
+/// 京东开播接口在未到预告开播时间时返回的错误信息关键词
+const NOT_YET_START_ERROR_KEYWORDS: &[&str] = &["未到开播时间", "开播时间未到", "尚未到达"];
+
+/// 开播（把预告或未开播的直播间切换到直播中），与 end_live_room 配对
+/// 实验性：LIVE_START_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn start_live(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<StartLiveResult, JdApiError> {
+    info!("[开播] 直播间: {}", live_id);
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = LIVE_START_API.direct_url(&[])?;
+
+    let body = serde_json::json!({ "liveId": live_id });
+
+    let builder = LIVE_START_API.request(&url, &cookie_str)?.json(&body);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "开播").await?;
+
+    let data: StartLiveResponse = parse_response(&response_text)?;
+
+    if !data.success {
+        let error_msg = data.error_msg.unwrap_or_default();
+        let has_keyword = |keywords: &[&str]| keywords.iter().any(|k| error_msg.contains(k));
+        let message = if has_keyword(ALREADY_LIVE_ERROR_KEYWORDS) {
+            format!("直播间 {} 已在直播中，无需重复开播", live_id)
+        } else if has_keyword(NOT_YET_START_ERROR_KEYWORDS) {
+            format!("直播间 {} 未到开播时间: {}", live_id, error_msg)
+        } else if error_msg.is_empty() {
+            format!("开播失败（错误码: {:?}）", data.code)
+        } else {
+            error_msg.clone()
+        };
+        return Err(JdApiError {
+            message,
+            ..JdApiError::business(data.code, data.subcode, Some(error_msg), "")
+        });
+    }
+
+    info!("[开播] 已开播: {}", live_id);
+
+    // 开播已成功，详情查询失败不影响结果
+    match get_live_detail(cookies, live_id.clone(), config).await {
+        Ok(detail) => Ok(StartLiveResult {
+            live_id,
+            status: detail.status,
+            start_time: detail.start_time,
+        }),
+        Err(e) => {
+            warn!("[开播] 查询开播后状态失败: {}", e);
+            Ok(StartLiveResult {
+                live_id,
+                status: None,
+                start_time: None,
+            })
+        }
+    }
+}
+
+/// 京东详情接口在直播间不存在时返回的错误信息关键词
+const LIVE_NOT_FOUND_ERROR_KEYWORDS: &[&str] = &["不存在", "已删除"];
+
+/// 京东详情接口在无权访问直播间时返回的错误信息关键词
+const NO_PERMISSION_ERROR_KEYWORDS: &[&str] = &["无权", "没有权限", "非本人"];
+
+/// 将直播间详情接口的失败响应转换为错误信息
+fn live_detail_error(live_id: &str, code: Option<i32>, error_msg: Option<String>) -> String {
+    let error_msg = error_msg.unwrap_or_default();
+    let has_keyword = |keywords: &[&str]| keywords.iter().any(|k| error_msg.contains(k));
+
+    if has_keyword(LIVE_NOT_FOUND_ERROR_KEYWORDS) {
+        return format!("直播间 {} 不存在", live_id);
+    }
+    if has_keyword(NO_PERMISSION_ERROR_KEYWORDS) {
+        return format!("无权访问直播间 {}，请确认是否为当前账号创建", live_id);
+    }
+    if error_msg.is_empty() {
+        return format!("获取直播间详情失败（错误码: {:?}）", code);
+    }
+    error_msg
+}
+
+/// 查询直播间详情（标题、状态、封面、开播时间、商品数量等）
+/// 实验性：LIVE_DETAIL_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn get_live_detail(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<LiveDetail, JdApiError> {
+    info!("[直播间详情] 直播间: {}", live_id);
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = LIVE_DETAIL_API.direct_url(&[&live_id])?;
+
+    let builder = LIVE_DETAIL_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "直播间详情").await?;
+
+    let data: LiveDetailResponse = parse_response(&response_text)?;
+
+    if !data.success {
+        return Err(JdApiError {
+            message: live_detail_error(&live_id, data.code, data.error_msg.clone()),
+            ..JdApiError::business(data.code, None, data.error_msg, "")
+        });
+    }
+    let mut detail = data
+        .data
+        .ok_or_else(|| format!("直播间 {} 不存在", live_id))?;
+    detail.normalize_image_urls();
+    Ok(detail)
+}
+
+/// 开播前检查项
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub passed: bool,
+    pub message: String,
+}
+
+impl ChecklistItem {
+    fn new(passed: bool, message: impl Into<String>) -> Self {
+        Self {
+            passed,
+            message: message.into(),
+        }
+    }
+}
+
+/// 开播前检查清单
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Checklist {
+    /// 登录有效
+    pub login: ChecklistItem,
+    /// 直播间已创建
+    pub live_room: ChecklistItem,
+    /// 购物袋有商品
+    pub shopping_bag: ChecklistItem,
+    /// 封面已设置
+    pub cover: ChecklistItem,
+    pub all_passed: bool,
+}
+
+/// 根据登录结果和直播间详情生成检查清单
+fn build_checklist(
+    login: Result<JdLoginResult, String>,
+    detail: Result<LiveDetail, String>,
+) -> Checklist {
+    let login = match login {
+        Ok(result) if result.is_logged_in => ChecklistItem::new(
+            true,
+            format!("已登录: {}", result.nickname.unwrap_or_default()),
+        ),
+        Ok(_) => ChecklistItem::new(false, "未登录或登录已过期"),
+        Err(e) => ChecklistItem::new(false, format!("检查登录状态失败: {}", e)),
+    };
+
+    let (live_room, shopping_bag, cover) = match detail {
+        Ok(detail) => {
+            let sku_num = detail.sku_num.unwrap_or(0);
+            let shopping_bag = if sku_num > 0 {
+                ChecklistItem::new(true, format!("购物袋已有 {} 个商品", sku_num))
+            } else {
+                ChecklistItem::new(false, "购物袋没有商品")
+            };
+
+            let missing: Vec<&str> = [
+                ("4:3", &detail.index_image),
+                ("2:1", &detail.resize_index_image),
+                ("1:1", &detail.square_index_image),
+                ("3:4", &detail.portrait_index_image),
+            ]
+            .iter()
+            .filter(|(_, url)| url.as_deref().is_none_or(|u| u.trim().is_empty()))
+            .map(|(ratio, _)| *ratio)
+            .collect();
+            let cover = if missing.is_empty() {
+                ChecklistItem::new(true, "封面已设置")
+            } else {
+                ChecklistItem::new(false, format!("封面缺少比例: {}", missing.join(", ")))
+            };
+
+            let title = detail.title.unwrap_or_default();
+            (
+                ChecklistItem::new(true, format!("直播间已创建: {}", title)),
+                shopping_bag,
+                cover,
+            )
+        }
+        Err(e) => (
+            ChecklistItem::new(false, e),
+            ChecklistItem::new(false, "无法获取直播间商品数量"),
+            ChecklistItem::new(false, "无法获取直播间封面"),
+        ),
+    };
+
+    let all_passed = login.passed && live_room.passed && shopping_bag.passed && cover.passed;
+    Checklist {
+        login,
+        live_room,
+        shopping_bag,
+        cover,
+        all_passed,
+    }
+}
+
+/// 开播前检查清单（并发检查登录状态和直播间详情，单项失败只标记为未通过）
+#[tauri::command]
+pub async fn pre_live_checklist(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<Checklist, String> {
+    info!("[开播检查] 直播间: {}", live_id);
+
+    let (login, detail) = tokio::join!(
+        verify_jd_login(cookies.clone(), config, None),
+        get_live_detail(cookies, live_id, config),
+    );
+
+    let checklist = build_checklist(login, detail.map_err(|e| e.message));
+    info!("[开播检查] 全部通过: {}", checklist.all_passed);
+    Ok(checklist)
+}
+
+/// 上传单个商品到直播间（与 get_sku_info_by_file 走同一 xlsx 上传路径）
+#[tauri::command]
+pub async fn upload_sku(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    sku_id: String,
+    config: Option<RequestConfig>,
+) -> Result<(), JdApiError> {
+    info!("[上传商品] 直播间: {}, 商品: {}", live_id, sku_id);
+
+    let live_id: i64 = live_id
+        .trim()
+        .parse()
+        .map_err(|_| format!("直播间 ID 无效: {}", live_id))?;
+    let sku_list =
+        upload_sku_file(&cookies, live_id, std::slice::from_ref(&sku_id), config).await?;
+
+    if !sku_list.iter().any(|sku| sku.sku == sku_id) {
+        return Err(format!("上传失败: 京东未识别商品 {}", sku_id).into());
+    }
+    Ok(())
+}
+
+/// 以 JSON 提交单个商品（已废弃：uploadSku 接口只接受文件上传，请改用 upload_sku）
+#[tauri::command]
+pub async fn upload_sku_json(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    sku_id: String,
+    config: Option<RequestConfig>,
+) -> Result<(), JdApiError> {
+    warn!("[上传商品] upload_sku_json 已废弃，请改用 upload_sku");
+    info!("[上传商品] 直播间: {}, 商品: {}", live_id, sku_id);
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = UPLOAD_SKU_JSON_API.direct_url(&[])?;
+
+    let request = UploadSkuRequest { live_id, sku_id };
+
+    let builder = UPLOAD_SKU_JSON_API
+        .request(&url, &cookie_str)?
+        .json(&request);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "上传商品").await?;
+
+    let data: SkuOperationResponse = parse_response(&response_text)?;
+
+    if data.success {
+        return Ok(());
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "上传失败",
+    ))
+}
+
+/// 按商品 ID 添加商品到购物袋，任一商品失败时返回错误
+/// 京东后台添加商品时提交的是完整商品详情（同 add_sku_to_bag_batch），旧版只传 skuIds 的请求体
+/// 与之不一致、无法确认仍被接受，因此先补全商品详情（优先用缓存，否则上传文件查询）再走批量添加；
+/// 需要逐个商品的添加结果时请直接使用 add_sku_to_bag_batch
+#[tauri::command]
+pub async fn add_sku_to_bag(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    sku_ids: Vec<String>,
+    config: Option<RequestConfig>,
+) -> Result<(), JdApiError> {
+    info!(
+        "[添加商品] 直播间: {}, 商品数量: {}",
+        live_id,
+        sku_ids.len()
+    );
+
+    let live_id: i64 = live_id
+        .trim()
+        .parse()
+        .map_err(|_| format!("直播间 ID 无效: {}", live_id))?;
+
+    let sku_list = match resolve_cached_skus(&sku_ids) {
+        Ok(sku_list) => sku_list,
+        Err(_) => upload_sku_file(&cookies, live_id, &sku_ids, config).await?,
+    };
+    let unknown: Vec<&str> = sku_ids
+        .iter()
+        .filter(|id| !sku_list.iter().any(|sku| sku.sku == **id))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("添加失败: 京东未识别商品 {}", unknown.join(", ")).into());
+    }
+
+    let result = add_sku_list(&cookies, live_id, sku_list, config).await?;
+    if !result.success {
+        let message = result.error_msg.unwrap_or_else(|| "添加失败".to_string());
+        return Err(message.into());
+    }
+    if !result.failed_items.is_empty() {
+        let reasons: Vec<String> = result
+            .failed_items
+            .iter()
+            .map(|item| format!("{}（{}）", item.sku_id, item.reason))
+            .collect();
+        return Err(format!("部分商品添加失败: {}", reasons.join("，")).into());
+    }
+    Ok(())
+}
+
+/// 京东删除接口在商品不在购物袋中时返回的错误信息关键词
+const NOT_IN_BAG_ERROR_KEYWORDS: &[&str] = &["不在购物袋", "不存在", "已删除"];
+
+/// 删除商品结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoveSkuResult {
+    /// 实际删除的商品
+    pub removed: Vec<String>,
+    /// 不在购物袋中而跳过的商品（不算失败）
+    pub skipped: Vec<String>,
+    /// 删除失败的商品明细
+    pub failed_items: Vec<FailedSku>,
+}
+
+/// 解析单个商品的删除结果，返回 false 表示商品不在购物袋中
+fn parse_remove_outcome(data: SkuOperationResponse) -> Result<bool, JdApiError> {
+    if data.success {
+        return Ok(true);
+    }
+
+    let error_msg = data.error_msg.unwrap_or_default();
+    if NOT_IN_BAG_ERROR_KEYWORDS
+        .iter()
+        .any(|keyword| error_msg.contains(keyword))
+    {
+        return Ok(false);
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        Some(error_msg),
+        "删除失败",
+    ))
+}
+
+/// 从购物袋删除单个商品
+async fn remove_one_sku(
+    url: &str,
+    cookie_str: &str,
+    live_id: &str,
+    sku_id: &str,
+    config: RequestConfig,
+) -> Result<bool, JdApiError> {
+    let request = RemoveSkuRequest {
+        live_id: live_id.to_string(),
+        sku_ids: vec![sku_id.to_string()],
+    };
+
+    let builder = SKU_DELETE_API.request(url, cookie_str)?.json(&request);
+    let response = send_with_retry(builder, config).await?;
+
+    let response_text = read_response_text(response, "删除商品").await?;
+
+    parse_remove_outcome(parse_response(&response_text)?)
+}
+
+/// 从购物袋删除商品，返回删除、跳过和失败的商品
+/// 逐个删除以统计实际结果，商品不在购物袋中时跳过，单个商品失败不影响其余商品
+/// 实验性：SKU_DELETE_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn remove_sku_from_bag(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    sku_ids: Vec<String>,
+    config: Option<RequestConfig>,
+) -> Result<RemoveSkuResult, JdApiError> {
+    info!(
+        "[删除商品] 直播间: {}, 商品数量: {}",
+        live_id,
+        sku_ids.len()
+    );
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = SKU_DELETE_API.direct_url(&[])?;
+    let config = config.unwrap_or_default();
+    let mut result = RemoveSkuResult {
+        removed: Vec::new(),
+        skipped: Vec::new(),
+        failed_items: Vec::new(),
+    };
+
+    for sku_id in sku_ids {
+        match remove_one_sku(&url, &cookie_str, &live_id, &sku_id, config).await {
+            Ok(true) => result.removed.push(sku_id),
+            Ok(false) => {
+                info!("[删除商品] {} 不在购物袋中，跳过", sku_id);
+                result.skipped.push(sku_id);
+            }
+            Err(e) => {
+                info!("[删除商品] {} 删除失败: {}", sku_id, e);
+                result.failed_items.push(FailedSku {
+                    sku_id,
+                    reason: e.message,
+                });
+            }
+        }
+    }
+
+    info!(
+        "[删除商品] 成功删除 {} 个商品，跳过 {} 个，失败 {} 个",
+        result.removed.len(),
+        result.skipped.len(),
+        result.failed_items.len()
+    );
+    Ok(result)
+}
+
+/// 置顶或取消置顶购物袋中的商品（讲解时常把主推商品置顶）
+/// 实验性：SKU_TOP_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn set_sku_top(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    sku_id: String,
+    top: bool,
+    config: Option<RequestConfig>,
+) -> Result<(), JdApiError> {
+    info!(
+        "[商品置顶] 直播间: {}, 商品: {}, 置顶: {}",
+        live_id, sku_id, top
+    );
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = SKU_TOP_API.direct_url(&[])?;
+
+    let request = SetSkuTopRequest {
+        live_id,
+        sku_id: sku_id.clone(),
+        top: i32::from(top),
+    };
+
+    let builder = SKU_TOP_API.request(&url, &cookie_str)?.json(&request);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "商品置顶").await?;
+
+    let data: SkuOperationResponse = parse_response(&response_text)?;
+
+    if data.success {
+        return Ok(());
+    }
+
+    let error_msg = data.error_msg.unwrap_or_default();
+    if NOT_IN_BAG_ERROR_KEYWORDS
+        .iter()
+        .any(|keyword| error_msg.contains(keyword))
+    {
+        return Err(JdApiError {
+            message: format!("商品 {} 不在购物袋中", sku_id),
+            ..JdApiError::business(Some(data.code), None, Some(error_msg), "")
+        });
+    }
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        Some(error_msg),
+        "商品置顶失败",
+    ))
+}
+
+/// 按排序列表生成购物袋的完整顺序
+/// 列表中重复或不在购物袋中的商品返回错误，购物袋中遗漏的商品按原顺序追加到末尾
+fn plan_sku_order(bag: &[String], ordered: &[String]) -> Result<ReorderSkuResult, String> {
+    let mut seen = std::collections::HashSet::new();
+    let duplicated: Vec<&str> = ordered
+        .iter()
+        .filter(|id| !seen.insert(id.as_str()))
+        .map(String::as_str)
+        .collect();
+    if !duplicated.is_empty() {
+        return Err(format!("排序列表中有重复商品: {}", duplicated.join(", ")));
+    }
+
+    let unknown: Vec<&str> = ordered
+        .iter()
+        .filter(|id| !bag.contains(*id))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("商品不在购物袋中: {}", unknown.join(", ")));
+    }
+
+    let appended: Vec<String> = bag
+        .iter()
+        .filter(|id| !seen.contains(id.as_str()))
+        .cloned()
+        .collect();
+    Ok(ReorderSkuResult {
+        sku_ids: ordered.iter().chain(&appended).cloned().collect(),
+        appended,
+    })
+}
+
+/// 调整购物袋商品顺序（ordered_sku_ids 为期望的顺序）
+/// 先查询购物袋实际商品：列表中多余的商品报错，遗漏的商品保持原顺序排在最后
+/// 实验性：SKU_SORT_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn reorder_skus(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    ordered_sku_ids: Vec<String>,
+    config: Option<RequestConfig>,
+) -> Result<ReorderSkuResult, JdApiError> {
+    info!(
+        "[商品排序] 直播间: {}, 商品数量: {}",
+        live_id,
+        ordered_sku_ids.len()
+    );
+
+    let bag: Vec<String> = fetch_bag_skus(&cookies, &live_id, config)
+        .await?
+        .into_iter()
+        .map(|sku| sku.sku)
+        .collect();
+    let plan = plan_sku_order(&bag, &ordered_sku_ids)?;
+    if !plan.appended.is_empty() {
+        info!(
+            "[商品排序] 排序列表遗漏的商品追加到末尾: {:?}",
+            plan.appended
+        );
+    }
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = SKU_SORT_API.direct_url(&[])?;
+
+    let request = SortSkuRequest {
+        live_id,
+        sku_ids: plan.sku_ids.clone(),
+    };
+
+    let builder = SKU_SORT_API.request(&url, &cookie_str)?.json(&request);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "商品排序").await?;
+
+    let data: SkuOperationResponse = parse_response(&response_text)?;
+
+    if data.success {
+        return Ok(plan);
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "商品排序失败",
+    ))
+}
+
+/// 获取直播实时数据
+#[tauri::command]
+pub async fn get_live_general_data(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<LiveGeneralData, JdApiError> {
+    info!("[实时数据] 获取直播间 {} 的实时数据", live_id);
+
+    fetch_general_data(&cookies, &live_id, config)
+        .await?
+        .into_result()
+}
+
+/// 获取直播实时数据（使用 set_active_cookies 缓存的 Cookie）
+#[tauri::command]
+pub async fn get_live_general_data_cached(
+    active: tauri::State<'_, ActiveCookies>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<LiveGeneralData, JdApiError> {
+    let cookies = active.get()?;
+    fetch_general_data(&cookies, &live_id, config)
+        .await?
+        .into_result()
+}
+
+/// 请求实时数据接口，网络或解析错误返回 Err，接口业务失败由 success 字段体现
+async fn fetch_general_data(
+    cookies: &[Cookie],
+    live_id: &str,
+    config: Option<RequestConfig>,
+) -> Result<GeneralDataResponse, String> {
+    let cookie_str = cookies_to_string(cookies);
+    let url = GENERAL_DATA_API.direct_url(&[live_id])?;
+
+    let builder = GENERAL_DATA_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "实时数据").await?;
+
+    parse_response(&response_text)
+}
+
+// ============ 实时数据轮询 ============
+
+/// 最小轮询间隔（毫秒），避免请求过于频繁
+const MIN_POLLING_INTERVAL_MS: u64 = 1000;
+
+/// 实时数据轮询任务类型
+const GENERAL_DATA_POLLING_TASK: &str = "general_data_polling";
+
+/// general-data-update 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneralDataUpdate {
+    pub task_id: u64,
+    pub live_id: String,
+    pub data: LiveGeneralData,
+}
+
+/// general-data-error 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneralDataError {
+    pub task_id: u64,
+    pub live_id: String,
+    pub message: String,
+}
+
+/// 启动实时数据轮询，按间隔推送 general-data-update 事件，返回任务 id
+/// 接口返回失败（如直播间已失效）时自动停止并推送 general-data-error 事件
+#[tauri::command]
+pub fn start_general_data_polling(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, TaskManager>,
+    cookies: Vec<Cookie>,
+    live_id: String,
+    interval_ms: u64,
+    config: Option<RequestConfig>,
+) -> u64 {
+    let interval = Duration::from_millis(interval_ms.max(MIN_POLLING_INTERVAL_MS));
+    let description = format!("直播间 {} 实时数据轮询", live_id);
+    let task_id = manager.spawn_task(
+        GENERAL_DATA_POLLING_TASK,
+        description,
+        |task_id, _| async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+
+                let response = match fetch_general_data(&cookies, &live_id, config).await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        warn!("[实时数据] 轮询 {} 请求失败: {}", task_id, e);
+                        continue;
+                    }
+                };
+
+                match response.into_result() {
+                    Ok(data) => {
+                        let update = GeneralDataUpdate {
+                            task_id,
+                            live_id: live_id.clone(),
+                            data,
+                        };
+                        let _ = app.emit("general-data-update", update);
+                    }
+                    Err(error) => {
+                        warn!("[实时数据] 轮询 {} 已停止: {}", task_id, error);
+                        let error = GeneralDataError {
+                            task_id,
+                            live_id: live_id.clone(),
+                            message: error.message,
+                        };
+                        let _ = app.emit("general-data-error", error);
+                        break;
+                    }
+                }
+            }
+        },
+    );
+    info!("[实时数据] 启动轮询 {}，间隔: {:?}", task_id, interval);
+
+    task_id
+}
+
+/// 停止实时数据轮询
+#[tauri::command]
+pub fn stop_general_data_polling(
+    manager: tauri::State<'_, TaskManager>,
+    task_id: u64,
+) -> Result<(), String> {
+    if !manager.cancel_kind(task_id, GENERAL_DATA_POLLING_TASK) {
+        return Err(format!("轮询任务不存在: {}", task_id));
+    }
+    info!("[实时数据] 已停止轮询 {}", task_id);
+    Ok(())
+}
+
+// ============ 商品变动监控 ============
+
+/// 商品监控最小间隔（毫秒），每次检查都要上传文件查询详情，间隔不宜过短
+const MIN_SKU_WATCH_INTERVAL_MS: u64 = 60_000;
+
+/// 京东商品在售状态
+const SKU_ON_SALE_STATUS: &str = "1";
+
+/// 商品变动监控任务类型
+const SKU_WATCH_TASK: &str = "sku_watch";
+
+/// 参与比对的商品字段
+#[derive(Debug, Clone, PartialEq)]
+struct SkuSnapshot {
+    price: Option<String>,
+    stock_state: Option<i32>,
+    sku_status: Option<String>,
+}
+
+impl From<&SkuInfo> for SkuSnapshot {
+    fn from(sku: &SkuInfo) -> Self {
+        Self {
+            price: sku.price.clone(),
+            stock_state: sku.stock_state,
+            sku_status: sku.sku_status.clone(),
+        }
+    }
+}
+
+/// 单个字段的变动明细
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkuChange {
+    pub sku_id: String,
+    /// 变动字段：price / stock_state / sku_status
+    pub field: &'static str,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// 商品是否已下架（sku_status 变为非在售或查询不到商品）
+    pub off_shelf: bool,
+}
+
+/// sku-changed / sku-status-changed 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkuChangedEvent {
+    pub task_id: u64,
+    pub live_id: i64,
+    pub changes: Vec<SkuChange>,
+}
+
+/// sku-watch-error 事件内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkuWatchError {
+    pub task_id: u64,
+    pub live_id: i64,
+    pub message: String,
+}
+
+/// 对比两次查询结果，返回变动明细（上次有、本次查询不到的商品视为下架）
+fn diff_sku_snapshots(
+    previous: &HashMap<String, SkuSnapshot>,
+    current: &HashMap<String, SkuSnapshot>,
+) -> Vec<SkuChange> {
+    let mut sku_ids: Vec<_> = previous.keys().collect();
+    sku_ids.sort();
+
+    let mut changes = Vec::new();
+    for sku_id in sku_ids {
+        let old = &previous[sku_id];
+        let Some(new) = current.get(sku_id) else {
+            changes.push(SkuChange {
+                sku_id: sku_id.clone(),
+                field: "sku_status",
+                old_value: old.sku_status.clone(),
+                new_value: None,
+                off_shelf: true,
+            });
+            continue;
+        };
+
+        let fields = [
+            ("price", old.price.clone(), new.price.clone()),
+            (
+                "stock_state",
+                old.stock_state.map(|v| v.to_string()),
+                new.stock_state.map(|v| v.to_string()),
+            ),
+            ("sku_status", old.sku_status.clone(), new.sku_status.clone()),
+        ];
+        for (field, old_value, new_value) in fields {
+            if old_value == new_value {
+                continue;
+            }
+            let off_shelf =
+                field == "sku_status" && new_value.as_deref() != Some(SKU_ON_SALE_STATUS);
+            changes.push(SkuChange {
+                sku_id: sku_id.clone(),
+                field,
+                old_value,
+                new_value,
+                off_shelf,
+            });
+        }
+    }
+    changes
+}
+
+/// 查询一次商品详情，按商品 ID 建立快照
+async fn fetch_sku_snapshots(
+    cookies: &[Cookie],
+    live_id: i64,
+    sku_ids: &[String],
+    config: Option<RequestConfig>,
+) -> Result<HashMap<String, SkuSnapshot>, JdApiError> {
+    let result = upload_sku_batches(cookies, live_id, sku_ids, config).await;
+    // 部分批次失败时不生成快照，否则查询不到的商品会被误判为下架
+    if let Some(failure) = result.failures.into_iter().next() {
+        return Err(failure.error);
+    }
+    Ok(result
+        .sku_list
+        .iter()
+        .map(|sku| (sku.sku.clone(), SkuSnapshot::from(sku)))
+        .collect())
+}
+
+/// 启动商品价格/库存/状态变动监控，返回任务 id
+/// 有变动时推送 sku-changed 事件；上下架状态变化额外推送 sku-status-changed 事件
+/// 登录失效时自动停止并推送 sku-watch-error 事件
+///
+/// 副作用：京东没有按 skuId 查询的只读接口，每次轮询都会向该直播间调用一次 uploadSku
+/// （写接口，按 100 个一批上传 xlsx），京东端是否留下上传记录未经核实；
+/// 缓存只保存上次结果、无法反映变动，因此不能用于轮询。轮询间隔不低于 60 秒，本命令不调用 sku/add
+#[tauri::command]
+pub fn watch_sku_changes(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, TaskManager>,
+    cookies: Vec<Cookie>,
+    live_id: i64,
+    sku_ids: Vec<String>,
+    interval_ms: u64,
+    config: Option<RequestConfig>,
+) -> Result<u64, String> {
+    if sku_ids.is_empty() {
+        return Err("监控商品列表为空".to_string());
+    }
+
+    let interval = Duration::from_millis(interval_ms.max(MIN_SKU_WATCH_INTERVAL_MS));
+    let description = format!(
+        "直播间 {} 商品变动监控（{} 个商品）",
+        live_id,
+        sku_ids.len()
+    );
+    let task_id = manager.spawn_task(SKU_WATCH_TASK, description, |task_id, _| async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut previous: Option<HashMap<String, SkuSnapshot>> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let current = match fetch_sku_snapshots(&cookies, live_id, &sku_ids, config).await {
+                Ok(current) => current,
+                Err(error) if error.kind == JdError::NotLoggedIn => {
+                    warn!("[商品监控] 监控 {} 已停止: {}", task_id, error);
+                    let error = SkuWatchError {
+                        task_id,
+                        live_id,
+                        message: error.message,
+                    };
+                    let _ = app.emit("sku-watch-error", error);
+                    break;
+                }
+                Err(error) => {
+                    warn!("[商品监控] 监控 {} 查询失败: {}", task_id, error);
+                    continue;
+                }
+            };
+
+            if let Some(previous) = &previous {
+                let changes = diff_sku_snapshots(previous, &current);
+                if !changes.is_empty() {
+                    info!("[商品监控] 监控 {} 发现 {} 项变动", task_id, changes.len());
+                    let status_changes: Vec<_> = changes
+                        .iter()
+                        .filter(|c| c.field == "sku_status")
+                        .cloned()
+                        .collect();
+                    if !status_changes.is_empty() {
+                        let event = SkuChangedEvent {
+                            task_id,
+                            live_id,
+                            changes: status_changes,
+                        };
+                        let _ = app.emit("sku-status-changed", event);
+                    }
+                    let event = SkuChangedEvent {
+                        task_id,
+                        live_id,
+                        changes,
+                    };
+                    let _ = app.emit("sku-changed", event);
+                }
+            }
+            previous = Some(current);
+        }
+    });
+    info!("[商品监控] 启动监控 {}，间隔: {:?}", task_id, interval);
+
+    Ok(task_id)
+}
+
+/// 停止商品变动监控
+#[tauri::command]
+pub fn stop_watch_sku_changes(
+    manager: tauri::State<'_, TaskManager>,
+    task_id: u64,
+) -> Result<(), String> {
+    if !manager.cancel_kind(task_id, SKU_WATCH_TASK) {
+        return Err(format!("监控任务不存在: {}", task_id));
+    }
+    info!("[商品监控] 已停止监控 {}", task_id);
+    Ok(())
+}
+
+/// 请求直播间 H5 页面 URL
+async fn fetch_h5_url(
+    cookie_str: &str,
+    live_id: &str,
+    config: RequestConfig,
+) -> Result<String, JdApiError> {
+    let url = H5_URL_API.direct_url(&[live_id])?;
+
+    let builder = H5_URL_API.request(&url, cookie_str)?;
+    let response = send_with_retry(builder, config).await?;
+
+    let response_text = read_response_text(response, "H5页面").await?;
+
+    let data: H5Response = parse_response(&response_text)?;
+
+    if data.success {
+        if let Some(url) = data.url {
+            return Ok(url);
+        }
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "获取失败",
+    ))
+}
+
+/// 整理评论：去掉空评论和命中屏蔽词（忽略大小写）的评论，并计算下次拉取的游标
+/// 接口未返回游标时使用最后一条评论的 id，仍没有则沿用本次传入的游标
+fn build_comment_page(
+    data: CommentListData,
+    last_id: Option<String>,
+    block_words: &[String],
+) -> LiveCommentPage {
+    let block_words: Vec<String> = block_words
+        .iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let list = data.list.unwrap_or_default();
+    let next_id = data
+        .last_id
+        .filter(|id| !id.is_empty())
+        .or_else(|| list.iter().rev().find_map(|c| c.comment_id.clone()))
+        .or(last_id);
+
+    let mut filtered_count = 0;
+    let comments = list
+        .into_iter()
+        .filter(|c| c.content.as_deref().is_some_and(|s| !s.trim().is_empty()))
+        .filter(|c| {
+            let content = c.content.as_deref().unwrap_or_default().to_lowercase();
+            let blocked = block_words.iter().any(|w| content.contains(w.as_str()));
+            if blocked {
+                filtered_count += 1;
+            }
+            !blocked
+        })
+        .collect();
+
+    LiveCommentPage {
+        comments,
+        last_id: next_id,
+        filtered_count,
+    }
+}
+
+/// 请求评论接口并整理结果
+async fn fetch_live_comments(
+    cookies: &[Cookie],
+    live_id: &str,
+    last_id: Option<String>,
+    block_words: &[String],
+    config: Option<RequestConfig>,
+) -> Result<LiveCommentPage, JdApiError> {
+    let last_id = last_id.filter(|id| !id.is_empty());
+    info!("[直播评论] 直播间: {}, 游标: {:?}", live_id, last_id);
+
+    let cookie_str = cookies_to_string(cookies);
+
+    let body_json = serde_json::json!({
+        "liveId": live_id,
+        "lastId": last_id,
+        "pageSize": COMMENT_PAGE_SIZE,
+    });
+
+    let url = COMMENT_LIST_API.gateway_url(&body_json)?;
+
+    let builder = COMMENT_LIST_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "直播评论").await?;
+
+    let data: CommentListResponse = parse_response(&response_text)?;
+
+    if !data.success {
+        return Err(JdApiError::business(
+            Some(data.code),
+            None,
+            data.error_msg,
+            "获取评论失败",
+        ));
+    }
+
+    // 没有新评论时接口可能不返回 data
+    let list = data.data.unwrap_or(CommentListData {
+        list: None,
+        last_id: None,
+    });
+    let page = build_comment_page(list, last_id, block_words);
+    info!(
+        "[直播评论] 新评论 {} 条，过滤 {} 条",
+        page.comments.len(),
+        page.filtered_count
+    );
+    Ok(page)
+}
+
+/// 拉取直播评论（用于投屏展示），last_id 为上次返回的游标，为空时拉取最新一页
+/// block_words 为可选的屏蔽词，命中的评论不返回
+/// 实验性：COMMENT_LIST_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn get_live_comments(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    last_id: Option<String>,
+    block_words: Option<Vec<String>>,
+    config: Option<RequestConfig>,
+) -> Result<LiveCommentPage, JdApiError> {
+    let block_words = block_words.unwrap_or_default();
+    fetch_live_comments(&cookies, &live_id, last_id, &block_words, config).await
+}
+
+/// 拉取直播评论（使用 set_active_cookies 缓存的 Cookie）
+#[tauri::command]
+pub async fn get_live_comments_cached(
+    active: tauri::State<'_, ActiveCookies>,
+    live_id: String,
+    last_id: Option<String>,
+    block_words: Option<Vec<String>>,
+    config: Option<RequestConfig>,
+) -> Result<LiveCommentPage, JdApiError> {
+    let cookies = active.get()?;
+    let block_words = block_words.unwrap_or_default();
+    fetch_live_comments(&cookies, &live_id, last_id, &block_words, config).await
+}
+
+/// 开始讲解商品（成功后推送 script-switch 事件，脚本窗口切换到该商品的话术）
+#[tauri::command]
+pub async fn start_explain(
+    app: tauri::AppHandle,
+    tracker: tauri::State<'_, ExplainTracker>,
+    cookies: Vec<Cookie>,
+    live_id: String,
+    sku_id: String,
+    config: Option<RequestConfig>,
+) -> Result<(), JdApiError> {
+    info!("[开始讲解] 直播间: {}, 商品: {}", live_id, sku_id);
+
+    let cookie_str = cookies_to_string(&cookies);
+
+    // 构建请求体 JSON
+    let body_json = serde_json::json!({
+        "liveId": live_id,
+        "type": 0,
+        "skuId": sku_id,
+        "pageId": "jlive.jd.com",
+        "PRICE_COLOR_API_TAG": true
+    });
+
+    let url = EXPLAIN_BEGIN_API.gateway_url(&body_json)?;
+
+    let builder = EXPLAIN_BEGIN_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "开始讲解").await?;
+
+    let data: ExplainResponse = parse_response(&response_text)?;
+
+    if data.success {
+        tracker.begin(&live_id, &sku_id);
+        screen::emit_script_switch(&app, &sku_id);
+        return Ok(());
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "开始讲解失败",
+    ))
+}
+
+/// 结束讲解商品
+#[tauri::command]
+pub async fn end_explain(
+    tracker: tauri::State<'_, ExplainTracker>,
+    cookies: Vec<Cookie>,
+    live_id: String,
+    sku_id: String,
+    config: Option<RequestConfig>,
+) -> Result<ExplainResult, JdApiError> {
+    info!("[结束讲解] 直播间: {}, 商品: {}", live_id, sku_id);
+
+    let cookie_str = cookies_to_string(&cookies);
+
+    // 构建请求体 JSON
+    let body_json = serde_json::json!({
+        "liveId": live_id,
+        "type": 0,
+        "skuId": sku_id,
+        "pageId": "jlive.jd.com",
+        "PRICE_COLOR_API_TAG": true
+    });
+
+    let url = EXPLAIN_END_API.gateway_url(&body_json)?;
+
+    let builder = EXPLAIN_END_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "结束讲解").await?;
+
+    let data: ExplainResponse = parse_response(&response_text)?;
+
+    if data.success {
+        let duration_secs = tracker.finish(&live_id, &sku_id);
+        info!("[结束讲解] 商品 {} 讲解时长: {} 秒", sku_id, duration_secs);
+        return Ok(ExplainResult { duration_secs });
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "结束讲解失败",
+    ))
+}
+
+/// 获取直播间当前讲解的商品及已讲解时长，没有讲解中的商品时返回 None
+#[tauri::command]
+pub fn get_current_explain(
+    tracker: tauri::State<'_, ExplainTracker>,
+    live_id: String,
+) -> Option<CurrentExplain> {
+    tracker.current(&live_id)
+}
+
+/// 京东返回的讲解中标记（explainStatus 为 "1" 或 explainBegin 为 1）
+const EXPLAINING_FLAG: &str = "1";
+
+/// 讲解中的商品
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplainingSku {
+    pub sku_id: String,
+    pub title: Option<String>,
+    /// 开始讲解时间（Unix 毫秒），仅本应用发起的讲解有记录
+    pub started_at: Option<u64>,
+}
+
+/// 从购物袋商品中筛选讲解中的商品，开始时间取本地讲解记录
+fn collect_explaining(skus: &[SkuInfo], tracked: Option<&CurrentExplain>) -> Vec<ExplainingSku> {
+    skus.iter()
+        .filter(|sku| {
+            sku.explain_status.as_deref() == Some(EXPLAINING_FLAG) || sku.explain_begin == Some(1)
+        })
+        .map(|sku| ExplainingSku {
+            sku_id: sku.sku.clone(),
+            title: sku.title.clone(),
+            started_at: tracked
+                .filter(|current| current.sku_id == sku.sku)
+                .map(|current| current.started_at),
+        })
+        .collect()
+}
+
+/// 查询购物袋中的商品（按购物袋展示顺序）
+async fn fetch_bag_skus(
+    cookies: &[Cookie],
+    live_id: &str,
+    config: Option<RequestConfig>,
+) -> Result<Vec<SkuInfo>, JdApiError> {
+    let cookie_str = cookies_to_string(cookies);
+    let url = SKU_LIST_API.direct_url(&[live_id])?;
+
+    let builder = SKU_LIST_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "购物袋商品").await?;
+
+    let data: GetSkuInfoResponse = parse_response(&response_text)?;
+
+    if !data.success {
+        return Err(JdApiError::business(
+            Some(data.code),
+            data.subcode,
+            data.error_msg,
+            "查询购物袋商品失败",
+        ));
+    }
+    Ok(data.data.unwrap_or_default())
+}
+
+/// 查询直播间讲解中的商品（以京东购物袋状态为准，应用重启或在其他端开始的讲解也能查到）
+/// 没有讲解中的商品时返回空列表
+/// 实验性：SKU_LIST_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn get_current_explaining(
+    tracker: tauri::State<'_, ExplainTracker>,
+    cookies: Vec<Cookie>,
+    live_id: String,
+    config: Option<RequestConfig>,
+) -> Result<Vec<ExplainingSku>, JdApiError> {
+    info!("[讲解状态] 直播间: {}", live_id);
+
+    let skus = fetch_bag_skus(&cookies, &live_id, config).await?;
+
+    let tracked = tracker.current(&live_id);
+    let explaining = collect_explaining(&skus, tracked.as_ref());
+    info!("[讲解状态] 讲解中的商品: {}", explaining.len());
+    Ok(explaining)
+}
+
+/// 拉取主播模板（优先使用缓存）
+async fn fetch_author_templates(
+    cookies: &[Cookie],
+    config: Option<RequestConfig>,
+) -> Result<AuthorTemplates, String> {
+    let cache_key = cookie_cache_key(cookies);
+    if let Some(templates) = AUTHOR_TEMPLATE_CACHE.get(&cache_key) {
+        info!("[主播模板] 命中缓存");
+        return Ok(templates);
+    }
+
+    let cookie_str = cookies_to_string(cookies);
+
+    let body_json = serde_json::json!({
+        "pageId": "jlive.jd.com",
+        "PRICE_COLOR_API_TAG": true
+    });
+
+    let url = AUTHOR_TEMPLATES_API.gateway_url(&body_json)?;
+
+    let builder = AUTHOR_TEMPLATES_API.request(&url, &cookie_str)?;
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "主播模板").await?;
+
+    let data: AuthorTemplatesResponse = parse_response(&response_text)?;
+
+    if data.success {
+        let d = data.data;
+        let templates = AuthorTemplates {
+            welcomes: d
+                .as_ref()
+                .and_then(|d| d.welcome_list.clone())
+                .unwrap_or_default(),
+            scripts: d.and_then(|d| d.script_list).unwrap_or_default(),
+        };
+        AUTHOR_TEMPLATE_CACHE.insert(cache_key, templates.clone());
+        return Ok(templates);
+    }
+
+    Err(data.error_msg.unwrap_or_else(|| "获取模板失败".to_string()))
+}
+
+/// 获取主播在京东预设的欢迎语和话术模板
+/// 实验性：AUTHOR_TEMPLATES_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn get_author_templates(
+    cookies: Vec<Cookie>,
+    config: Option<RequestConfig>,
+) -> Result<AuthorTemplates, String> {
+    info!("[主播模板] 开始获取主播模板");
+    fetch_author_templates(&cookies, config).await
+}
+
+// ============ 封面图片相关 ============
+
+/// 封面图片项
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverImage {
+    pub four_to_three: Option<String>,
+    pub two_to_one: Option<String>,
+    pub one_to_one: Option<String>,
+    pub three_to_four: Option<String>,
+}
+
+/// 封面图片响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverImagesResponse {
+    pub success: bool,
+    pub code: i32,
+    pub error_msg: Option<String>,
+    pub data: Option<Vec<CoverImage>>,
+}
+
+/// 获取封面图片列表
+#[tauri::command]
+pub async fn get_cover_images(
+    cookies: Vec<Cookie>,
+    config: Option<RequestConfig>,
+) -> Result<Vec<CoverImage>, JdApiError> {
+    info!("[封面图片] 开始获取封面图片列表");
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = COVER_IMAGES_API.direct_url(&[])?;
+
+    let body = format!(
+        "appid={}&functionId=live_pc_recentUsedIndex&body={{}}{}",
+        sign::GATEWAY_APPID,
+        sign::sign_query("live_pc_recentUsedIndex", "{}")?
+    );
+
+    let builder = COVER_IMAGES_API.request(&url, &cookie_str)?.body(body);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "封面图片").await?;
+
+    let data: CoverImagesResponse = parse_response(&response_text)?;
+
+    if data.success {
+        let mut covers = data.data.unwrap_or_default();
+        covers.iter_mut().for_each(CoverImage::normalize_image_urls);
+        return Ok(covers);
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "获取封面图片失败",
+    ))
+}
+
+impl CoverImage {
+    fn normalize_image_urls(&mut self) {
+        normalize_image_field(&mut self.four_to_three);
+        normalize_image_field(&mut self.two_to_one);
+        normalize_image_field(&mut self.one_to_one);
+        normalize_image_field(&mut self.three_to_four);
+    }
+}
+
+/// 修改直播间封面请求
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditLiveCoverRequest {
+    pub live_id: String,
+    pub index_image: String,          // 封面图（4:3）
+    pub resize_index_image: String,   // 封面图（2:1）
+    pub square_index_image: String,   // 封面图（1:1）
+    pub portrait_index_image: String, // 封面图（3:4）
+    pub pc_version: i32,              // PC 版本，固定 1
+}
+
+/// 修改直播间响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditLiveResponse {
+    pub success: bool,
+    pub code: i32,
+    pub error_msg: Option<String>,
+    pub data: Option<RecentLiveRoom>,
+}
+
+/// 校验封面图四种比例齐全（与创建直播间一致，横竖屏均需提供全部比例）
+fn validate_cover_ratios(cover: &CoverImage) -> Result<(), String> {
+    let ratios = [
+        ("4:3", &cover.four_to_three),
+        ("2:1", &cover.two_to_one),
+        ("1:1", &cover.one_to_one),
+        ("3:4", &cover.three_to_four),
+    ];
+
+    let missing: Vec<&str> = ratios
+        .iter()
+        .filter(|(_, url)| url.as_deref().is_none_or(|u| u.trim().is_empty()))
+        .map(|(ratio, _)| *ratio)
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(format!("封面图缺少比例: {}", missing.join(", ")));
+    }
+
+    Ok(())
+}
+
+/// 将 get_cover_images 返回的封面设置为直播间封面
+/// 实验性：LIVE_EDIT_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn set_room_cover(
+    cookies: Vec<Cookie>,
+    live_id: String,
+    cover: CoverImage,
+    config: Option<RequestConfig>,
+) -> Result<RecentLiveRoom, JdApiError> {
+    info!("[设置封面] 直播间: {}", live_id);
+
+    validate_cover_ratios(&cover)?;
+
+    let request = EditLiveCoverRequest {
+        live_id: live_id.clone(),
+        index_image: cover.four_to_three.clone().unwrap_or_default(),
+        resize_index_image: cover.two_to_one.clone().unwrap_or_default(),
+        square_index_image: cover.one_to_one.clone().unwrap_or_default(),
+        portrait_index_image: cover.three_to_four.clone().unwrap_or_default(),
+        pc_version: 1,
+    };
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = LIVE_EDIT_API.direct_url(&[])?;
+
+    let builder = LIVE_EDIT_API.request(&url, &cookie_str)?.json(&request);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "设置封面").await?;
+
+    let data: EditLiveResponse = parse_response(&response_text)?;
+
+    if data.success {
+        // 接口未回传直播间信息时，用已知字段组装
+        let mut room = data.data.unwrap_or(RecentLiveRoom {
+            live_id: Some(live_id),
+            title: None,
+            cover_url: cover.four_to_three,
+            status: None,
+            start_time: None,
+            end_time: None,
+        });
+        room.normalize_image_urls();
+        return Ok(room);
+    }
+
+    Err(JdApiError::business(
+        Some(data.code),
+        None,
+        data.error_msg,
+        "设置封面失败",
+    ))
+}
+
+/// 封面图比例（名称与 CoverImage 字段一致）
+const COVER_RATIOS: &[(&str, u32, u32)] = &[
+    ("fourToThree", 4, 3),
+    ("twoToOne", 2, 1),
+    ("oneToOne", 1, 1),
+    ("threeToFour", 3, 4),
+];
+
+/// 比例允许的误差（1%）
+const COVER_RATIO_TOLERANCE: f64 = 0.01;
+
+/// 封面图支持的格式
+const COVER_IMAGE_MIMES: &[&str] = &["image/png", "image/jpeg", "image/webp"];
+
+/// 从文件头读取图片宽高
+fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be16 = |i: usize| Some(u16::from_be_bytes(bytes.get(i..i + 2)?.try_into().ok()?) as u32);
+    let le16 = |i: usize| Some(u16::from_le_bytes(bytes.get(i..i + 2)?.try_into().ok()?) as u32);
+    let le24 = |i: usize| Some(le16(i)? | ((*bytes.get(i + 2)? as u32) << 16));
+
+    match utils::sniff_image_mime(bytes)? {
+        "image/png" => {
+            let width = u32::from_be_bytes(bytes.get(16..20)?.try_into().ok()?);
+            let height = u32::from_be_bytes(bytes.get(20..24)?.try_into().ok()?);
+            Some((width, height))
+        }
+        "image/jpeg" => {
+            // 逐段查找 SOF 段（C0-CF，排除 C4/C8/CC）
+            let mut i = 2;
+            while i + 9 < bytes.len() {
+                if bytes[i] != 0xFF {
+                    return None;
+                }
+                let marker = bytes[i + 1];
+                if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+                    return Some((be16(i + 7)?, be16(i + 5)?));
+                }
+                i += 2 + be16(i + 2)? as usize;
+            }
+            None
+        }
+        "image/webp" => match bytes.get(12..16)? {
+            b"VP8 " => Some((le16(26)? & 0x3FFF, le16(28)? & 0x3FFF)),
+            b"VP8L" => {
+                let bits = u32::from_le_bytes(bytes.get(21..25)?.try_into().ok()?);
+                Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+            }
+            b"VP8X" => Some((le24(24)? + 1, le24(27)? + 1)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 匹配封面图比例，不符合任何比例时返回 None
+fn match_cover_ratio(width: u32, height: u32) -> Option<&'static str> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let actual = width as f64 / height as f64;
+
+    COVER_RATIOS
+        .iter()
+        .find(|(_, w, h)| {
+            let expected = *w as f64 / *h as f64;
+            (actual - expected).abs() / expected <= COVER_RATIO_TOLERANCE
+        })
+        .map(|(name, _, _)| *name)
+}
+
+/// 图片上传响应
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadImageResponse {
+    pub success: bool,
+    pub code: Option<i32>,
+    pub error_msg: Option<String>,
+    pub data: Option<String>,
+}
+
+/// 本地封面图上传结果
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedCoverImage {
+    /// 京东 CDN 地址
+    pub url: String,
+    /// 对应的 CoverImage 字段（fourToThree / twoToOne / oneToOne / threeToFour）
+    pub ratio: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 上传本地封面图片（png/jpg/webp），返回 CDN 地址及其所属比例
+/// 前端按 ratio 填入 CreateLiveRequest 对应的封面字段
+/// 实验性：UPLOAD_IMAGE_API 未经抓包核实（见 EndpointSpec::experimental）
+#[tauri::command]
+pub async fn upload_cover_image(
+    cookies: Vec<Cookie>,
+    file_path: String,
+    config: Option<RequestConfig>,
+) -> Result<UploadedCoverImage, String> {
+    info!("[上传封面] 文件: {}", file_path);
+
+    let bytes = tokio::fs::read(&file_path)
+        .await
+        .map_err(|e| format!("读取图片失败: {}", e))?;
+
+    let mime = utils::sniff_image_mime(&bytes)
+        .filter(|mime| COVER_IMAGE_MIMES.contains(mime))
+        .ok_or("读取图片失败: 仅支持 png、jpg、webp 格式")?;
+    let (width, height) = image_dimensions(&bytes).ok_or("读取图片失败: 无法识别图片尺寸")?;
+    let ratio = match_cover_ratio(width, height).ok_or_else(|| {
+        format!(
+            "图片尺寸 {}x{} 不符合封面比例要求（4:3、2:1、1:1、3:4）",
+            width, height
+        )
+    })?;
+
+    check_upload_size(UPLOAD_IMAGE_ENDPOINT, bytes.len())?;
+
+    let file_name = std::path::Path::new(&file_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "cover".to_string());
+    let file_part = reqwest::multipart::Part::bytes(bytes)
+        .file_name(file_name)
+        .mime_str(mime)
+        .map_err(|e| format!("创建文件部分失败: {}", e))?;
+    let form = reqwest::multipart::Form::new().part("file", file_part);
+
+    let cookie_str = cookies_to_string(&cookies);
+    let url = UPLOAD_IMAGE_API.direct_url(&[])?;
+
+    let builder = UPLOAD_IMAGE_API.request(&url, &cookie_str)?.multipart(form);
+    let response = send_with_retry(builder, config.unwrap_or_default())
+        .await
+        .map_err(|e| format!("上传图片失败: {}", e))?;
+
+    let response_text = read_response_text(response, "上传封面")
+        .await
+        .map_err(|e| format!("上传图片失败: {}", e))?;
+
+    let data: UploadImageResponse =
+        parse_response(&response_text).map_err(|e| format!("上传图片失败: {}", e))?;
+
+    match data.data {
+        Some(url) if data.success && !url.is_empty() => Ok(UploadedCoverImage {
+            url,
+            ratio: ratio.to_string(),
+            width,
+            height,
+        }),
+        _ => Err(format!(
+            "上传图片失败: {}",
+            data.error_msg
+                .unwrap_or_else(|| "接口未返回图片地址".to_string())
+        )),
+    }
+}
+
+// ============ 商品详情相关（购物袋功能）============
+
+/// 商品详情（从京东接口返回的完整数据）
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SkuInfo {
     pub sku: String,
@@ -914,6 +4125,42 @@ pub struct SkuInfo {
     pub fs_arrival_price: Option<String>,
 }
 
+/// 商品精简信息（前端展示用）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkuBrief {
+    pub sku: String,
+    pub title: Option<String>,
+    pub img: Option<String>,
+    pub price: Option<String>,
+}
+
+impl SkuInfo {
+    /// 转换为精简信息
+    pub fn to_brief(&self) -> SkuBrief {
+        SkuBrief {
+            sku: self.sku.clone(),
+            title: self.title.clone(),
+            img: self.img.clone(),
+            price: self.price.clone(),
+        }
+    }
+}
+
+/// 商品详情列表（完整或精简）
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SkuInfoList {
+    Full(Vec<SkuInfo>),
+    Brief(Vec<SkuBrief>),
+}
+
+/// 商品详情缓存有效期
+const SKU_INFO_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// 商品详情缓存（按 sku 索引，批量添加时用于补全完整字段）
+pub static SKU_INFO_CACHE: TtlCache<SkuInfo> = TtlCache::new("sku_info", SKU_INFO_TTL);
+
 /// 获取商品详情响应
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -950,67 +4197,67 @@ pub struct AddSkuBatchResponse {
     pub hide_error_msg: Option<bool>,
 }
 
+/// 添加或删除失败的商品
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FailedSku {
+    pub sku_id: String,
+    pub reason: String,
+}
+
 /// 添加商品结果
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AddSkuResult {
     pub success: bool,
     pub success_count: i32,
     pub error_msg: Option<String>,
+    /// 添加失败的商品明细（京东只返回整体失败时为空）
+    pub failed_items: Vec<FailedSku>,
 }
 
-/// 通过上传文件获取商品详情
-#[tauri::command]
-pub async fn get_sku_info_by_file(
-    cookies: Vec<Cookie>,
-    live_id: i64,
-    sku_ids: Vec<String>,
-) -> Result<Vec<SkuInfo>, String> {
-    info!("[获取商品详情] 直播间: {}, 商品数量: {}", live_id, sku_ids.len());
-
-    if sku_ids.is_empty() {
-        return Ok(vec![]);
-    }
-
-    // 1. 生成临时 xlsx 文件
-    let temp_dir = std::env::temp_dir();
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis();
-    let file_path = temp_dir.join(format!("jd-upload-{}.xlsx", timestamp));
-
-    info!("[获取商品详情] 生成临时文件: {:?}", file_path);
-
-    // 使用 rust_xlsxwriter 生成 xlsx
-    {
-        use rust_xlsxwriter::Workbook;
-        let mut workbook = Workbook::new();
-        let worksheet = workbook.add_worksheet();
-
-        // 写入表头
-        worksheet.write_string(0, 0, "skuId").map_err(|e| format!("写入表头失败: {}", e))?;
-
-        // 写入商品 ID
-        for (i, sku_id) in sku_ids.iter().enumerate() {
-            worksheet.write_string((i + 1) as u32, 0, sku_id)
-                .map_err(|e| format!("写入商品ID失败: {}", e))?;
-        }
-
-        workbook.save(&file_path).map_err(|e| format!("保存文件失败: {}", e))?;
+/// 生成商品上传用的 xlsx 文件内容（首行表头 skuId，之后每行一个商品 ID）
+fn build_sku_xlsx(sku_ids: &[String]) -> Result<Vec<u8>, String> {
+    use rust_xlsxwriter::Workbook;
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet
+        .write_string(0, 0, "skuId")
+        .map_err(|e| format!("写入表头失败: {}", e))?;
+    for (i, sku_id) in sku_ids.iter().enumerate() {
+        worksheet
+            .write_string((i + 1) as u32, 0, sku_id)
+            .map_err(|e| format!("写入商品ID失败: {}", e))?;
     }
 
-    // 2. 读取文件内容
-    let file_content = tokio::fs::read(&file_path)
-        .await
-        .map_err(|e| format!("读取文件失败: {}", e))?;
+    workbook
+        .save_to_buffer()
+        .map_err(|e| format!("生成文件失败: {}", e))
+}
 
-    // 3. 构建 multipart 请求
-    let cookie_str = cookies_to_string(&cookies);
-    let url = "https://drlives.jd.com/live-shopping-bag/sku/uploadSku";
+/// 以 xlsx 文件上传商品到 uploadSku 接口（该接口只接受文件上传），返回京东识别出的商品详情
+/// 上传后商品只是暂存，需再调用 add_sku_to_bag_batch 才会进入购物袋；单个商品也走这里
+async fn upload_sku_file(
+    cookies: &[Cookie],
+    live_id: i64,
+    sku_ids: &[String],
+    config: Option<RequestConfig>,
+) -> Result<Vec<SkuInfo>, JdApiError> {
+    // 1. 在内存中生成 xlsx，不落盘
+    let file_content = build_sku_xlsx(sku_ids)?;
+    check_upload_size(UPLOAD_SKU_ENDPOINT, file_content.len())?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+
+    // 2. 构建 multipart 请求
+    let cookie_str = cookies_to_string(cookies);
+    let url = UPLOAD_SKU_FILE_API.direct_url(&[])?;
 
     let file_name = format!("jd-upload-{}.xlsx", timestamp);
     let file_part = reqwest::multipart::Part::bytes(file_content)
-        .file_name(file_name.clone())
+        .file_name(file_name)
         .mime_str("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet")
         .map_err(|e| format!("创建文件部分失败: {}", e))?;
 
@@ -1020,75 +4267,304 @@ pub async fn get_sku_info_by_file(
         .text("type", "undefined")
         .part("file", file_part);
 
-    let client = reqwest::Client::new();
-    let mut headers = reqwest::header::HeaderMap::new();
-    if let Ok(value) = cookie_str.parse() {
-        headers.insert(reqwest::header::COOKIE, value);
+    let builder = UPLOAD_SKU_FILE_API
+        .request(&url, &cookie_str)?
+        .multipart(form);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
+
+    let response_text = read_response_text(response, "获取商品详情").await?;
+
+    let data: GetSkuInfoResponse = parse_response(&response_text)?;
+
+    if data.success {
+        let mut sku_list = data.data.unwrap_or_default();
+        for sku in &mut sku_list {
+            normalize_image_field(&mut sku.img);
+        }
+        info!("[获取商品详情] 成功获取 {} 个商品详情", sku_list.len());
+        for sku in &sku_list {
+            SKU_INFO_CACHE.insert(sku.sku.clone(), sku.clone());
+        }
+        return Ok(sku_list);
     }
-    headers.insert(
-        reqwest::header::USER_AGENT,
-        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/134.0.0.0 Safari/537.36"
-            .parse()
-            .unwrap(),
-    );
-    headers.insert(
-        reqwest::header::REFERER,
-        "https://jlive.jd.com/".parse().unwrap(),
-    );
-    headers.insert(
-        reqwest::header::HOST,
-        "drlives.jd.com".parse().unwrap(),
+
+    Err(JdApiError::business(
+        Some(data.code),
+        data.subcode,
+        data.error_msg,
+        "获取商品详情失败",
+    ))
+}
+
+/// uploadSku 单次上传的商品数上限，超出时分批上传
+const SKU_UPLOAD_BATCH_SIZE: usize = 100;
+
+/// 分批上传的最大并发数（并发过高容易触发风控）
+const SKU_UPLOAD_CONCURRENCY: usize = 2;
+
+/// 获取失败的商品批次
+#[derive(Debug, Serialize)]
+pub struct FailedSkuBatch {
+    /// 批次序号（从 0 开始）
+    pub index: usize,
+    /// 该批次的商品 ID
+    pub sku_ids: Vec<String>,
+    /// 失败原因（多批时错误信息注明批次和商品范围）
+    pub error: JdApiError,
+}
+
+/// 分批上传商品的合并结果
+struct SkuBatchResult {
+    /// 成功批次的商品详情（按原始顺序）
+    sku_list: Vec<SkuInfo>,
+    batch_count: usize,
+    /// 失败的批次
+    failures: Vec<FailedSkuBatch>,
+}
+
+/// 按 SKU_UPLOAD_BATCH_SIZE 分批上传商品并限制并发，某批失败不影响其他批次
+async fn upload_sku_batches(
+    cookies: &[Cookie],
+    live_id: i64,
+    sku_ids: &[String],
+    config: Option<RequestConfig>,
+) -> SkuBatchResult {
+    fetch_sku_batches(sku_ids, |batch| {
+        upload_sku_file(cookies, live_id, batch, config)
+    })
+    .await
+}
+
+/// 分批获取商品详情并限制并发，fetch 负责获取单个批次
+async fn fetch_sku_batches<'a, F, Fut>(sku_ids: &'a [String], fetch: F) -> SkuBatchResult
+where
+    F: Fn(&'a [String]) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<SkuInfo>, JdApiError>>,
+{
+    use futures::StreamExt;
+
+    let batches: Vec<&[String]> = sku_ids.chunks(SKU_UPLOAD_BATCH_SIZE).collect();
+    let batch_count = batches.len();
+    if batch_count > 1 {
+        info!(
+            "[获取商品详情] 商品数量 {} 超过单次上限，分 {} 批上传",
+            sku_ids.len(),
+            batch_count
+        );
+    }
+
+    // buffered 按提交顺序产出结果，合并后仍保持原始顺序
+    let results: Vec<_> = futures::stream::iter(batches.into_iter().enumerate())
+        .map(|(index, batch)| {
+            let fetching = fetch(batch);
+            async move { (index, batch, fetching.await) }
+        })
+        .buffered(SKU_UPLOAD_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut merged = SkuBatchResult {
+        sku_list: Vec::new(),
+        batch_count,
+        failures: Vec::new(),
+    };
+    for (index, batch, result) in results {
+        let error = match result {
+            Ok(sku_list) => {
+                merged.sku_list.extend(sku_list);
+                continue;
+            }
+            Err(e) if batch_count == 1 => e,
+            Err(e) => {
+                let start = index * SKU_UPLOAD_BATCH_SIZE + 1;
+                let message = format!(
+                    "第 {}/{} 批（第 {}-{} 个商品）获取失败: {}",
+                    index + 1,
+                    batch_count,
+                    start,
+                    start + batch.len() - 1,
+                    e.message
+                );
+                warn!("[获取商品详情] {}", message);
+                JdApiError { message, ..e }
+            }
+        };
+        merged.failures.push(FailedSkuBatch {
+            index,
+            sku_ids: batch.to_vec(),
+            error,
+        });
+    }
+    merged
+}
+
+/// 批量获取商品详情结果
+#[derive(Debug, Serialize)]
+pub struct SkuInfoBatchResult {
+    /// 成功批次的商品（brief 为 true 时为精简信息）
+    pub items: SkuInfoList,
+    /// 获取失败的批次，全部成功时为空
+    pub failed_batches: Vec<FailedSkuBatch>,
+}
+
+/// 通过上传文件获取商品详情（批量，超过 100 个时自动分批）
+/// 全部批次失败时返回错误；部分失败时返回成功批次的商品，并在 failed_batches 中列出失败批次
+#[tauri::command]
+pub async fn get_sku_info_by_file(
+    cookies: Vec<Cookie>,
+    live_id: i64,
+    sku_ids: Vec<String>,
+    brief: Option<bool>,
+    config: Option<RequestConfig>,
+) -> Result<SkuInfoBatchResult, JdApiError> {
+    info!(
+        "[获取商品详情] 直播间: {}, 商品数量: {}",
+        live_id,
+        sku_ids.len()
     );
 
-    let response = client
-        .post(url)
-        .headers(headers)
-        .multipart(form)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+    let mut result = upload_sku_batches(&cookies, live_id, &sku_ids, config).await;
+    if !result.failures.is_empty() && result.failures.len() == result.batch_count {
+        return Err(result.failures.remove(0).error);
+    }
 
-    // 4. 删除临时文件
-    let _ = tokio::fs::remove_file(&file_path).await;
+    let items = if brief.unwrap_or(false) {
+        SkuInfoList::Brief(result.sku_list.iter().map(SkuInfo::to_brief).collect())
+    } else {
+        SkuInfoList::Full(result.sku_list)
+    };
+    Ok(SkuInfoBatchResult {
+        items,
+        failed_batches: result.failures,
+    })
+}
 
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+/// 按 sku_ids 的顺序排列商品详情（重复 ID 只保留一个，京东未识别的 ID 跳过）
+fn order_skus_by_ids(sku_ids: &[String], sku_list: Vec<SkuInfo>) -> Vec<SkuInfo> {
+    let mut by_id: HashMap<String, SkuInfo> = sku_list
+        .into_iter()
+        .map(|sku| (sku.sku.clone(), sku))
+        .collect();
+    sku_ids.iter().filter_map(|id| by_id.remove(id)).collect()
+}
 
-    info!("[获取商品详情] 响应长度: {} 字符", response_text.len());
+/// 查询商品详情（价格、库存等展示用途），refresh 为 false（默认）时优先使用缓存
+///
+/// 这不是只读查询：京东直播后台没有按 skuId 查询的只读接口，未命中缓存的商品
+/// 会通过 uploadSku 上传到该直播间解析详情。uploadSku 是写接口，京东端的副作用
+/// （是否留下上传记录、是否影响购物袋）未经核实；本命令只保证自身不调用 sku/add
+#[tauri::command]
+pub async fn query_sku_detail(
+    cookies: Vec<Cookie>,
+    live_id: i64,
+    sku_ids: Vec<String>,
+    refresh: Option<bool>,
+    config: Option<RequestConfig>,
+) -> Result<Vec<SkuInfo>, JdApiError> {
+    info!(
+        "[查询商品] 直播间: {}, 商品数量: {}",
+        live_id,
+        sku_ids.len()
+    );
 
-    let data: GetSkuInfoResponse =
-        serde_json::from_str(&response_text).map_err(|e| format!("解析响应失败: {}", e))?;
+    let mut sku_list = Vec::new();
+    let mut missing = Vec::new();
+    for id in &sku_ids {
+        match SKU_INFO_CACHE.get(id).filter(|_| !refresh.unwrap_or(false)) {
+            Some(sku) => sku_list.push(sku),
+            None if !missing.contains(id) => missing.push(id.clone()),
+            None => {}
+        }
+    }
 
-    if data.success {
-        let sku_list = data.data.unwrap_or_default();
-        info!("[获取商品详情] 成功获取 {} 个商品详情", sku_list.len());
-        return Ok(sku_list);
+    if !missing.is_empty() {
+        info!(
+            "[查询商品] 缓存命中 {} 个，需从京东获取 {} 个",
+            sku_list.len(),
+            missing.len()
+        );
+        let mut result = upload_sku_batches(&cookies, live_id, &missing, config).await;
+        if !result.failures.is_empty() {
+            return Err(result.failures.remove(0).error);
+        }
+        sku_list.extend(result.sku_list);
     }
 
-    Err(data.error_msg.unwrap_or_else(|| "获取商品详情失败".to_string()))
+    let sku_list = order_skus_by_ids(&sku_ids, sku_list);
+    if sku_list.len() < sku_ids.len() {
+        warn!("[查询商品] 部分商品京东未识别，已跳过");
+    }
+    Ok(sku_list)
+}
+
+/// 用缓存的商品详情补全 sku_id 列表
+fn resolve_cached_skus(sku_ids: &[String]) -> Result<Vec<SkuInfo>, String> {
+    let mut missing = Vec::new();
+    let sku_list: Vec<SkuInfo> = sku_ids
+        .iter()
+        .filter_map(|id| {
+            let cached = SKU_INFO_CACHE.get(id);
+            if cached.is_none() {
+                missing.push(id.as_str());
+            }
+            cached
+        })
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "商品详情未缓存或已过期，请重新获取: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(sku_list)
 }
 
 /// 批量添加商品到购物袋
+/// sku_ids 与 sku_list 二选一：传 sku_ids 时由后端用 get_sku_info_by_file 缓存的详情补全
+/// 两者都未传或商品详情未缓存时返回 InvalidParam 错误
 #[tauri::command]
 pub async fn add_sku_to_bag_batch(
     cookies: Vec<Cookie>,
     live_id: i64,
+    sku_list: Option<Vec<SkuInfo>>,
+    sku_ids: Option<Vec<String>>,
+    config: Option<RequestConfig>,
+) -> Result<AddSkuResult, JdApiError> {
+    let sku_list = match (sku_list, sku_ids) {
+        (Some(sku_list), _) => sku_list,
+        (None, Some(sku_ids)) => {
+            resolve_cached_skus(&sku_ids).map_err(JdApiError::invalid_param)?
+        }
+        (None, None) => return Err(JdApiError::invalid_param("缺少 sku_list 或 sku_ids 参数")),
+    };
+    info!(
+        "[批量添加商品] 直播间: {}, 商品数量: {}",
+        live_id,
+        sku_list.len()
+    );
+
+    Ok(add_sku_list(&cookies, live_id, sku_list, config).await?)
+}
+
+/// 提交完整商品详情到购物袋
+async fn add_sku_list(
+    cookies: &[Cookie],
+    live_id: i64,
     sku_list: Vec<SkuInfo>,
+    config: Option<RequestConfig>,
 ) -> Result<AddSkuResult, String> {
-    info!("[批量添加商品] 直播间: {}, 商品数量: {}", live_id, sku_list.len());
-
     if sku_list.is_empty() {
         return Ok(AddSkuResult {
             success: true,
             success_count: 0,
             error_msg: None,
+            failed_items: Vec::new(),
         });
     }
 
-    let cookie_str = cookies_to_string(&cookies);
-    let url = "https://drlives.jd.com/live-shopping-bag/sku/add";
+    let cookie_str = cookies_to_string(cookies);
+    let url = SKU_ADD_API.direct_url(&[])?;
 
     let request = AddSkuBatchRequest {
         live_id: live_id.to_string(),
@@ -1096,47 +4572,1033 @@ pub async fn add_sku_to_bag_batch(
         sku_list: sku_list.clone(),
     };
 
-    let client = reqwest::Client::new();
-    let headers = build_create_live_headers(&cookie_str);
-
-    let response = client
-        .post(url)
-        .headers(headers)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
-
-    let response_text = response
-        .text()
-        .await
-        .map_err(|e| format!("读取响应失败: {}", e))?;
+    let builder = SKU_ADD_API.request(&url, &cookie_str)?.json(&request);
+    let response = send_with_retry(builder, config.unwrap_or_default()).await?;
 
-    info!("[批量添加商品] 响应长度: {} 字符", response_text.len());
+    let response_text = read_response_text(response, "批量添加商品").await?;
 
     // 尝试解析响应
-    let data: AddSkuBatchResponse = serde_json::from_str(&response_text)
-        .map_err(|e| format!("解析响应失败: {}", e))?;
+    let data: AddSkuBatchResponse = parse_response(&response_text)?;
+    let result = summarize_add_sku_result(&sku_list, data);
+
+    info!(
+        "[批量添加商品] 成功添加 {} 个商品，失败 {} 个",
+        result.success_count,
+        result.failed_items.len()
+    );
+    Ok(result)
+}
 
-    // 判断是否成功
-    // 成功时：返回 skuList（echo back），没有 success 字段或 success=null
-    // 失败时：success=false，有 errorMsg
+/// 汇总批量添加结果
+/// 成功时：返回 skuList（echo back），没有 success 字段或 success=null，
+/// 其中带 forbiddenReason / errorTips 的商品视为添加失败
+/// 失败时：success=false，有 errorMsg，通常没有单个商品明细
+fn summarize_add_sku_result(submitted: &[SkuInfo], data: AddSkuBatchResponse) -> AddSkuResult {
     if let Some(false) = data.success {
         // 明确失败
-        return Ok(AddSkuResult {
+        return AddSkuResult {
             success: false,
             success_count: 0,
             error_msg: data.error_msg,
-        });
+            failed_items: Vec::new(),
+        };
     }
 
-    // 成功（返回了 skuList 或没有明确失败）
-    let success_count = data.sku_list.map(|list| list.len() as i32).unwrap_or(sku_list.len() as i32);
-    info!("[批量添加商品] 成功添加 {} 个商品", success_count);
+    // 没有返回 skuList 时按全部成功处理
+    let Some(echoed) = data.sku_list else {
+        return AddSkuResult {
+            success: true,
+            success_count: submitted.len() as i32,
+            error_msg: None,
+            failed_items: Vec::new(),
+        };
+    };
+
+    let mut failed_items: Vec<FailedSku> = echoed
+        .iter()
+        .filter_map(|sku| {
+            [&sku.forbidden_reason, &sku.error_tips]
+                .into_iter()
+                .flatten()
+                .find(|reason| !reason.trim().is_empty())
+                .map(|reason| FailedSku {
+                    sku_id: sku.sku.clone(),
+                    reason: reason.clone(),
+                })
+        })
+        .collect();
+    let success_count = (echoed.len() - failed_items.len()) as i32;
+
+    // 提交了但没有回显的商品
+    failed_items.extend(
+        submitted
+            .iter()
+            .filter(|s| !echoed.iter().any(|e| e.sku == s.sku))
+            .map(|s| FailedSku {
+                sku_id: s.sku.clone(),
+                reason: "未返回添加结果".to_string(),
+            }),
+    );
 
-    Ok(AddSkuResult {
+    AddSkuResult {
         success: true,
         success_count,
         error_msg: None,
+        failed_items,
+    }
+}
+
+// ============ 调试相关 ============
+
+/// 京东接口原始响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawJdResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// 调试用：以标准京东请求头发送任意请求，原样返回状态码和响应体（仅调试版本可用）
+/// 用于京东接口结构变化导致类型化命令失效时快速排查
+#[tauri::command]
+pub async fn debug_jd_request(
+    cookies: Vec<Cookie>,
+    url: String,
+    method: String,
+    body: Option<String>,
+    config: Option<RequestConfig>,
+) -> Result<RawJdResponse, String> {
+    if !cfg!(debug_assertions) && !DEBUG_MODE.load(Ordering::Relaxed) {
+        return Err("调试命令仅在调试版本或调试模式下可用".to_string());
+    }
+
+    // 只允许请求京东域名，避免 Cookie 被发往其他站点
+    let parsed = reqwest::Url::parse(&url).map_err(|e| format!("URL 格式错误: {}", e))?;
+    let host = parsed.host_str().unwrap_or_default();
+    if host != "jd.com" && !host.ends_with(".jd.com") {
+        return Err(format!("只允许请求京东域名: {}", host));
+    }
+
+    let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| format!("不支持的请求方法: {}", method))?;
+
+    info!("[调试请求] {} {}", method, url);
+
+    let cookie_str = cookies_to_string(&cookies);
+    let client = http::client();
+    let headers = build_headers(&cookie_str)?;
+
+    let mut request = client.request(method, parsed).headers(headers);
+    if let Some(body) = body {
+        request = request.body(body);
+    }
+
+    let response = send_with_retry(request, config.unwrap_or_default()).await?;
+
+    let status = response.status().as_u16();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("读取响应失败: {}", e))?;
+
+    info!(
+        "[调试请求] 状态码: {}, 响应长度: {} 字符",
+        status,
+        body.len()
+    );
+
+    Ok(RawJdResponse { status, body })
+}
+
+/// 按字段名筛选商品 JSON（字段名使用京东返回的驼峰格式，如 skuStatus）
+fn select_sku_fields(
+    value: serde_json::Value,
+    fields: &[String],
+) -> Result<serde_json::Value, String> {
+    let serde_json::Value::Object(mut map) = value else {
+        return Err("商品数据格式错误".to_string());
+    };
+
+    if let Some(unknown) = fields.iter().find(|f| !map.contains_key(f.as_str())) {
+        return Err(format!("未知字段: {}", unknown));
+    }
+
+    map.retain(|key, _| fields.iter().any(|f| f == key));
+    Ok(serde_json::Value::Object(map))
+}
+
+/// 导出商品详情为 JSON 文件（供外部脚本使用）
+/// fields 为空时导出全部字段，否则只保留指定字段
+#[tauri::command]
+pub async fn export_skus_json(
+    sku_list: Vec<SkuInfo>,
+    output_path: String,
+    fields: Option<Vec<String>>,
+) -> Result<String, String> {
+    utils::validate_output_path(&output_path, "json")?;
+    info!(
+        "[导出商品] 商品数量: {}, 输出路径: {}",
+        sku_list.len(),
+        output_path
+    );
+
+    let mut items = Vec::with_capacity(sku_list.len());
+    for sku in &sku_list {
+        let value = serde_json::to_value(sku).map_err(|e| format!("序列化商品失败: {}", e))?;
+        let value = match &fields {
+            Some(fields) if !fields.is_empty() => select_sku_fields(value, fields)?,
+            _ => value,
+        };
+        items.push(value);
+    }
+
+    let json_content =
+        serde_json::to_string_pretty(&items).map_err(|e| format!("序列化商品失败: {}", e))?;
+
+    tokio::fs::write(&output_path, json_content)
+        .await
+        .map_err(|e| format!("写入文件失败: {}", e))?;
+
+    info!(
+        "[导出商品] 已导出 {} 个商品到: {}",
+        items.len(),
+        output_path
+    );
+    Ok(output_path)
+}
+
+/// FNV-1a 64 位哈希（算法固定，跨版本、跨进程结果一致）
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(PRIME)
     })
 }
+
+/// 计算购物袋内容指纹，用于低成本判断购物袋是否变化
+/// 与商品在列表中的先后无关，但 sort、top 变化会改变指纹
+#[tauri::command]
+pub fn bag_fingerprint(sku_list: Vec<SkuInfo>) -> String {
+    let mut entries: Vec<String> = sku_list
+        .iter()
+        .map(|sku| {
+            format!(
+                "{}|{}|{}|{}|{}",
+                sku.sku,
+                sku.price.as_deref().unwrap_or_default(),
+                sku.stock_state.map(|v| v.to_string()).unwrap_or_default(),
+                sku.sort.as_deref().unwrap_or_default(),
+                sku.top.map(|v| v.to_string()).unwrap_or_default(),
+            )
+        })
+        .collect();
+    entries.sort();
+
+    format!("{:016x}", fnv1a_64(entries.join("\n").as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn sku(value: serde_json::Value) -> SkuInfo {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// 指纹与列表顺序无关
+    #[test]
+    fn test_bag_fingerprint_order_independent() {
+        let a = sku(serde_json::json!({ "sku": "1001", "price": "9.90" }));
+        let b = sku(serde_json::json!({ "sku": "1002", "price": "19.90" }));
+
+        assert_eq!(
+            bag_fingerprint(vec![a.clone(), b.clone()]),
+            bag_fingerprint(vec![b, a])
+        );
+    }
+
+    /// 排序和置顶变化会改变指纹
+    #[test]
+    fn test_bag_fingerprint_sort_and_top_sensitive() {
+        let base = sku(serde_json::json!({ "sku": "1001", "sort": "1", "top": 0 }));
+        let resorted = sku(serde_json::json!({ "sku": "1001", "sort": "2", "top": 0 }));
+        let topped = sku(serde_json::json!({ "sku": "1001", "sort": "1", "top": 1 }));
+
+        let fingerprint = bag_fingerprint(vec![base]);
+        assert_ne!(fingerprint, bag_fingerprint(vec![resorted]));
+        assert_ne!(fingerprint, bag_fingerprint(vec![topped]));
+    }
+
+    /// 混合成功/失败的响应按商品拆出失败明细
+    #[test]
+    fn test_summarize_add_sku_result_mixed() {
+        let submitted = vec![
+            sku(serde_json::json!({ "sku": "1001" })),
+            sku(serde_json::json!({ "sku": "1002" })),
+            sku(serde_json::json!({ "sku": "1003" })),
+        ];
+        let data: AddSkuBatchResponse = serde_json::from_value(serde_json::json!({
+            "skuList": [
+                { "sku": "1001" },
+                { "sku": "1002", "forbiddenReason": "商品已下架" },
+                { "sku": "1003", "errorTips": "超出限购数量", "forbiddenReason": "" }
+            ]
+        }))
+        .unwrap();
+
+        let result = summarize_add_sku_result(&submitted, data);
+        assert!(result.success);
+        assert_eq!(result.success_count, 1);
+        let failed: Vec<_> = result
+            .failed_items
+            .iter()
+            .map(|f| (f.sku_id.as_str(), f.reason.as_str()))
+            .collect();
+        assert_eq!(failed, [("1002", "商品已下架"), ("1003", "超出限购数量")]);
+    }
+
+    /// 只返回整体失败时没有明细
+    #[test]
+    fn test_summarize_add_sku_result_overall_failure() {
+        let submitted = vec![sku(serde_json::json!({ "sku": "1001" }))];
+        let data: AddSkuBatchResponse = serde_json::from_value(serde_json::json!({
+            "success": false,
+            "errorMsg": "直播间不存在"
+        }))
+        .unwrap();
+
+        let result = summarize_add_sku_result(&submitted, data);
+        assert!(!result.success);
+        assert_eq!(result.error_msg.as_deref(), Some("直播间不存在"));
+        assert!(result.failed_items.is_empty());
+    }
+
+    #[test]
+    fn test_order_skus_by_ids() {
+        let sku_list = vec![
+            sku(serde_json::json!({ "sku": "2" })),
+            sku(serde_json::json!({ "sku": "1" })),
+        ];
+        let ids = ["1", "3", "2", "1"].map(String::from);
+        let ordered: Vec<_> = order_skus_by_ids(&ids, sku_list)
+            .into_iter()
+            .map(|sku| sku.sku)
+            .collect();
+        assert_eq!(ordered, ["1", "2"]);
+    }
+
+    #[test]
+    fn test_resolve_cached_skus() {
+        SKU_INFO_CACHE.insert(
+            "2001",
+            sku(serde_json::json!({ "sku": "2001", "title": "测试商品", "price": "9.9" })),
+        );
+
+        let resolved = resolve_cached_skus(&["2001".to_string()]).unwrap();
+        assert_eq!(resolved[0].title.as_deref(), Some("测试商品"));
+        assert_eq!(resolved[0].to_brief().price.as_deref(), Some("9.9"));
+
+        let err = resolve_cached_skus(&["2001".to_string(), "2999".to_string()]).unwrap_err();
+        assert!(err.contains("2999") && !err.contains("2001"));
+    }
+
+    #[test]
+    fn test_live_detail_error() {
+        assert_eq!(
+            live_detail_error("123", Some(1), Some("直播不存在".to_string())),
+            "直播间 123 不存在"
+        );
+        let err = live_detail_error("123", Some(1), Some("您无权操作该直播".to_string()));
+        assert!(err.starts_with("无权访问直播间 123"));
+        assert_eq!(
+            live_detail_error("123", Some(500), None),
+            "获取直播间详情失败（错误码: Some(500)）"
+        );
+    }
+
+    #[test]
+    fn test_explain_tracker_finish() {
+        let tracker = ExplainTracker::default();
+        // 没有开始记录（如应用重启后）时返回 0
+        assert_eq!(tracker.finish("1", "sku-a"), 0);
+
+        tracker.begin("1", "sku-a");
+        assert_eq!(tracker.finish("1", "sku-b"), 0);
+        assert!(tracker.current("1").is_some());
+
+        tracker.begin("1", "sku-b");
+        assert_eq!(tracker.current("1").unwrap().sku_id, "sku-b");
+        tracker.finish("1", "sku-b");
+        assert!(tracker.current("1").is_none());
+    }
+
+    #[test]
+    fn test_normalize_image_url() {
+        assert_eq!(
+            normalize_image_url("//img10.360buyimg.com/a.jpg"),
+            "https://img10.360buyimg.com/a.jpg"
+        );
+        assert_eq!(
+            normalize_image_url("https://img10.360buyimg.com/a.jpg"),
+            "https://img10.360buyimg.com/a.jpg"
+        );
+        assert_eq!(
+            normalize_image_url("http://img10.360buyimg.com/a.jpg"),
+            "http://img10.360buyimg.com/a.jpg"
+        );
+        assert_eq!(
+            normalize_image_url("jfs/t1/a.jpg"),
+            "https://img30.360buyimg.com/jfs/t1/a.jpg"
+        );
+        assert_eq!(
+            normalize_image_url(" /jfs/t1/a.jpg "),
+            "https://img30.360buyimg.com/jfs/t1/a.jpg"
+        );
+        assert_eq!(
+            normalize_image_url("data:image/png;base64,AA"),
+            "data:image/png;base64,AA"
+        );
+        assert_eq!(normalize_image_url(""), "");
+    }
+
+    #[test]
+    fn test_cookies_to_string_skips_invalid() {
+        let cookie = |name: &str, value: &str| Cookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: ".jd.com".to_string(),
+            path: "/".to_string(),
+            expires: None,
+            is_secure: false,
+            is_http_only: false,
+            same_site: None,
+        };
+        let cookies = vec![
+            cookie("pt_key", "AAJ-abc=="),
+            cookie("bad", "a;b"),
+            cookie("newline", "a\r\nX-Injected: 1"),
+            cookie("pt pin", "x"),
+            cookie("pt_pin", "jd_%E4%B8%AD"),
+        ];
+
+        let cookie_str = cookies_to_string(&cookies);
+        assert_eq!(cookie_str, "pt_key=AAJ-abc==; pt_pin=jd_%E4%B8%AD");
+        assert!(build_headers(&cookie_str).is_ok());
+        assert!(cookie_header_value("a=\u{7f}").is_err());
+    }
+
+    #[test]
+    fn test_build_checklist() {
+        let login = || {
+            Ok(JdLoginResult {
+                is_logged_in: true,
+                nickname: Some("主播".to_string()),
+                avatar: None,
+                pin: Some("jd_test".to_string()),
+            })
+        };
+        let detail: LiveDetail = serde_json::from_value(serde_json::json!({
+            "title": "测试直播",
+            "indexImage": "https://a/1.jpg",
+            "resizeIndexImage": "https://a/2.jpg",
+            "squareIndexImage": "https://a/3.jpg",
+            "skuNum": 0
+        }))
+        .unwrap();
+
+        let checklist = build_checklist(login(), Ok(detail));
+        assert!(checklist.login.passed && checklist.live_room.passed);
+        assert!(!checklist.shopping_bag.passed);
+        assert_eq!(checklist.cover.message, "封面缺少比例: 3:4");
+        assert!(!checklist.all_passed);
+
+        // 单项失败不影响其他项
+        let checklist = build_checklist(login(), Err("直播间 1 不存在".to_string()));
+        assert!(checklist.login.passed);
+        assert_eq!(checklist.live_room.message, "直播间 1 不存在");
+    }
+
+    fn room(live_id: &str, status: i32) -> RecentLiveRoom {
+        serde_json::from_value(serde_json::json!({ "liveId": live_id, "status": status })).unwrap()
+    }
+
+    /// 空列表和页码越界时返回空列表
+    #[test]
+    fn test_paginate_live_rooms_out_of_range() {
+        let empty = paginate_live_rooms(vec![], Some(1), Some(10), None);
+        assert_eq!(empty.total, 0);
+        assert!(empty.items.is_empty());
+
+        let rooms = vec![room("1", 1), room("2", 1), room("3", 2)];
+        let beyond = paginate_live_rooms(rooms, Some(5), Some(2), None);
+        assert_eq!(beyond.total, 3);
+        assert!(beyond.items.is_empty());
+    }
+
+    /// 先按状态过滤再分页
+    #[test]
+    fn test_paginate_live_rooms_status_filter() {
+        let rooms = vec![room("1", 1), room("2", 2), room("3", 1), room("4", 1)];
+        let page = paginate_live_rooms(rooms, Some(2), Some(2), Some(1));
+
+        assert_eq!(page.total, 3);
+        let ids: Vec<_> = page
+            .items
+            .iter()
+            .filter_map(|r| r.live_id.as_deref())
+            .collect();
+        assert_eq!(ids, ["4"]);
+    }
+
+    /// 识别 PNG 尺寸并匹配封面比例
+    #[test]
+    fn test_png_dimensions_and_ratio() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&800u32.to_be_bytes());
+        png.extend_from_slice(&600u32.to_be_bytes());
+
+        assert_eq!(image_dimensions(&png), Some((800, 600)));
+        assert_eq!(match_cover_ratio(800, 600), Some("fourToThree"));
+        assert_eq!(match_cover_ratio(750, 1000), Some("threeToFour"));
+        assert_eq!(match_cover_ratio(1920, 1080), None);
+    }
+
+    /// 本地 mock server：按顺序返回给定状态码（用完后重复最后一个），返回地址和请求计数
+    async fn mock_server(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let status = statuses[n.min(statuses.len() - 1)];
+
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                    status
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, hits)
+    }
+
+    fn fast_retry() -> RequestConfig {
+        RequestConfig {
+            timeout_ms: 2_000,
+            max_retries: 2,
+            retry_delay_ms: 1,
+        }
+    }
+
+    /// 间歇性 500 在重试后成功
+    #[tokio::test]
+    async fn test_send_with_retry_recovers_from_500() {
+        let (url, hits) = mock_server(vec![500, 500, 200]).await;
+
+        let response = send_with_retry(reqwest::Client::new().get(&url), fast_retry())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    /// 重试用尽后错误信息包含尝试次数
+    #[tokio::test]
+    async fn test_send_with_retry_reports_attempts() {
+        let (url, hits) = mock_server(vec![503]).await;
+
+        let err = send_with_retry(reqwest::Client::new().get(&url), fast_retry())
+            .await
+            .unwrap_err();
+
+        assert!(err.contains("共尝试 3 次"), "{}", err);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    /// 4xx 不重试
+    #[tokio::test]
+    async fn test_send_with_retry_skips_client_errors() {
+        let (url, hits) = mock_server(vec![404, 200]).await;
+
+        let response = send_with_retry(reqwest::Client::new().get(&url), fast_retry())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 404);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    /// 非 2xx 响应不读取响应体，429 标记为限流
+    #[tokio::test]
+    async fn test_read_response_text_checks_status() {
+        let (url, _) = mock_server(vec![429, 502, 200]).await;
+        let client = reqwest::Client::new();
+
+        let response = client.get(&url).send().await.unwrap();
+        let err = read_response_text(response, "测试").await.unwrap_err();
+        assert_eq!(err.kind, JdError::RateLimited);
+        assert!(err.message.contains("429"), "{}", err);
+
+        let response = client.get(&url).send().await.unwrap();
+        let err = read_response_text(response, "测试").await.unwrap_err();
+        assert_eq!(err.kind, JdError::Request);
+        assert!(err.message.contains("502"), "{}", err);
+
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(read_response_text(response, "测试").await.unwrap(), "ok");
+    }
+
+    #[test]
+    fn test_general_data_amount_in_yuan() {
+        let response: GeneralDataResponse = serde_json::from_str(
+            r#"{"success":true,"code":0,"data":{"orderCount":3,"orderAmount":12345}}"#,
+        )
+        .unwrap();
+        let data = response.into_result().unwrap();
+        assert_eq!(data.order_amount, Some(123.45));
+        assert_eq!(data.amount_unit, "yuan");
+
+        let response: GeneralDataResponse =
+            serde_json::from_str(r#"{"success":true,"code":0,"data":{}}"#).unwrap();
+        assert_eq!(response.into_result().unwrap().order_amount, None);
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(Some(3), None, None), JdError::NotLoggedIn);
+        assert_eq!(classify(Some(1), Some(403), None), JdError::Forbidden);
+        assert_eq!(
+            classify(Some(1), None, Some("操作太频繁，请稍后再试")),
+            JdError::RateLimited
+        );
+        assert_eq!(
+            classify(Some(500), None, Some("系统繁忙")),
+            JdError::Unknown(Some(500))
+        );
+
+        let error = JdApiError::business(Some(1), None, Some(String::new()), "添加失败");
+        assert_eq!(error.message, "添加失败");
+        assert_eq!(error.to_string(), "添加失败");
+    }
+
+    #[test]
+    fn test_diff_sku_snapshots() {
+        let snapshot = |price: &str, stock: i32, status: &str| SkuSnapshot {
+            price: Some(price.to_string()),
+            stock_state: Some(stock),
+            sku_status: Some(status.to_string()),
+        };
+        let previous = HashMap::from([
+            ("1".to_string(), snapshot("9.90", 33, "1")),
+            ("2".to_string(), snapshot("19.90", 33, "1")),
+            ("3".to_string(), snapshot("29.90", 33, "1")),
+        ]);
+        let current = HashMap::from([
+            ("1".to_string(), snapshot("8.90", 33, "1")),
+            ("2".to_string(), snapshot("19.90", 34, "0")),
+        ]);
+
+        let changes = diff_sku_snapshots(&previous, &current);
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.sku_id.as_str(), c.field, c.off_shelf))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("1", "price", false),
+                ("2", "stock_state", false),
+                ("2", "sku_status", true),
+                ("3", "sku_status", true),
+            ]
+        );
+        assert_eq!(changes[0].new_value.as_deref(), Some("8.90"));
+        assert!(diff_sku_snapshots(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_build_sku_xlsx() {
+        let bytes = build_sku_xlsx(&["100012043978".to_string()]).unwrap();
+        // xlsx 为 zip 格式
+        assert!(bytes.starts_with(b"PK\x03\x04"));
+        assert!(bytes.len() < 64 * 1024);
+    }
+
+    #[test]
+    fn test_collect_explaining() {
+        let skus = [
+            sku(serde_json::json!({ "sku": "1001", "explainStatus": "1" })),
+            sku(serde_json::json!({ "sku": "1002", "explainStatus": "0" })),
+            sku(serde_json::json!({ "sku": "1003", "explainBegin": 1 })),
+        ];
+        let tracked = CurrentExplain {
+            sku_id: "1003".to_string(),
+            started_at: 1_700_000_000_000,
+            elapsed_ms: 0,
+        };
+
+        let explaining = collect_explaining(&skus, Some(&tracked));
+        let ids: Vec<_> = explaining.iter().map(|s| s.sku_id.as_str()).collect();
+        assert_eq!(ids, ["1001", "1003"]);
+        assert_eq!(explaining[0].started_at, None);
+        assert_eq!(explaining[1].started_at, Some(1_700_000_000_000));
+
+        assert!(collect_explaining(&skus[1..2], None).is_empty());
+    }
+
+    #[test]
+    fn test_build_headers_from_profile() {
+        use reqwest::header;
+
+        let user_agent = http::user_agent();
+        let headers = build_create_live_headers("a=1").unwrap();
+        assert_eq!(headers[header::COOKIE], "a=1");
+        assert_eq!(headers[header::USER_AGENT], user_agent.as_str());
+        assert_eq!(headers[header::REFERER], "https://jlive.jd.com/");
+        assert_eq!(
+            headers[header::CONTENT_TYPE],
+            "application/json; charset=UTF-8"
+        );
+
+        let headers = build_upload_headers("a=1").unwrap();
+        assert_eq!(headers[header::HOST], "drlives.jd.com");
+        assert!(!headers.contains_key(header::CONTENT_TYPE));
+
+        let headers = build_form_headers("a=1").unwrap();
+        assert_eq!(headers[header::REFERER], "https://jlive.jd.com/");
+        assert_eq!(
+            headers[header::CONTENT_TYPE],
+            "application/x-www-form-urlencoded"
+        );
+
+        let headers = build_headers("a=1").unwrap();
+        assert_eq!(headers[header::REFERER], "https://drlives.jd.com/");
+        assert_eq!(headers[header::ACCEPT], "application/json, text/plain, */*");
+    }
+
+    #[test]
+    fn test_parse_push_info() {
+        assert_eq!(
+            split_push_url("rtmp://push.jd.com/live/abc123?auth_key=xyz"),
+            Some((
+                "rtmp://push.jd.com/live".to_string(),
+                "abc123?auth_key=xyz".to_string()
+            ))
+        );
+        assert_eq!(split_push_url("rtmp://push.jd.com/live/"), None);
+        assert_eq!(split_push_url("rtmp://push.jd.com"), None);
+
+        let response =
+            |success: bool, error_msg: Option<&str>, data: Option<PushInfo>| PushInfoResponse {
+                success,
+                code: if success { Some(0) } else { Some(500) },
+                error_msg: error_msg.map(str::to_string),
+                data,
+            };
+
+        let info = PushInfo {
+            push_url: Some("rtmp://push.jd.com/live".to_string()),
+            stream_key: Some("key001".to_string()),
+        };
+        let (push_url, stream_key) =
+            parse_push_info("1", response(true, None, Some(info))).unwrap();
+        assert_eq!(push_url, "rtmp://push.jd.com/live");
+        assert_eq!(stream_key, "key001");
+
+        let err =
+            parse_push_info("1", response(false, Some("直播未到开播时间"), None)).unwrap_err();
+        assert!(err.message.contains("暂不可推流"), "{}", err.message);
+        let err = parse_push_info("1", response(true, None, None)).unwrap_err();
+        assert!(err.message.contains("暂不可推流"), "{}", err.message);
+        let err = parse_push_info("1", response(false, Some("系统繁忙"), None)).unwrap_err();
+        assert_eq!(err.message, "系统繁忙");
+    }
+
+    #[test]
+    fn test_build_comment_page() {
+        let comment = |id: &str, content: &str| LiveComment {
+            comment_id: Some(id.to_string()),
+            nick_name: Some("观众".to_string()),
+            content: Some(content.to_string()),
+            create_time: None,
+        };
+        let data = CommentListData {
+            list: Some(vec![
+                comment("1", "主播好"),
+                comment("2", "  "),
+                comment("3", "加微信 ABC"),
+                comment("4", "这个多少钱"),
+            ]),
+            last_id: None,
+        };
+
+        let page = build_comment_page(data, Some("0".to_string()), &["abc".to_string()]);
+        let ids: Vec<_> = page
+            .comments
+            .iter()
+            .map(|c| c.comment_id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["1", "4"]);
+        assert_eq!(page.filtered_count, 1);
+        assert_eq!(page.last_id.as_deref(), Some("4"));
+
+        let empty = CommentListData {
+            list: None,
+            last_id: None,
+        };
+        let page = build_comment_page(empty, Some("4".to_string()), &[]);
+        assert!(page.comments.is_empty());
+        assert_eq!(page.last_id.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_active_cookies_requires_login() {
+        let active = ActiveCookies::default();
+        assert_eq!(active.get().unwrap_err().kind, JdError::NotLoggedIn);
+
+        *active.cookies.lock().unwrap() = Some(Arc::new(Vec::new()));
+        assert!(active.get().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_login_cache_key_uses_session_cookie() {
+        let cookie = |name: &str, value: &str| Cookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: ".jd.com".to_string(),
+            path: "/".to_string(),
+            expires: None,
+            is_secure: false,
+            is_http_only: false,
+            same_site: None,
+        };
+        let a = [cookie("pt_key", "k1"), cookie("__jda", "1")];
+        let b = [cookie("pt_key", "k1"), cookie("__jda", "2")];
+        let c = [cookie("pt_key", "k2"), cookie("__jda", "1")];
+        assert_eq!(login_cache_key(&a), login_cache_key(&b));
+        assert_ne!(login_cache_key(&a), login_cache_key(&c));
+    }
+
+    #[test]
+    fn test_parse_error_hides_response_by_default() {
+        let text = r#"{"pin":"jd_user_123","success":"#;
+        let err = parse_response::<serde_json::Value>(text).unwrap_err();
+        assert!(err.contains("响应长度"), "{}", err);
+        assert!(!err.contains("jd_user_123"), "{}", err);
+    }
+
+    #[test]
+    fn test_next_expiry_step() {
+        let now = 1_700_000_000;
+        assert_eq!(
+            next_expiry_step(now + 7200, now, false),
+            ExpiryStep::Wait(3600)
+        );
+        assert_eq!(
+            next_expiry_step(now + 600, now, false),
+            ExpiryStep::ExpiringSoon(600)
+        );
+        assert_eq!(
+            next_expiry_step(now + 600, now, true),
+            ExpiryStep::Wait(600)
+        );
+        assert_eq!(next_expiry_step(now, now, true), ExpiryStep::Expired);
+        assert_eq!(next_expiry_step(now - 10, now, false), ExpiryStep::Expired);
+    }
+
+    #[test]
+    fn test_update_live_fields_skip_none() {
+        let patch = UpdateLiveRequest {
+            title: Some("新标题".to_string()),
+            square_index_image: Some("https://img.example.com/1.jpg".to_string()),
+            ..Default::default()
+        };
+        let fields = update_live_fields(&patch).unwrap();
+        let keys: Vec<_> = fields.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["squareIndexImage", "title"]);
+
+        assert!(update_live_fields(&UpdateLiveRequest::default()).is_err());
+        let blank_title = UpdateLiveRequest {
+            title: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert!(update_live_fields(&blank_title).is_err());
+    }
+
+    #[test]
+    fn test_plan_sku_order() {
+        let ids = |list: &[&str]| list.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let bag = ids(&["1", "2", "3", "4"]);
+
+        let plan = plan_sku_order(&bag, &ids(&["3", "1"])).unwrap();
+        assert_eq!(plan.sku_ids, ["3", "1", "2", "4"]);
+        assert_eq!(plan.appended, ["2", "4"]);
+
+        let err = plan_sku_order(&bag, &ids(&["3", "5"])).unwrap_err();
+        assert!(err.contains('5'), "{}", err);
+        assert!(plan_sku_order(&bag, &ids(&["1", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_author_permissions() {
+        let info = serde_json::json!({
+            "pin": "jd_user",
+            "liveAuth": 1,
+            "canSale": "0",
+            "shopId": "10086",
+        });
+        let permissions = parse_author_permissions(&info);
+        assert_eq!(permissions.pin.as_deref(), Some("jd_user"));
+        assert_eq!(permissions.can_create_live, Some(true));
+        assert_eq!(permissions.can_sell, Some(false));
+        assert_eq!(permissions.identity, AuthorIdentity::Merchant);
+        assert!(!permissions.degraded);
+
+        // 老接口只返回昵称、头像、pin
+        let legacy = serde_json::json!({ "name": "主播", "pic": "", "pin": "jd_user" });
+        let permissions = parse_author_permissions(&legacy);
+        assert_eq!(permissions.can_create_live, None);
+        assert_eq!(permissions.identity, AuthorIdentity::Unknown);
+        assert!(permissions.degraded);
+    }
+
+    #[test]
+    fn test_author_permissions_from_response() {
+        let response = |json| serde_json::from_value::<AuthorInfoRawResponse>(json).unwrap();
+
+        // 成功但没有 authorInfo：降级而不是报未登录
+        let permissions = author_permissions_from_response(response(serde_json::json!({
+            "success": true,
+            "code": 0,
+        })))
+        .unwrap();
+        assert!(permissions.degraded);
+        assert_eq!(permissions.pin, None);
+        assert_eq!(permissions.can_sell, None);
+
+        // 业务失败按错误码分类
+        let error = author_permissions_from_response(response(serde_json::json!({
+            "success": false,
+            "code": 429,
+            "errorMsg": "请求太多",
+        })))
+        .unwrap_err();
+        assert_eq!(error.kind, JdError::RateLimited);
+        assert_eq!(error.message, "请求太多");
+
+        let error = author_permissions_from_response(response(serde_json::json!({
+            "success": false,
+            "code": 3,
+        })))
+        .unwrap_err();
+        assert_eq!(error.kind, JdError::NotLoggedIn);
+
+        let error = author_permissions_from_response(response(serde_json::json!({
+            "success": false,
+            "code": 500,
+        })))
+        .unwrap_err();
+        assert_eq!(error.kind, JdError::Unknown(Some(500)));
+        assert_eq!(error.message, "查询主播权限失败");
+    }
+
+    #[test]
+    fn test_endpoints_build_urls() {
+        assert_eq!(
+            PUSH_INFO_API.direct_url(&["a b"]).unwrap(),
+            "https://drlives.jd.com/live/live-push-info?liveId=a%20b"
+        );
+        assert!(COMMENT_LIST_API.direct_url(&[]).is_err());
+        assert_eq!(COMMENT_LIST_API.host(), "api.m.jd.com");
+
+        for (command, spec) in ENDPOINTS {
+            assert!(find_endpoint(command).is_ok());
+            assert!(reqwest::Method::from_bytes(spec.method.as_bytes()).is_ok());
+            assert!((spec.build_headers)("a=1").is_ok(), "{}", command);
+        }
+
+        // 网关接口的描述与命令走同一地址构建逻辑
+        let description = describe_request_config("get_live_comments".to_string()).unwrap();
+        assert!(description
+            .url
+            .starts_with("https://api.m.jd.com/live_pc_getCommentList?appid="));
+        assert!(
+            description.url.ends_with("&body=%7B%7D"),
+            "{}",
+            description.url
+        );
+        assert_eq!(description.timeout_ms, RequestConfig::default().timeout_ms);
+        assert!(description.experimental);
+
+        // 原有抓包接口与推测的实验性接口区分标记
+        assert!(!find_endpoint("create_live_room").unwrap().experimental);
+        assert!(find_endpoint("start_live").unwrap().experimental);
+    }
+
+    #[test]
+    fn test_parse_remove_outcome() {
+        let response = |success: bool, error_msg: Option<&str>| SkuOperationResponse {
+            success,
+            code: if success { 0 } else { 1 },
+            error_msg: error_msg.map(str::to_string),
+        };
+
+        assert!(parse_remove_outcome(response(true, None)).unwrap());
+        assert!(!parse_remove_outcome(response(false, Some("商品不在购物袋中"))).unwrap());
+        let err = parse_remove_outcome(response(false, Some("系统繁忙"))).unwrap_err();
+        assert_eq!(err.message, "系统繁忙");
+    }
+
+    /// 多批中一批失败时，其余批次的商品照常返回，失败批次单独列出
+    #[tokio::test]
+    async fn test_fetch_sku_batches_reports_failed_batch() {
+        let (ok_url, _) = mock_server(vec![200]).await;
+        let (fail_url, _) = mock_server(vec![500]).await;
+
+        let sku_ids: Vec<String> = (0..250).map(|i| (1000 + i).to_string()).collect();
+        let bad_id = sku_ids[150].clone();
+        let result = fetch_sku_batches(&sku_ids, |batch| {
+            let url = if batch.contains(&bad_id) {
+                &fail_url
+            } else {
+                &ok_url
+            };
+            async move {
+                let response =
+                    send_with_retry(reqwest::Client::new().get(url), fast_retry()).await?;
+                read_response_text(response, "测试").await?;
+                Ok(batch
+                    .iter()
+                    .map(|id| sku(serde_json::json!({ "sku": id })))
+                    .collect())
+            }
+        })
+        .await;
+
+        assert_eq!(result.batch_count, 3);
+        assert_eq!(result.sku_list.len(), 150);
+        assert_eq!(result.sku_list[100].sku, "1200");
+        assert_eq!(result.failures.len(), 1);
+        let failed = &result.failures[0];
+        assert_eq!(failed.index, 1);
+        assert_eq!(failed.sku_ids.first().map(String::as_str), Some("1100"));
+        assert_eq!(failed.sku_ids.len(), 100);
+        assert!(
+            failed.error.message.contains("第 2/3 批"),
+            "{}",
+            failed.error.message
+        );
+    }
+}
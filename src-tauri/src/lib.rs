@@ -1,26 +1,54 @@
 //! Tauri 应用入口模块
 
-use log::info;
+use log::{info, warn};
+use tauri::{Emitter, Manager};
 
 // 功能模块
+mod cache;
+mod config;
 mod cookie;
+mod countdown;
 mod crypto;
+mod http;
 mod jd;
+mod logging;
 mod screen;
+mod sign;
+mod tasks;
 mod utils;
 
 // 重新导出供其他模块使用
-pub use cookie::{get_chrome_profiles, read_chrome_cookies_cdp, Cookie};
+pub use cookie::{
+    get_chrome_profiles, read_chrome_cookies_cdp, Browser, Cookie, CookieNameFilter,
+    ReadCookieOptions,
+};
+pub use sign::{set_signer, SignParams, SignRequest, Signer};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 禁用 WebView2 GPU 加速，让 OBS 可以正常捕获窗口内容
-    // SAFETY: 在程序启动时设置环境变量，此时只有主线程运行
-    unsafe {
-        std::env::set_var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS", "--disable-gpu");
+    // 不使用 OBS 的用户可通过 set_webview_gpu 启用 GPU 加速（重启后生效）
+    if utils::read_disable_webview_gpu() {
+        // SAFETY: 在程序启动时设置环境变量，此时只有主线程运行
+        unsafe {
+            std::env::set_var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS", "--disable-gpu");
+        }
     }
 
     tauri::Builder::default()
+        // 单实例：重复启动时聚焦已有主窗口，并把启动参数转交给前端（需最先注册）
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            info!("检测到应用已在运行，启动参数: {:?}", args);
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit(
+                "app-already-running",
+                serde_json::json!({ "args": args, "cwd": cwd }),
+            );
+        }))
         .plugin(
             tauri_plugin_log::Builder::new()
                 .target(tauri_plugin_log::Target::new(
@@ -34,6 +62,33 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(jd::ExplainTracker::default())
+        .manage(jd::ActiveCookies::default())
+        .manage(tasks::TaskManager::default())
+        .setup(|app| {
+            // 日志插件初始化后再检测 WebView2 版本，确保警告能输出
+            if let Err(e) = utils::detect_webview2_runtime() {
+                warn!("{}", e);
+            }
+
+            // 确定数据目录（配置、Cookie 等文件都保存在此目录下）
+            if let Err(e) = utils::init_data_dir(app.handle()) {
+                warn!("初始化数据目录失败: {}", e);
+            }
+
+            // 加载应用配置并应用代理、超时、日志级别等设置
+            if let Err(e) = config::load() {
+                warn!("加载应用配置失败，使用默认配置: {}", e);
+            }
+
+            // 环境变量中配置了密钥时替换默认密钥
+            match crypto::init_key_from_env(crypto::KEY_ENV_VAR) {
+                Ok(()) => info!("已从环境变量 {} 加载加密密钥", crypto::KEY_ENV_VAR),
+                Err(crypto::CryptoError::KeyNotFound) => {}
+                Err(e) => warn!("加载加密密钥失败，使用默认密钥: {}", e),
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // utils 模块
             utils::greet,
@@ -41,37 +96,120 @@ pub fn run() {
             utils::async_operation,
             utils::get_machine_code,
             utils::get_app_dir,
+            utils::get_webview2_version,
+            utils::set_webview_gpu,
             utils::save_cookies_to_file,
             utils::load_cookies_from_file,
+            utils::export_cookies_netscape,
             utils::http_post,
+            utils::http_get,
+            utils::http_request_full,
             utils::http_post_encrypted,
+            utils::benchmark_crypto,
             utils::save_live_sessions,
             utils::load_live_sessions,
+            utils::export_sessions_to_xlsx,
+            utils::set_account_note,
+            utils::get_account_notes,
+            // config 模块
+            config::load_config,
+            config::save_config,
+            // cache 模块
+            cache::invalidate_cache,
+            cache::get_cache_status,
             // cookie 模块
             cookie::get_browser_profiles,
             cookie::read_chrome_cookies,
+            cookie::get_account_pin,
+            cookie::parse_cookie_string,
+            cookie::find_jd_logged_in_profile,
+            cookie::jd_qrcode_login,
+            cookie::jd_qrcode_check,
+            // http 模块
+            http::rebuild_http_client,
+            http::set_proxy,
+            http::set_user_agent,
             // jd 模块
+            jd::check_cookie_coverage,
+            jd::describe_request_config,
             jd::verify_jd_login,
+            jd::get_author_permissions,
+            jd::check_cookies_valid,
             jd::get_recent_live_rooms,
             jd::create_live_room,
+            jd::update_live_room,
+            jd::start_live,
+            jd::end_live_room,
+            jd::get_live_detail,
+            jd::pre_live_checklist,
+            jd::get_author_templates,
             jd::upload_sku,
+            jd::upload_sku_json,
             jd::add_sku_to_bag,
+            jd::remove_sku_from_bag,
+            jd::set_sku_top,
+            jd::reorder_skus,
+            jd::set_active_cookies,
             jd::get_live_general_data,
+            jd::get_live_general_data_cached,
+            jd::start_general_data_polling,
+            jd::stop_general_data_polling,
+            jd::watch_sku_changes,
+            jd::stop_watch_sku_changes,
             jd::get_h5_url,
+            jd::get_stream_info,
+            jd::get_live_comments,
+            jd::get_live_comments_cached,
             jd::start_explain,
             jd::end_explain,
+            jd::get_current_explain,
+            jd::get_current_explaining,
             jd::get_cover_images,
+            jd::set_room_cover,
+            jd::upload_cover_image,
             jd::get_sku_info_by_file,
+            jd::query_sku_detail,
             jd::add_sku_to_bag_batch,
+            jd::export_skus_json,
+            jd::bag_fingerprint,
+            jd::set_upload_size_limit,
+            jd::set_debug_mode,
+            logging::set_log_verbosity,
+            jd::debug_jd_request,
+            // countdown 模块
+            countdown::start_countdown,
+            countdown::pause_countdown,
+            countdown::resume_countdown,
+            countdown::stop_countdown,
+            // sign 模块
+            sign::set_h5st_signature,
+            sign::clear_h5st_signatures,
+            // tasks 模块
+            tasks::cancel_task,
+            tasks::list_tasks,
             // screen 模块
             screen::create_screen_window,
             screen::close_screen_window,
             screen::start_dragging_window,
+            screen::set_window_always_on_top,
+            screen::set_ignore_cursor_events,
+            screen::set_window_opacity,
+            screen::set_window_decorations,
+            screen::set_script_content,
             screen::read_image_as_base64,
             screen::get_window_state,
+            screen::save_window_state,
+            screen::capture_window,
+            screen::list_monitors,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // 退出时取消所有后台任务
+            if let tauri::RunEvent::Exit = event {
+                app.state::<tasks::TaskManager>().cancel_all();
+            }
+        });
 
     info!("应用启动完成");
 }
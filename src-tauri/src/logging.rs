@@ -0,0 +1,191 @@
+//! 接口响应日志模块
+//!
+//! 京东接口返回中可能含账号 pin、手机号、金额等隐私信息，日志会同时输出到 stdout 和 webview。
+//! 默认只记录状态码和响应长度；排查问题时可切换为完整响应，但敏感字段仍会掩码。
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config;
+
+/// 响应日志详细程度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogVerbosity {
+    /// 只记录状态码和响应长度（默认）
+    #[default]
+    Summary,
+    /// 记录脱敏后的完整响应
+    Full,
+}
+
+/// 是否记录完整响应
+static FULL_RESPONSE_LOG: AtomicBool = AtomicBool::new(false);
+
+/// 需要掩码的字段名后缀（忽略大小写），如 pin / ptPin / cpsPrice / orderAmount
+const SENSITIVE_KEY_SUFFIXES: &[&str] = &[
+    "pin",
+    "phone",
+    "mobile",
+    "tel",
+    "price",
+    "amount",
+    "money",
+    "gmv",
+    "pushurl",
+    "streamkey",
+    "ticket",
+    "token",
+];
+
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SENSITIVE_KEY_SUFFIXES
+        .iter()
+        .any(|suffix| key.ends_with(suffix))
+}
+
+/// 掩码字符串：保留首尾各一个字符
+pub fn mask_str(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    match chars.len() {
+        0 => String::new(),
+        1..=2 => "***".to_string(),
+        n => format!("{}***{}", chars[0], chars[n - 1]),
+    }
+}
+
+/// 掩码文本中的手机号（1 开头的 11 位连续数字，保留前 3 位和后 4 位）
+fn mask_phone_numbers(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        let digits = &chars[start..i];
+        if digits.len() == 11 && digits[0] == '1' {
+            result.extend(&digits[..3]);
+            result.push_str("****");
+            result.extend(&digits[7..]);
+        } else {
+            result.extend(digits);
+        }
+    }
+    result
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match field {
+                    Value::String(s) if is_sensitive_key(key) => *s = mask_str(s),
+                    Value::Number(_) if is_sensitive_key(key) => {
+                        *field = Value::String("***".to_string())
+                    }
+                    _ => redact_value(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::String(s) => *s = mask_phone_numbers(s),
+        _ => {}
+    }
+}
+
+/// 对响应内容脱敏：JSON 按字段名掩码敏感字段，其余文本只掩码手机号
+pub fn redact(text: &str) -> String {
+    match serde_json::from_str::<Value>(text) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => mask_phone_numbers(text),
+    }
+}
+
+/// 脱敏后截取前 max_chars 个字符，用于错误信息中附带响应片段（被截断时以 ... 结尾）
+pub fn redacted_snippet(text: &str, max_chars: usize) -> String {
+    let redacted = redact(text);
+    match redacted.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &redacted[..end]),
+        None => redacted,
+    }
+}
+
+/// 是否记录完整响应（日志级别为 Full）
+pub fn full_response_log() -> bool {
+    FULL_RESPONSE_LOG.load(Ordering::Relaxed)
+}
+
+/// 按当前日志级别记录接口响应
+pub fn log_response(tag: &str, status: u16, text: &str) {
+    if FULL_RESPONSE_LOG.load(Ordering::Relaxed) {
+        info!("[{}] 响应状态码: {}，响应: {}", tag, status, redact(text));
+    } else {
+        info!(
+            "[{}] 响应状态码: {}，响应长度: {} 字节",
+            tag,
+            status,
+            text.len()
+        );
+    }
+}
+
+/// 应用响应日志详细程度
+pub fn apply_log_verbosity(level: LogVerbosity) {
+    FULL_RESPONSE_LOG.store(level == LogVerbosity::Full, Ordering::Relaxed);
+    info!("[日志] 响应日志级别: {:?}", level);
+}
+
+/// 设置响应日志详细程度并保存到配置（排查问题时临时开启 full）
+#[tauri::command]
+pub fn set_log_verbosity(level: LogVerbosity) -> Result<(), String> {
+    apply_log_verbosity(level);
+    config::update(|config| config.log_verbosity = level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let text = r#"{"authorInfo":{"name":"主播","pin":"jd_user01","ptPin":"ab"},"data":[{"cpsPrice":"19.90","orderAmount":1024,"desc":"联系 13812345678"}],"shoppingBag":1}"#;
+        let redacted: Value = serde_json::from_str(&redact(text)).unwrap();
+
+        assert_eq!(redacted["authorInfo"]["name"], "主播");
+        assert_eq!(redacted["authorInfo"]["pin"], "j***1");
+        assert_eq!(redacted["authorInfo"]["ptPin"], "***");
+        assert_eq!(redacted["data"][0]["cpsPrice"], "1***0");
+        assert_eq!(redacted["data"][0]["orderAmount"], "***");
+        assert_eq!(redacted["data"][0]["desc"], "联系 138****5678");
+        assert_eq!(redacted["shoppingBag"], 1);
+
+        assert_eq!(
+            redact("<html>13812345678 / 123456789012</html>"),
+            "<html>138****5678 / 123456789012</html>"
+        );
+    }
+
+    #[test]
+    fn test_redacted_snippet() {
+        assert_eq!(redacted_snippet("手机 13812345678", 20), "手机 138****5678");
+        assert_eq!(redacted_snippet("手机 13812345678", 5), "手机 13...");
+        assert_eq!(
+            redacted_snippet(r#"{"ticket":"AAAABBBB"}"#, 100),
+            r#"{"ticket":"A***B"}"#
+        );
+    }
+}
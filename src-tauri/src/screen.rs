@@ -1,14 +1,46 @@
 //! 投屏窗口功能模块
 
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
 use tauri::{Emitter, Manager};
 
+use crate::jd;
+use crate::utils;
+
 /// 窗口状态信息
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct WindowState {
     pub x: f64,
     pub y: f64,
     pub width: f64,
     pub height: f64,
+    /// 窗口所在显示器名称（旧版本保存的状态中没有此字段）
+    #[serde(default)]
+    pub monitor_name: Option<String>,
+}
+
+/// 读取窗口当前位置和尺寸（逻辑像素），窗口不存在时返回 None
+/// 按窗口实际所在显示器的缩放比例换算，多显示器 DPI 不同时也能得到正确坐标
+fn current_window_state(app: &tauri::AppHandle, label: &str) -> Option<WindowState> {
+    let window = app.get_webview_window(label)?;
+    let monitor = window.current_monitor().ok().flatten();
+    let scale = monitor
+        .as_ref()
+        .map(|m| m.scale_factor())
+        .or_else(|| window.scale_factor().ok())
+        .unwrap_or(1.0);
+
+    let pos = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+
+    Some(WindowState {
+        x: pos.x as f64 / scale,
+        y: pos.y as f64 / scale,
+        width: size.width as f64 / scale,
+        height: size.height as f64 / scale,
+        monitor_name: monitor.and_then(|m| m.name().cloned()),
+    })
 }
 
 /// 获取窗口当前位置和尺寸（逻辑像素）
@@ -17,48 +49,350 @@ pub async fn get_window_state(
     app: tauri::AppHandle,
     label: String,
 ) -> Result<Option<WindowState>, String> {
-    if let Some(window) = app.get_webview_window(&label) {
-        let scale = app
-            .primary_monitor()
-            .ok()
-            .flatten()
-            .map(|m| m.scale_factor())
-            .unwrap_or(1.0);
-
-        let pos = window.outer_position().ok();
-        let size = window.inner_size().ok();
-
-        if let (Some(p), Some(s)) = (pos, size) {
-            return Ok(Some(WindowState {
-                x: p.x as f64 / scale,
-                y: p.y as f64 / scale,
-                width: s.width as f64 / scale,
-                height: s.height as f64 / scale,
-            }));
+    Ok(current_window_state(&app, &label))
+}
+
+/// 窗口状态保存文件路径（数据目录下 data/window_states.json）
+fn window_states_path() -> Result<std::path::PathBuf, String> {
+    Ok(utils::get_data_dir()?
+        .join("data")
+        .join("window_states.json"))
+}
+
+/// 读取所有已保存的窗口状态（按 label 区分）
+fn read_window_states() -> Result<HashMap<String, WindowState>, String> {
+    let file_path = window_states_path()?;
+
+    // 如果文件不存在，返回空表
+    if !file_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content =
+        std::fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析窗口状态失败: {}", e))
+}
+
+/// 保存指定窗口的当前状态
+fn persist_window_state(app: &tauri::AppHandle, label: &str) -> Result<(), String> {
+    let state = current_window_state(app, label).ok_or_else(|| format!("窗口不存在: {}", label))?;
+
+    let mut states = read_window_states()?;
+    states.insert(label.to_string(), state);
+
+    let file_path = window_states_path()?;
+    if let Some(data_dir) = file_path.parent() {
+        std::fs::create_dir_all(data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+
+    let json_content =
+        serde_json::to_string_pretty(&states).map_err(|e| format!("序列化窗口状态失败: {}", e))?;
+    utils::write_file_atomic(&file_path, &json_content)?;
+
+    info!("[投屏窗口] 已保存窗口状态: {}", label);
+    Ok(())
+}
+
+/// 直播脚本窗口的默认 label
+const SCRIPT_WINDOW_LABEL: &str = "screen-script";
+
+/// script-switch 事件内容（script 为空表示该商品没有脚本）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSwitch {
+    pub label: String,
+    pub sku_id: String,
+    pub script: String,
+}
+
+/// 商品脚本文件路径
+fn scripts_path() -> Result<std::path::PathBuf, String> {
+    Ok(utils::get_data_dir()?.join("data").join("scripts.json"))
+}
+
+/// 读取所有商品脚本（窗口 label -> 商品 ID -> 脚本文本）
+fn read_scripts() -> Result<HashMap<String, HashMap<String, String>>, String> {
+    let file_path = scripts_path()?;
+
+    // 如果文件不存在，返回空表
+    if !file_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content =
+        std::fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析商品脚本失败: {}", e))
+}
+
+/// 各脚本窗口对应商品的切换事件（默认脚本窗口总是包含在内，无脚本时内容为空）
+fn script_switches(
+    scripts: &HashMap<String, HashMap<String, String>>,
+    sku_id: &str,
+) -> Vec<ScriptSwitch> {
+    let mut labels: Vec<&str> = scripts.keys().map(String::as_str).collect();
+    if !labels.contains(&SCRIPT_WINDOW_LABEL) {
+        labels.push(SCRIPT_WINDOW_LABEL);
+    }
+    labels.sort_unstable();
+
+    labels
+        .into_iter()
+        .map(|label| ScriptSwitch {
+            label: label.to_string(),
+            sku_id: sku_id.to_string(),
+            script: scripts
+                .get(label)
+                .and_then(|by_sku| by_sku.get(sku_id))
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// 开始讲解商品时推送 script-switch 事件，让脚本窗口翻到该商品的话术
+pub fn emit_script_switch(app: &tauri::AppHandle, sku_id: &str) {
+    let scripts = read_scripts().unwrap_or_else(|e| {
+        warn!("[直播脚本] 读取商品脚本失败: {}", e);
+        HashMap::new()
+    });
+    for switch in script_switches(&scripts, sku_id) {
+        let _ = app.emit("script-switch", switch);
+    }
+}
+
+/// 设置脚本窗口中某个商品的脚本（script 为空时删除），保存到数据目录
+/// 该商品正在讲解时立即推送 script-switch 事件刷新窗口内容
+#[tauri::command]
+pub async fn set_script_content(
+    app: tauri::AppHandle,
+    label: String,
+    sku_id: String,
+    script: String,
+) -> Result<(), String> {
+    if sku_id.trim().is_empty() {
+        return Err("商品 ID 不能为空".to_string());
+    }
+
+    let mut scripts = read_scripts()?;
+    let by_sku = scripts.entry(label.clone()).or_default();
+    if script.trim().is_empty() {
+        by_sku.remove(&sku_id);
+    } else {
+        by_sku.insert(sku_id.clone(), script.clone());
+    }
+    if by_sku.is_empty() {
+        scripts.remove(&label);
+    }
+
+    let file_path = scripts_path()?;
+    if let Some(data_dir) = file_path.parent() {
+        std::fs::create_dir_all(data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    }
+    let json_content =
+        serde_json::to_string_pretty(&scripts).map_err(|e| format!("序列化商品脚本失败: {}", e))?;
+    utils::write_file_atomic(&file_path, &json_content)?;
+    info!("[直播脚本] 已更新 {} 中商品 {} 的脚本", label, sku_id);
+
+    if app.state::<jd::ExplainTracker>().is_explaining(&sku_id) {
+        let switch = ScriptSwitch {
+            label,
+            sku_id,
+            script,
+        };
+        let _ = app.emit("script-switch", switch);
+    }
+    Ok(())
+}
+
+/// 保存窗口当前位置和尺寸，下次以 restore_state 创建时恢复
+#[tauri::command]
+pub async fn save_window_state(app: tauri::AppHandle, label: String) -> Result<(), String> {
+    persist_window_state(&app, &label)
+}
+
+/// 解析 #RRGGBB 或 #RRGGBBAA 格式的颜色
+fn parse_hex_color(value: &str) -> Result<tauri::window::Color, String> {
+    let invalid = || format!("无效的背景颜色: {}（应为 #RRGGBB 或 #RRGGBBAA）", value);
+
+    let hex = value.trim().strip_prefix('#').ok_or_else(invalid)?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return Err(invalid());
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+
+    let (red, green, blue) = (channel(0)?, channel(2)?, channel(4)?);
+    Ok(tauri::window::Color(red, green, blue, alpha))
+}
+
+/// 显示器信息（位置和尺寸为逻辑像素，与 create_screen_window 的坐标一致）
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+/// 读取所有显示器信息
+fn monitor_infos(app: &tauri::AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let monitors = app
+        .available_monitors()
+        .map_err(|e| format!("获取显示器列表失败: {}", e))?;
+    let primary = app.primary_monitor().ok().flatten();
+
+    Ok(monitors
+        .iter()
+        .map(|m| {
+            let scale = m.scale_factor();
+            let (pos, size) = (m.position(), m.size());
+            MonitorInfo {
+                name: m.name().cloned(),
+                x: pos.x as f64 / scale,
+                y: pos.y as f64 / scale,
+                width: size.width as f64 / scale,
+                height: size.height as f64 / scale,
+                scale_factor: scale,
+                is_primary: primary
+                    .as_ref()
+                    .is_some_and(|p| p.name() == m.name() && p.position() == pos),
+            }
+        })
+        .collect())
+}
+
+/// 获取显示器列表（按系统枚举顺序，下标即 create_screen_window 的 monitor_index）
+#[tauri::command]
+pub async fn list_monitors(app: tauri::AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    monitor_infos(&app)
+}
+
+/// 窗口创建后无法修改的属性，变化时必须重建窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WindowStyle {
+    transparent: bool,
+    decorations: bool,
+}
+
+/// 已创建投屏窗口的样式（按 label 记录）
+static WINDOW_STYLES: LazyLock<Mutex<HashMap<String, WindowStyle>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 就地更新时通知页面的事件，页面据此重新读取参数（不刷新页面，事件监听保持有效）
+const SCREEN_PARAMS_EVENT: &str = "screen-params-update";
+
+/// 投屏页面参数更新事件的负载
+#[derive(Clone, serde::Serialize)]
+struct ScreenParamsUpdate {
+    params: String,
+}
+
+/// 同步已有窗口地址栏 hash 的脚本（只改 hash 不刷新页面，保证之后手动刷新时参数一致）
+fn navigate_script(url: &str) -> String {
+    let hash = url.trim_start_matches('/');
+    format!(
+        "window.location.hash = {};",
+        serde_json::to_string(hash).unwrap_or_default()
+    )
+}
+
+/// 已有窗口是否需要关闭重建（transparent、decorations 变化或样式未知时）
+/// 标题、尺寸、位置、页面参数等变化都走就地更新
+fn needs_rebuild(previous: Option<WindowStyle>, style: WindowStyle) -> bool {
+    previous != Some(style)
+}
+
+/// 就地更新已有窗口的标题、尺寸、位置等属性
+fn update_existing_window(
+    window: &tauri::WebviewWindow,
+    title: &str,
+    (width, height): (f64, f64),
+    position: Option<(f64, f64)>,
+    always_on_top: bool,
+    resizable: bool,
+    fullscreen: bool,
+) -> Result<(), String> {
+    use tauri::{LogicalPosition, LogicalSize};
+
+    window.set_title(title).map_err(|e| e.to_string())?;
+    window
+        .set_always_on_top(always_on_top)
+        .map_err(|e| e.to_string())?;
+    window.set_resizable(resizable).map_err(|e| e.to_string())?;
+    window
+        .set_fullscreen(fullscreen)
+        .map_err(|e| e.to_string())?;
+    if !fullscreen {
+        window
+            .set_size(LogicalSize::new(width, height))
+            .map_err(|e| e.to_string())?;
+        if let Some((px, py)) = position {
+            window
+                .set_position(LogicalPosition::new(px, py))
+                .map_err(|e| e.to_string())?;
         }
     }
-    Ok(None)
+    window.set_focus().map_err(|e| e.to_string())
 }
 
 /// 创建投屏窗口（独立窗口，OBS 可捕获）
+/// 同 label 窗口已存在且 transparent、decorations 未变化时就地更新，不关闭重建
+/// restore_state 为 true 且有保存记录时，使用上次的位置和尺寸
+/// monitor_index 指定时把窗口居中放到该显示器（优先于坐标参数），fullscreen 为 true 时在该显示器全屏
 #[tauri::command]
 pub async fn create_screen_window(
     app: tauri::AppHandle,
     label: String,
     title: String,
-    width: f64,
-    height: f64,
+    mut width: f64,
+    mut height: f64,
     transparent: bool,
     always_on_top: bool,
     decorations: bool,
     resizable: bool,
-    _background_color: String,
+    background_color: String,
     extra_params: Option<String>,
-    x: Option<f64>,
-    y: Option<f64>,
+    mut x: Option<f64>,
+    mut y: Option<f64>,
+    restore_state: Option<bool>,
+    monitor_index: Option<usize>,
+    fullscreen: Option<bool>,
 ) -> Result<(), String> {
     use tauri::{WebviewUrl, WebviewWindowBuilder};
 
+    // 非透明窗口不支持半透明背景，忽略 alpha
+    let mut background = parse_hex_color(&background_color)?;
+    if !transparent {
+        background.3 = 255;
+    }
+
+    if restore_state.unwrap_or(false) {
+        match read_window_states() {
+            Ok(states) => {
+                if let Some(state) = states.get(&label) {
+                    info!("[投屏窗口] 恢复窗口状态: {}", label);
+                    (width, height) = (state.width, state.height);
+                    (x, y) = (Some(state.x), Some(state.y));
+                }
+            }
+            Err(e) => warn!("[投屏窗口] 读取窗口状态失败: {}", e),
+        }
+    }
+
+    if let Some(index) = monitor_index {
+        let monitors = monitor_infos(&app)?;
+        let monitor = monitors
+            .get(index)
+            .ok_or_else(|| format!("显示器不存在: {}（共 {} 个显示器）", index, monitors.len()))?;
+        (width, height) = (width.min(monitor.width), height.min(monitor.height));
+        x = Some(monitor.x + (monitor.width - width) / 2.0);
+        y = Some(monitor.y + (monitor.height - height) / 2.0);
+    }
+
     // 根据 label 决定路由路径
     let route = match label.as_str() {
         "screen-countdown" => "screen-countdown",
@@ -67,10 +401,39 @@ pub async fn create_screen_window(
     };
 
     // 构建窗口 URL（使用 hash 路由兼容性更好）
-    let url = format!("/#/{}?{}", route, extra_params.unwrap_or_default());
+    let params = extra_params.unwrap_or_default();
+    let url = format!("/#/{}?{}", route, params);
 
-    // 如果窗口已存在，先关闭
+    let style = WindowStyle {
+        transparent,
+        decorations,
+    };
     if let Some(existing) = app.get_webview_window(&label) {
+        let previous = WINDOW_STYLES.lock().unwrap().get(&label).copied();
+        if !needs_rebuild(previous, style) {
+            info!("[投屏窗口] 就地更新窗口: {}", label);
+            existing
+                .eval(&navigate_script(&url))
+                .map_err(|e| format!("更新窗口内容失败: {}", e))?;
+            existing
+                .emit_to(
+                    label.as_str(),
+                    SCREEN_PARAMS_EVENT,
+                    ScreenParamsUpdate { params },
+                )
+                .map_err(|e| format!("通知窗口更新参数失败: {}", e))?;
+            return update_existing_window(
+                &existing,
+                &title,
+                (width, height),
+                x.zip(y),
+                always_on_top,
+                resizable,
+                fullscreen.unwrap_or(false),
+            );
+        }
+        // 关键属性变化，只能关闭重建
+        info!("[投屏窗口] 窗口样式变化，重建窗口: {}", label);
         let _ = existing.close();
     }
 
@@ -79,19 +442,32 @@ pub async fn create_screen_window(
         .title(&title)
         .inner_size(width, height)
         .transparent(transparent)
+        .background_color(background)
         .always_on_top(always_on_top)
         .decorations(decorations)
         .resizable(resizable)
         .skip_taskbar(false)
         .visible(true)
-        .focused(true);
+        .focused(true)
+        .fullscreen(fullscreen.unwrap_or(false));
 
     // 如果提供了坐标则设置位置
     if let (Some(px), Some(py)) = (x, y) {
         builder = builder.position(px, py);
     }
 
-    builder.build().map_err(|e| e.to_string())?;
+    let window = builder.build().map_err(|e| e.to_string())?;
+    WINDOW_STYLES.lock().unwrap().insert(label.clone(), style);
+
+    // 关闭时自动保存位置和尺寸
+    let app_handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::CloseRequested { .. } = event {
+            if let Err(e) = persist_window_state(&app_handle, &label) {
+                warn!("[投屏窗口] 保存窗口状态失败: {}", e);
+            }
+        }
+    });
 
     Ok(())
 }
@@ -116,25 +492,435 @@ pub async fn start_dragging_window(app: tauri::AppHandle, label: String) -> Resu
     Ok(())
 }
 
+/// 内容区保持在 content 处时窗口外框应放置的位置（inner、outer 为切换边框后的实际位置）
+fn outer_position_for_content(
+    content: tauri::PhysicalPosition<i32>,
+    inner: tauri::PhysicalPosition<i32>,
+    outer: tauri::PhysicalPosition<i32>,
+) -> tauri::PhysicalPosition<i32> {
+    tauri::PhysicalPosition::new(
+        content.x - (inner.x - outer.x),
+        content.y - (inner.y - outer.y),
+    )
+}
+
+/// 运行时切换窗口边框和标题栏
+/// 切换后保持内容区大小和屏幕位置不变；去掉边框后可通过 start_dragging_window 拖动
+#[tauri::command]
+pub async fn set_window_decorations(
+    app: tauri::AppHandle,
+    label: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("窗口不存在: {}", label))?;
+
+    let inner_size = window.inner_size().map_err(|e| e.to_string())?;
+    let content_position = window.inner_position().map_err(|e| e.to_string())?;
+
+    window.set_decorations(enabled).map_err(|e| e.to_string())?;
+    window.set_size(inner_size).map_err(|e| e.to_string())?;
+
+    let inner = window.inner_position().map_err(|e| e.to_string())?;
+    let outer = window.outer_position().map_err(|e| e.to_string())?;
+    window
+        .set_position(outer_position_for_content(content_position, inner, outer))
+        .map_err(|e| e.to_string())?;
+
+    if let Some(style) = WINDOW_STYLES.lock().unwrap().get_mut(&label) {
+        style.decorations = enabled;
+    }
+    info!("[投屏窗口] {} 边框: {}", label, enabled);
+    Ok(())
+}
+
+#[cfg(windows)]
+mod topmost {
+    /// SetWindowPos 的 hWndInsertAfter 参数：置于所有非置顶窗口之上
+    const HWND_TOPMOST: isize = -1;
+    /// SetWindowPos 的 hWndInsertAfter 参数：取消置顶
+    const HWND_NOTOPMOST: isize = -2;
+    /// 不改变位置、尺寸，不激活窗口
+    const SWP_FLAGS: u32 = 0x0001 | 0x0002 | 0x0010;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn SetWindowPos(
+            hwnd: isize,
+            insert_after: isize,
+            x: i32,
+            y: i32,
+            cx: i32,
+            cy: i32,
+            flags: u32,
+        ) -> i32;
+    }
+
+    /// 用 HWND_TOPMOST 重新插入 Z 序顶端，压过后创建的置顶窗口
+    pub fn reassert(window: &tauri::WebviewWindow, enabled: bool) -> Result<(), String> {
+        let hwnd = window.hwnd().map_err(|e| e.to_string())?.0 as isize;
+        let insert_after = if enabled {
+            HWND_TOPMOST
+        } else {
+            HWND_NOTOPMOST
+        };
+        // SAFETY: hwnd 来自仍存活的 Tauri 窗口，参数均为值类型
+        if unsafe { SetWindowPos(hwnd, insert_after, 0, 0, 0, 0, SWP_FLAGS) } == 0 {
+            return Err(format!(
+                "设置窗口置顶失败: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 运行时切换窗口置顶
+/// Windows 上额外调用 SetWindowPos(HWND_TOPMOST) 把窗口重新提到置顶窗口的最上层
+#[tauri::command]
+pub async fn set_window_always_on_top(
+    app: tauri::AppHandle,
+    label: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("窗口不存在: {}", label))?;
+    window
+        .set_always_on_top(enabled)
+        .map_err(|e| e.to_string())?;
+    #[cfg(windows)]
+    topmost::reassert(&window, enabled)?;
+    info!("[投屏窗口] {} 置顶: {}", label, enabled);
+    Ok(())
+}
+
+/// 设置窗口鼠标穿透（ignore 为 true 时点击会落到下层窗口，需通过主窗口关闭穿透）
+#[tauri::command]
+pub async fn set_ignore_cursor_events(
+    app: tauri::AppHandle,
+    label: String,
+    ignore: bool,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("窗口不存在: {}", label))?;
+    window
+        .set_ignore_cursor_events(ignore)
+        .map_err(|e| e.to_string())?;
+    info!("[投屏窗口] {} 鼠标穿透: {}", label, ignore);
+    Ok(())
+}
+
+/// Windows 分层窗口（整体不透明度）
+#[cfg(windows)]
+mod layered {
+    /// GetWindowLongPtr 的 nIndex 参数：扩展窗口样式
+    const GWL_EXSTYLE: i32 = -20;
+    /// 分层窗口样式
+    const WS_EX_LAYERED: isize = 0x0008_0000;
+    /// SetLayeredWindowAttributes 按 alpha 值设置不透明度
+    const LWA_ALPHA: u32 = 0x0000_0002;
+
+    #[link(name = "user32")]
+    extern "system" {
+        #[cfg_attr(target_pointer_width = "32", link_name = "GetWindowLongW")]
+        fn GetWindowLongPtrW(hwnd: isize, index: i32) -> isize;
+        #[cfg_attr(target_pointer_width = "32", link_name = "SetWindowLongW")]
+        fn SetWindowLongPtrW(hwnd: isize, index: i32, value: isize) -> isize;
+        fn SetLayeredWindowAttributes(hwnd: isize, color_key: u32, alpha: u8, flags: u32) -> i32;
+    }
+
+    /// 加上 WS_EX_LAYERED 样式并设置整体 alpha
+    pub fn set_alpha(window: &tauri::WebviewWindow, alpha: u8) -> Result<(), String> {
+        let hwnd = window.hwnd().map_err(|e| e.to_string())?.0 as isize;
+        // SAFETY: hwnd 来自仍存活的 Tauri 窗口，参数均为值类型
+        unsafe {
+            let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+            if style & WS_EX_LAYERED == 0 {
+                SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED);
+            }
+            if SetLayeredWindowAttributes(hwnd, 0, alpha, LWA_ALPHA) == 0 {
+                return Err(format!(
+                    "设置窗口透明度失败: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(windows))]
+mod layered {
+    pub fn set_alpha(_window: &tauri::WebviewWindow, _alpha: u8) -> Result<(), String> {
+        Err("当前系统不支持调整窗口透明度".to_string())
+    }
+}
+
+/// 将不透明度限制在 0.0-1.0 并换算为 alpha 值
+fn opacity_to_alpha(opacity: f64) -> Result<u8, String> {
+    if opacity.is_nan() {
+        return Err("不透明度无效: NaN".to_string());
+    }
+    Ok((opacity.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+/// 设置窗口整体不透明度（0.0 完全透明 - 1.0 不透明，超出范围会被截断）
+/// 作用于整个窗口（包括网页内容）；transparent 窗口的透明背景仍保持透明，不透明的内容按该值叠加半透明
+/// 目前仅支持 Windows（SetLayeredWindowAttributes）
+#[tauri::command]
+pub async fn set_window_opacity(
+    app: tauri::AppHandle,
+    label: String,
+    opacity: f64,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("窗口不存在: {}", label))?;
+    let alpha = opacity_to_alpha(opacity)?;
+
+    layered::set_alpha(&window, alpha)?;
+    info!("[投屏窗口] {} 不透明度: {}", label, alpha);
+    Ok(())
+}
+
+/// read_image_as_base64 允许的最大文件大小（20MB）
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 根据扩展名推断图片 MIME 类型（魔数无法识别时兜底）
+fn image_mime_from_extension(path: &str) -> Option<&'static str> {
+    match path.to_lowercase() {
+        p if p.ends_with(".png") => Some("image/png"),
+        p if p.ends_with(".jpg") || p.ends_with(".jpeg") => Some("image/jpeg"),
+        p if p.ends_with(".webp") => Some("image/webp"),
+        p if p.ends_with(".gif") => Some("image/gif"),
+        _ => None,
+    }
+}
+
 /// 读取本地图片文件并返回 base64 编码
+/// 超过 20MB 或无法识别为图片时返回错误
 #[tauri::command]
 pub async fn read_image_as_base64(path: String) -> Result<String, String> {
     use base64::{engine::general_purpose::STANDARD, Engine};
     use std::fs;
 
+    // 先检查大小，避免读取超大文件
+    let size = fs::metadata(&path)
+        .map_err(|e| format!("读取文件失败: {}", e))?
+        .len();
+    if size > MAX_IMAGE_BYTES {
+        return Err(format!(
+            "图片文件过大: {:.1}MB（最大 {}MB）",
+            size as f64 / (1024.0 * 1024.0),
+            MAX_IMAGE_BYTES / (1024 * 1024)
+        ));
+    }
+
     // 读取文件
     let data = fs::read(&path).map_err(|e| format!("读取文件失败: {}", e))?;
 
-    // 根据扩展名确定 MIME 类型
-    let mime = match path.to_lowercase() {
-        p if p.ends_with(".png") => "image/png",
-        p if p.ends_with(".jpg") || p.ends_with(".jpeg") => "image/jpeg",
-        p if p.ends_with(".webp") => "image/webp",
-        p if p.ends_with(".gif") => "image/gif",
-        _ => "image/png",
-    };
+    // 优先按文件头识别 MIME 类型，扩展名只作兜底
+    let mime = utils::sniff_image_mime(&data)
+        .or_else(|| image_mime_from_extension(&path))
+        .ok_or_else(|| format!("不支持的图片格式: {}", path))?;
 
     // 编码为 base64 data URL
     let base64_str = STANDARD.encode(&data);
     Ok(format!("data:{};base64,{}", mime, base64_str))
 }
+
+/// 对指定投屏窗口截图，返回 base64 PNG（data URL，与 read_image_as_base64 格式一致）
+/// 截图为窗口实际像素尺寸（含 DPI 缩放）
+#[tauri::command]
+pub async fn capture_window(app: tauri::AppHandle, label: String) -> Result<String, String> {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("窗口不存在: {}", label))?;
+
+    if window.is_minimized().unwrap_or(false) {
+        return Err("窗口已最小化，无法截图".to_string());
+    }
+
+    let window_id = native_window_id(&window)?;
+
+    let png = tokio::task::spawn_blocking(move || capture_own_window(window_id))
+        .await
+        .map_err(|e| format!("截图任务失败: {}", e))??;
+
+    Ok(format!("data:image/png;base64,{}", STANDARD.encode(png)))
+}
+
+/// 窗口在 xcap 中的 id（Windows 为 HWND，X11 为 XID，macOS 为窗口编号）
+/// 按原生句柄匹配，多个窗口标题相同或标题被页面修改时也不会截错窗口
+fn native_window_id(window: &tauri::WebviewWindow) -> Result<u32, String> {
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+
+    let handle = window
+        .window_handle()
+        .map_err(|e| format!("获取窗口句柄失败: {}", e))?;
+    match handle.as_raw() {
+        RawWindowHandle::Win32(handle) => Ok(handle.hwnd.get() as u32),
+        RawWindowHandle::Xlib(handle) => Ok(handle.window as u32),
+        RawWindowHandle::Xcb(handle) => Ok(handle.window.get()),
+        #[cfg(target_os = "macos")]
+        RawWindowHandle::AppKit(_) => appkit_window_number(window),
+        _ => Err("当前窗口系统不支持截图".to_string()),
+    }
+}
+
+/// NSWindow 的窗口编号（与 CGWindowID 一致，xcap 在 macOS 上以此作为窗口 id）
+#[cfg(target_os = "macos")]
+fn appkit_window_number(window: &tauri::WebviewWindow) -> Result<u32, String> {
+    use objc2_app_kit::NSWindow;
+
+    let ns_window = window
+        .ns_window()
+        .map_err(|e| format!("获取窗口句柄失败: {}", e))?
+        .cast::<NSWindow>();
+    // SAFETY: 指针由 Tauri 返回，指向该窗口仍存活的 NSWindow
+    let ns_window = unsafe { ns_window.as_ref() }.ok_or("获取窗口句柄失败: NSWindow 为空")?;
+    Ok(ns_window.windowNumber() as u32)
+}
+
+/// 按窗口 id 查找本进程的窗口并截图，返回 PNG 数据
+fn capture_own_window(window_id: u32) -> Result<Vec<u8>, String> {
+    let pid = std::process::id();
+
+    let window = xcap::Window::all()
+        .map_err(|e| format!("枚举窗口失败: {}", e))?
+        .into_iter()
+        .find(|w| w.pid().is_ok_and(|p| p == pid) && w.id().is_ok_and(|id| id == window_id))
+        .ok_or_else(|| format!("未找到系统窗口: {}", window_id))?;
+
+    let image = window
+        .capture_image()
+        .map_err(|e| format!("截图失败: {}", e))?;
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("编码 PNG 失败: {}", e))?;
+
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_color() {
+        let color = parse_hex_color("#1a2B3c").unwrap();
+        assert_eq!(
+            (color.0, color.1, color.2, color.3),
+            (0x1a, 0x2b, 0x3c, 255)
+        );
+
+        let color = parse_hex_color("#00000080").unwrap();
+        assert_eq!(color.3, 0x80);
+
+        assert!(parse_hex_color("000000").is_err());
+        assert!(parse_hex_color("#12345").is_err());
+        assert!(parse_hex_color("#gg0000").is_err());
+    }
+
+    #[test]
+    fn test_navigate_script() {
+        assert_eq!(
+            navigate_script("/#/screen-content?imageUrl=a%22b"),
+            r##"window.location.hash = "#/screen-content?imageUrl=a%22b";"##
+        );
+        assert!(navigate_script("/#/screen-script?text=\"x\"").contains(r#"text=\"x\""#));
+        assert!(!navigate_script("/#/screen-content").contains("reload"));
+    }
+
+    #[test]
+    fn test_needs_rebuild_only_on_style_change() {
+        let style = WindowStyle {
+            transparent: true,
+            decorations: false,
+        };
+
+        // 参数、尺寸等变化时样式不变，走就地更新
+        assert!(!needs_rebuild(Some(style), style));
+
+        // transparent 或 decorations 变化只能重建
+        let opaque = WindowStyle {
+            transparent: false,
+            ..style
+        };
+        assert!(needs_rebuild(Some(style), opaque));
+        let decorated = WindowStyle {
+            decorations: true,
+            ..style
+        };
+        assert!(needs_rebuild(Some(style), decorated));
+
+        // 没有样式记录的窗口无法确认能否复用
+        assert!(needs_rebuild(None, style));
+    }
+
+    /// 截图依赖系统窗口枚举，无图形环境的 Linux CI 上无法运行
+    #[cfg(any(windows, target_os = "macos"))]
+    #[test]
+    fn test_capture_own_window_unknown_id() {
+        let err = capture_own_window(u32::MAX).unwrap_err();
+        assert!(err.contains("未找到系统窗口"), "{}", err);
+    }
+
+    #[test]
+    fn test_opacity_to_alpha_clamps() {
+        assert_eq!(opacity_to_alpha(0.5).unwrap(), 128);
+        assert_eq!(opacity_to_alpha(1.5).unwrap(), 255);
+        assert_eq!(opacity_to_alpha(-1.0).unwrap(), 0);
+        assert!(opacity_to_alpha(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_outer_position_keeps_content_in_place() {
+        use tauri::PhysicalPosition;
+
+        // 加上标题栏后内容区下移 30，外框应上移 30
+        let content = PhysicalPosition::new(100, 200);
+        let position = outer_position_for_content(
+            content,
+            PhysicalPosition::new(108, 231),
+            PhysicalPosition::new(100, 200),
+        );
+        assert_eq!(position, PhysicalPosition::new(92, 169));
+
+        // 去掉边框后内外位置一致，外框直接放到原内容区位置
+        let position = outer_position_for_content(
+            content,
+            PhysicalPosition::new(92, 169),
+            PhysicalPosition::new(92, 169),
+        );
+        assert_eq!(position, content);
+    }
+
+    #[test]
+    fn test_script_switches_default_window() {
+        assert_eq!(script_switches(&HashMap::new(), "100").len(), 1);
+
+        let mut scripts: HashMap<String, HashMap<String, String>> = HashMap::new();
+        scripts
+            .entry("screen-script-2".to_string())
+            .or_default()
+            .insert("100".to_string(), "话术".to_string());
+
+        let switches = script_switches(&scripts, "100");
+        let contents: Vec<_> = switches
+            .iter()
+            .map(|s| (s.label.as_str(), s.script.as_str()))
+            .collect();
+        assert_eq!(
+            contents,
+            [("screen-script", ""), ("screen-script-2", "话术")]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! api.m.jd.com 网关请求签名模块
+//!
+//! 网关越来越多接口校验 h5st 参数。签名由 Signer 实现生成，构建网关请求时自动附加 t、h5st；
+//! 内置算法完成前，默认的 ManualSigner 使用前端按 functionId 传入的签名作为过渡方案。
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// 网关地址
+pub const GATEWAY_URL: &str = "https://api.m.jd.com";
+
+/// 直播后台使用的 appid
+pub const GATEWAY_APPID: &str = "plat-live-operate";
+
+/// 待签名的网关请求
+pub struct SignRequest<'a> {
+    pub appid: &'a str,
+    pub function_id: &'a str,
+    /// body 参数（JSON 字符串，未编码）
+    pub body: &'a str,
+}
+
+/// 签名结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignParams {
+    /// 签名使用的毫秒时间戳（须与 h5st 内的时间一致，为空时不附加 t 参数）
+    pub t: Option<u64>,
+    pub h5st: String,
+}
+
+impl SignParams {
+    /// 转换为追加到 query 或表单的参数串（以 & 开头）
+    fn to_query(&self) -> String {
+        let mut query = String::new();
+        if let Some(t) = self.t {
+            query.push_str(&format!("&t={}", t));
+        }
+        query.push_str(&format!("&h5st={}", urlencoding::encode(&self.h5st)));
+        query
+    }
+}
+
+/// 网关请求签名器（返回 None 表示该接口不附加签名）
+pub trait Signer: Send + Sync {
+    fn sign(&self, request: &SignRequest) -> Result<Option<SignParams>, String>;
+}
+
+/// 使用前端手动传入签名的签名器（按 functionId 保存）
+#[derive(Default)]
+struct ManualSigner {
+    signatures: RwLock<HashMap<String, SignParams>>,
+}
+
+impl Signer for ManualSigner {
+    fn sign(&self, request: &SignRequest) -> Result<Option<SignParams>, String> {
+        let signatures = self.signatures.read().unwrap();
+        Ok(signatures.get(request.function_id).cloned())
+    }
+}
+
+/// 手动签名表（替换签名器后仍可通过命令修改，但不再生效）
+static MANUAL_SIGNER: LazyLock<Arc<ManualSigner>> = LazyLock::new(Arc::default);
+
+/// 当前签名器（默认使用手动签名）
+static SIGNER: LazyLock<RwLock<Arc<dyn Signer>>> = LazyLock::new(|| {
+    let signer: Arc<dyn Signer> = MANUAL_SIGNER.clone();
+    RwLock::new(signer)
+});
+
+/// 替换签名器（接入 h5st 算法实现时调用）
+pub fn set_signer(signer: Arc<dyn Signer>) {
+    *SIGNER.write().unwrap() = signer;
+    info!("[签名] 已替换签名器");
+}
+
+/// 对网关请求签名，返回要追加的参数串（不签名时为空）
+pub fn sign_query(function_id: &str, body: &str) -> Result<String, String> {
+    let request = SignRequest {
+        appid: GATEWAY_APPID,
+        function_id,
+        body,
+    };
+    let signer = SIGNER.read().unwrap().clone();
+    let params = signer
+        .sign(&request)
+        .map_err(|e| format!("请求签名失败: {}", e))?;
+    Ok(params.map(|p| p.to_query()).unwrap_or_default())
+}
+
+/// 构建网关接口地址（body 为 JSON 参数），有签名时附加 t、h5st
+pub fn gateway_url(function_id: &str, body: &serde_json::Value) -> Result<String, String> {
+    let body = body.to_string();
+    Ok(format!(
+        "{}/{}?appid={}&functionId={}&body={}{}",
+        GATEWAY_URL,
+        function_id,
+        GATEWAY_APPID,
+        function_id,
+        urlencoding::encode(&body),
+        sign_query(function_id, &body)?
+    ))
+}
+
+/// 手动设置网关接口的 h5st 签名（前端算好后传入，h5st 为空时清除该接口的签名）
+/// 签名通常与时间戳绑定，过期后需重新设置
+#[tauri::command]
+pub fn set_h5st_signature(function_id: String, h5st: Option<String>, t: Option<u64>) {
+    let mut signatures = MANUAL_SIGNER.signatures.write().unwrap();
+    match h5st.filter(|s| !s.trim().is_empty()) {
+        Some(h5st) => {
+            info!("[签名] 设置手动签名: {}", function_id);
+            signatures.insert(function_id, SignParams { t, h5st });
+        }
+        None => {
+            info!("[签名] 清除手动签名: {}", function_id);
+            signatures.remove(&function_id);
+        }
+    }
+}
+
+/// 清除所有手动签名
+#[tauri::command]
+pub fn clear_h5st_signatures() {
+    MANUAL_SIGNER.signatures.write().unwrap().clear();
+    info!("[签名] 已清除所有手动签名");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_signature_appended() {
+        let body = serde_json::json!({ "liveId": 1 });
+        let unsigned = gateway_url("test_unsigned", &body).unwrap();
+        assert_eq!(
+            unsigned,
+            "https://api.m.jd.com/test_unsigned?appid=plat-live-operate&functionId=test_unsigned&body=%7B%22liveId%22%3A1%7D"
+        );
+
+        set_h5st_signature(
+            "test_signed".to_string(),
+            Some("a;b".to_string()),
+            Some(1700000000000),
+        );
+        let signed = gateway_url("test_signed", &body).unwrap();
+        assert!(
+            signed.ends_with("&t=1700000000000&h5st=a%3Bb"),
+            "{}",
+            signed
+        );
+
+        set_h5st_signature("test_signed".to_string(), None, None);
+        assert!(!gateway_url("test_signed", &body).unwrap().contains("h5st"));
+    }
+}
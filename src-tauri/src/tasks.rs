@@ -0,0 +1,185 @@
+//! 后台长任务管理模块
+//!
+//! 轮询类长任务统一注册到 TaskManager，前端用返回的任务 id 查询或取消。
+//! 取消通过 CancellationToken 实现，任务在下一个 await 点即停止，不再发送事件。
+
+use log::info;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio_util::sync::CancellationToken;
+
+pub type TaskId = u64;
+
+/// 任务信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub id: TaskId,
+    /// 任务类型，如 general_data_polling、sku_watch
+    pub kind: String,
+    pub description: String,
+    /// 启动时间（Unix 毫秒）
+    pub started_at: u64,
+}
+
+struct TaskEntry {
+    info: TaskInfo,
+    token: CancellationToken,
+}
+
+/// 后台任务管理器（应用启动时注册为 Tauri State）
+#[derive(Default)]
+pub struct TaskManager {
+    next_id: AtomicU64,
+    tasks: Arc<Mutex<HashMap<TaskId, TaskEntry>>>,
+}
+
+impl TaskManager {
+    /// 启动后台任务并登记，返回任务 id（任务本身也会收到自己的 id，用于发送事件）
+    /// 任务可通过传入的 token 感知取消；未检查 token 的任务也会在取消后的下一个 await 点终止
+    /// 任务自行结束时自动从列表移除
+    pub fn spawn_task<F, Fut>(&self, kind: &str, description: String, task: F) -> TaskId
+    where
+        F: FnOnce(TaskId, CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let token = CancellationToken::new();
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        // 先登记再启动，保证任务立即结束时也能正确移除
+        self.tasks.lock().unwrap().insert(
+            id,
+            TaskEntry {
+                info: TaskInfo {
+                    id,
+                    kind: kind.to_string(),
+                    description,
+                    started_at,
+                },
+                token: token.clone(),
+            },
+        );
+
+        let future = task(id, token.clone());
+        let tasks = Arc::clone(&self.tasks);
+        tauri::async_runtime::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = future => {}
+            }
+            tasks.lock().unwrap().remove(&id);
+        });
+
+        id
+    }
+
+    /// 取消指定任务，任务不存在时返回 false
+    pub fn cancel(&self, id: TaskId) -> bool {
+        match self.tasks.lock().unwrap().remove(&id) {
+            Some(entry) => {
+                entry.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 取消指定类型的任务，类型不匹配时返回 false
+    pub fn cancel_kind(&self, id: TaskId, kind: &str) -> bool {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.get(&id).is_none_or(|entry| entry.info.kind != kind) {
+            return false;
+        }
+        if let Some(entry) = tasks.remove(&id) {
+            entry.token.cancel();
+        }
+        true
+    }
+
+    /// 取消全部任务（应用退出时调用）
+    pub fn cancel_all(&self) {
+        for (_, entry) in self.tasks.lock().unwrap().drain() {
+            entry.token.cancel();
+        }
+    }
+
+    /// 运行中的任务（按 id 排序）
+    pub fn list(&self) -> Vec<TaskInfo> {
+        let mut tasks: Vec<_> = self
+            .tasks
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| entry.info.clone())
+            .collect();
+        tasks.sort_by_key(|task| task.id);
+        tasks
+    }
+}
+
+/// 取消后台任务
+#[tauri::command]
+pub fn cancel_task(manager: tauri::State<'_, TaskManager>, id: TaskId) -> Result<(), String> {
+    if !manager.cancel(id) {
+        return Err(format!("任务不存在: {}", id));
+    }
+    info!("[任务] 已取消任务 {}", id);
+    Ok(())
+}
+
+/// 列出运行中的后台任务
+#[tauri::command]
+pub fn list_tasks(manager: tauri::State<'_, TaskManager>) -> Vec<TaskInfo> {
+    manager.list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_task_stops_events() {
+        let manager = TaskManager::default();
+        let events = Arc::new(AtomicUsize::new(0));
+
+        let counter = Arc::clone(&events);
+        let id = manager.spawn_task("test", "计数".to_string(), |_id, _token| async move {
+            loop {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        });
+        assert_eq!(manager.list().len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(manager.cancel(id));
+        assert!(manager.list().is_empty());
+        assert!(!manager.cancel(id));
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let stopped_at = events.load(Ordering::SeqCst);
+        assert!(stopped_at > 0);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(events.load(Ordering::SeqCst), stopped_at);
+    }
+
+    #[tokio::test]
+    async fn test_finished_task_is_removed() {
+        let manager = TaskManager::default();
+        let id = manager.spawn_task("test", "立即结束".to_string(), |_id, _token| async {});
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert!(manager.list().is_empty());
+        assert!(!manager.cancel_kind(id, "test"));
+    }
+}
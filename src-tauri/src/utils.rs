@@ -1,16 +1,58 @@
 //! 通用工具函数模块
 
-use log::info;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tauri::Manager;
 
+use crate::config;
 use crate::cookie::Cookie;
+use crate::crypto;
+use crate::http;
+use crate::logging;
 
-/// 获取机器码（基于系统信息生成唯一标识）
-#[tauri::command]
-pub fn get_machine_code() -> String {
+/// 机器码哈希前缀，避免与其他程序基于同一硬件标识生成的值相同
+const MACHINE_CODE_SALT: &str = "jd-live-assistant-plus:";
+
+/// 读取系统级硬件标识（Windows 为注册表 MachineGuid，Linux 为 /etc/machine-id）
+#[cfg(windows)]
+fn read_hardware_id() -> Option<String> {
+    use winreg::enums::{HKEY_LOCAL_MACHINE, KEY_READ, KEY_WOW64_64KEY};
+    use winreg::RegKey;
+
+    let key = RegKey::predef(HKEY_LOCAL_MACHINE)
+        .open_subkey_with_flags(
+            "SOFTWARE\\Microsoft\\Cryptography",
+            KEY_READ | KEY_WOW64_64KEY,
+        )
+        .ok()?;
+    let guid: String = key.get_value("MachineGuid").ok()?;
+    Some(guid.trim().to_string()).filter(|g| !g.is_empty())
+}
+
+#[cfg(not(windows))]
+fn read_hardware_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// 由硬件标识计算机器码（sha256 十六进制）
+fn hash_hardware_id(id: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(format!("{}{}", MACHINE_CODE_SALT, id.to_lowercase()));
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 旧版机器码（基于主机名、用户名等系统信息），读取不到硬件标识时使用
+fn legacy_machine_code() -> String {
     let mut hasher = DefaultHasher::new();
 
     // 获取主机名
@@ -36,72 +78,362 @@ pub fn get_machine_code() -> String {
     format!("{:016x}", hash)
 }
 
-/// 获取程序运行目录
+/// 获取机器码（基于硬件标识生成唯一标识）
+/// 默认返回 16 位十六进制，full 为 true 时返回完整 64 位 sha256
 #[tauri::command]
-pub fn get_app_dir() -> Result<String, String> {
-    let exe_dir = std::env::current_exe()
+pub fn get_machine_code(full: Option<bool>) -> String {
+    let Some(id) = read_hardware_id() else {
+        warn!("[机器码] 读取硬件标识失败，使用旧版算法");
+        return legacy_machine_code();
+    };
+
+    let code = hash_hardware_id(&id);
+    if full.unwrap_or(false) {
+        code
+    } else {
+        code[..16].to_string()
+    }
+}
+
+/// 已知能正确识别 --disable-gpu 附加参数的最低 WebView2 主版本号
+/// 低于此版本时 OBS 捕获窗口可能仍为黑屏
+const MIN_WEBVIEW2_MAJOR_FOR_GPU_ARGS: u32 = 110;
+
+/// WebView2 运行时信息
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebviewRuntimeInfo {
+    pub version: String,
+    pub gpu_args_supported: bool,
+}
+
+/// 读取 WebView2 运行时版本，版本过低时记录警告
+pub fn detect_webview2_runtime() -> Result<WebviewRuntimeInfo, String> {
+    let version = tauri::webview_version().map_err(|e| format!("获取 WebView2 版本失败: {}", e))?;
+
+    let major = version
+        .split('.')
+        .next()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0);
+    let gpu_args_supported = major >= MIN_WEBVIEW2_MAJOR_FOR_GPU_ARGS;
+
+    if gpu_args_supported {
+        info!("WebView2 版本: {}", version);
+    } else {
+        warn!(
+            "WebView2 版本 {} 低于 {}，禁用 GPU 参数可能无效，OBS 捕获可能黑屏，请更新 WebView2",
+            version, MIN_WEBVIEW2_MAJOR_FOR_GPU_ARGS
+        );
+    }
+
+    Ok(WebviewRuntimeInfo {
+        version,
+        gpu_args_supported,
+    })
+}
+
+/// 获取 WebView2 运行时版本（前端据此提示用户更新 WebView2）
+#[tauri::command]
+pub fn get_webview2_version() -> Result<WebviewRuntimeInfo, String> {
+    detect_webview2_runtime()
+}
+
+/// 是否禁用 WebView2 GPU 加速（默认禁用，保证 OBS 可以捕获窗口内容）
+pub fn read_disable_webview_gpu() -> bool {
+    config::current().disable_webview_gpu
+}
+
+/// 设置是否启用 WebView2 GPU 加速
+/// 环境变量必须在 WebView2 初始化前设置，因此仅在重启后生效；返回值表示是否需要重启
+#[tauri::command]
+pub fn set_webview_gpu(enabled: bool) -> Result<bool, String> {
+    config::update(|config| config.disable_webview_gpu = !enabled)?;
+
+    // 与本次启动时实际生效的设置不同则需要重启
+    let gpu_disabled_now = std::env::var("WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS")
+        .map(|args| args.contains("--disable-gpu"))
+        .unwrap_or(false);
+    let restart_required = gpu_disabled_now == enabled;
+
+    info!(
+        "WebView2 GPU 加速已设置为 {}，需要重启: {}",
+        if enabled { "启用" } else { "禁用" },
+        restart_required
+    );
+    Ok(restart_required)
+}
+
+/// 便携模式标记文件，与 exe 同目录存在时数据保存在 exe 目录
+const PORTABLE_MARKER: &str = "portable.txt";
+
+/// 应用标识（与 tauri.conf.json 的 identifier 一致，用于在 AppHandle 可用前推算应用数据目录）
+const APP_IDENTIFIER: &str = "com.app.jd-live-assistant";
+
+/// 启动时确定的数据目录
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 可执行文件所在目录
+fn exe_dir() -> Result<PathBuf, String> {
+    std::env::current_exe()
         .map_err(|e| format!("获取可执行文件路径失败: {}", e))?
         .parent()
         .map(|p| p.to_path_buf())
-        .ok_or_else(|| "无法获取程序目录".to_string())?;
+        .ok_or_else(|| "无法获取程序目录".to_string())
+}
+
+/// 是否为便携模式
+fn is_portable(exe_dir: &Path) -> bool {
+    exe_dir.join(PORTABLE_MARKER).exists()
+}
+
+/// 把旧版保存在 exe 目录下的 data、cookies 复制到新数据目录（目标已存在时跳过）
+fn migrate_legacy_data(exe_dir: &Path, data_dir: &Path) -> Result<(), String> {
+    use std::fs;
+
+    for name in ["data", "cookies"] {
+        let from = exe_dir.join(name);
+        let to = data_dir.join(name);
+        if !from.is_dir() || to.exists() {
+            continue;
+        }
+
+        fs::create_dir_all(&to).map_err(|e| format!("创建 {} 目录失败: {}", name, e))?;
+        let entries = fs::read_dir(&from).map_err(|e| format!("读取 {} 目录失败: {}", name, e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                fs::copy(&path, to.join(entry.file_name()))
+                    .map_err(|e| format!("复制 {:?} 失败: {}", path, e))?;
+            }
+        }
+        info!("已迁移旧版数据: {:?} -> {:?}", from, to);
+    }
+    Ok(())
+}
+
+/// 启动时确定数据目录（在 setup 中最先调用）
+/// 便携模式使用 exe 目录；安装模式使用可写的应用数据目录，并迁移旧版 exe 目录下的数据
+pub fn init_data_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let exe_dir = exe_dir()?;
+    let data_dir = if is_portable(&exe_dir) {
+        exe_dir
+    } else {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("获取应用数据目录失败: {}", e))?;
+        if let Err(e) = migrate_legacy_data(&exe_dir, &data_dir) {
+            warn!("迁移旧版数据失败: {}", e);
+        }
+        data_dir
+    };
+
+    std::fs::create_dir_all(&data_dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    info!("数据目录: {:?}", data_dir);
+    Ok(DATA_DIR.get_or_init(|| data_dir).clone())
+}
+
+/// 数据根目录（其下为 data、cookies 子目录）
+/// setup 之前（如启动时读取 GPU 设置）按应用标识推算，与 AppHandle 返回的目录一致
+pub fn get_data_dir() -> Result<PathBuf, String> {
+    if let Some(data_dir) = DATA_DIR.get() {
+        return Ok(data_dir.clone());
+    }
+
+    let exe_dir = exe_dir()?;
+    if is_portable(&exe_dir) {
+        return Ok(exe_dir);
+    }
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "无法获取应用数据目录".to_string())
+}
+
+/// 获取数据目录（便携模式为程序目录，安装模式为系统应用数据目录）
+#[tauri::command]
+pub fn get_app_dir() -> Result<String, String> {
+    Ok(get_data_dir()?.to_string_lossy().to_string())
+}
+
+/// 校验前端传入的文件名：只能是单个文件名，不能包含路径分隔符、`..` 或盘符
+/// 防止 `..\evil` 之类的输入把文件写到目标目录之外
+pub(crate) fn validate_file_name(filename: &str) -> Result<&str, String> {
+    let invalid = |reason: &str| Err(format!("文件名无效: {:?}（{}）", filename, reason));
+
+    if filename.trim().is_empty() {
+        return invalid("不能为空");
+    }
+    if filename.contains(['/', '\\']) {
+        return invalid("不能包含路径分隔符");
+    }
+    if filename.contains("..") {
+        return invalid("不能包含 ..");
+    }
+    if filename.contains(':') || filename.chars().any(char::is_control) {
+        return invalid("包含非法字符");
+    }
+    Ok(filename)
+}
+
+/// 校验前端传入的导出路径：必须是绝对路径、不含 `..`、扩展名与导出格式一致且所在目录已存在
+pub(crate) fn validate_output_path(output_path: &str, extension: &str) -> Result<PathBuf, String> {
+    use std::path::Component;
+
+    let path = PathBuf::from(output_path);
+    if !path.is_absolute() {
+        return Err(format!("导出路径必须是绝对路径: {}", output_path));
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(format!("导出路径不能包含 ..: {}", output_path));
+    }
+    let matches_extension = path
+        .extension()
+        .is_some_and(|ext| ext.to_string_lossy().eq_ignore_ascii_case(extension));
+    if !matches_extension {
+        return Err(format!(
+            "导出文件扩展名必须为 .{}: {}",
+            extension, output_path
+        ));
+    }
+    if !path.parent().is_some_and(Path::is_dir) {
+        return Err(format!("导出目录不存在: {}", output_path));
+    }
+    Ok(path)
+}
+
+/// 根据文件头魔数识别图片 MIME 类型
+pub(crate) fn sniff_image_mime(data: &[u8]) -> Option<&'static str> {
+    match data {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Cookie 保存结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveCookiesResult {
+    pub path: String,
+    /// 是否已回读校验通过（未要求校验时为 false）
+    pub verified: bool,
+}
+
+/// 加密 Cookie 文件扩展名
+const ENCRYPTED_COOKIE_EXTENSION: &str = "enc";
+
+/// 加密文件名（扩展名替换为 .enc）
+fn encrypted_cookie_filename(filename: &str) -> std::path::PathBuf {
+    std::path::Path::new(filename).with_extension(ENCRYPTED_COOKIE_EXTENSION)
+}
+
+/// 解析 Cookie 文件内容，兼容旧版明文 JSON（不是 Base64 时按明文解析）
+fn decode_cookie_file(content: &str) -> Result<Vec<Cookie>, String> {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 
-    Ok(exe_dir.to_string_lossy().to_string())
+    let content = content.trim();
+    let json = if BASE64.decode(content).is_ok() {
+        crypto::decrypt(content).map_err(|e| format!("解密 Cookie 失败: {}", e))?
+    } else {
+        content.to_string()
+    };
+
+    serde_json::from_str(&json).map_err(|e| format!("解析 Cookie 失败: {}", e))
 }
 
-/// 保存 Cookie 到本地文件
+/// 回读刚写入的 Cookie 文件，确认内容可以完整还原
+fn verify_saved_cookies(file_path: &std::path::Path, expected: &str) -> Result<(), String> {
+    let content = std::fs::read_to_string(file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+
+    let cookies = decode_cookie_file(&content)?;
+
+    let reserialized =
+        serde_json::to_string_pretty(&cookies).map_err(|e| format!("序列化 Cookie 失败: {}", e))?;
+
+    if reserialized != expected {
+        return Err("文件内容与写入内容不一致".to_string());
+    }
+
+    Ok(())
+}
+
+/// 保存 Cookie 到本地文件（AES-256-GCM 加密，扩展名为 .enc）
+/// 配置中关闭加密存储时按明文 JSON 保存到原文件名
+/// verify 为 true 时写入后立即回读校验
 #[tauri::command]
 pub async fn save_cookies_to_file(
-    app: tauri::AppHandle,
     cookies: Vec<Cookie>,
     filename: String,
-) -> Result<String, String> {
+    verify: Option<bool>,
+) -> Result<SaveCookiesResult, String> {
     use std::fs;
 
-    // 获取程序运行根目录
-    let app_dir = app
-        .path()
-        .resource_dir()
-        .map_err(|e| format!("获取程序目录失败: {}", e))?;
-
-    // 使用可执行文件所在目录
-    let exe_dir = std::env::current_exe()
-        .map_err(|e| format!("获取可执行文件路径失败: {}", e))?
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or(app_dir);
+    let filename = validate_file_name(&filename)?;
 
     // 创建 cookies 文件夹
-    let cookies_dir = exe_dir.join("cookies");
+    let cookies_dir = get_data_dir()?.join("cookies");
     if !cookies_dir.exists() {
         fs::create_dir_all(&cookies_dir).map_err(|e| format!("创建 cookies 目录失败: {}", e))?;
     }
 
-    let file_path = cookies_dir.join(&filename);
+    let encrypted_path = cookies_dir.join(encrypted_cookie_filename(filename));
 
     // 将 Cookie 转换为 JSON 格式
     let json_content =
         serde_json::to_string_pretty(&cookies).map_err(|e| format!("序列化 Cookie 失败: {}", e))?;
 
-    // 写入文件
-    fs::write(&file_path, &json_content).map_err(|e| format!("写入文件失败: {}", e))?;
+    let file_path = if config::current().encrypt_storage {
+        // 加密后写入文件
+        let encrypted =
+            crypto::encrypt(&json_content).map_err(|e| format!("加密 Cookie 失败: {}", e))?;
+        fs::write(&encrypted_path, &encrypted).map_err(|e| format!("写入文件失败: {}", e))?;
+        encrypted_path
+    } else {
+        let file_path = cookies_dir.join(filename);
+        fs::write(&file_path, &json_content).map_err(|e| format!("写入文件失败: {}", e))?;
+        // 读取时优先加密文件，删除旧的加密文件避免读到过期 Cookie
+        if encrypted_path.exists() {
+            fs::remove_file(&encrypted_path).map_err(|e| format!("删除旧的加密文件失败: {}", e))?;
+        }
+        file_path
+    };
 
     info!("Cookie 已保存到: {:?}", file_path);
-    Ok(file_path.to_string_lossy().to_string())
+
+    let verified = if verify.unwrap_or(false) {
+        match verify_saved_cookies(&file_path, &json_content) {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Cookie 文件回读校验失败: {}", e);
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    Ok(SaveCookiesResult {
+        path: file_path.to_string_lossy().to_string(),
+        verified,
+    })
 }
 
 /// 从本地文件读取 Cookie
+/// 优先读取同名的 .enc 加密文件，不存在时读取旧版明文文件
 #[tauri::command]
 pub async fn load_cookies_from_file(filename: String) -> Result<Vec<Cookie>, String> {
     use std::fs;
 
-    // 获取可执行文件所在目录
-    let exe_dir = std::env::current_exe()
-        .map_err(|e| format!("获取可执行文件路径失败: {}", e))?
-        .parent()
-        .map(|p| p.to_path_buf())
-        .ok_or_else(|| "无法获取程序目录".to_string())?;
+    let filename = validate_file_name(&filename)?;
 
-    let file_path = exe_dir.join("cookies").join(&filename);
+    let cookies_dir = get_data_dir()?.join("cookies");
+    let encrypted_path = cookies_dir.join(encrypted_cookie_filename(filename));
+    let file_path = if encrypted_path.exists() {
+        encrypted_path
+    } else {
+        cookies_dir.join(filename)
+    };
 
     // 检查文件是否存在
     if !file_path.exists() {
@@ -109,17 +441,66 @@ pub async fn load_cookies_from_file(filename: String) -> Result<Vec<Cookie>, Str
     }
 
     // 读取文件内容
-    let json_content =
-        fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let content = fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
 
-    // 解析 JSON
-    let cookies: Vec<Cookie> =
-        serde_json::from_str(&json_content).map_err(|e| format!("解析 Cookie 失败: {}", e))?;
+    let cookies = decode_cookie_file(&content)?;
 
     info!("从文件加载了 {} 个 Cookie: {:?}", cookies.len(), file_path);
     Ok(cookies)
 }
 
+/// 转换为 Netscape cookies.txt 格式（curl、yt-dlp 等工具使用）
+/// 每行依次为 domain、子域名标记、path、secure、过期时间、name、value，以 tab 分隔
+/// HttpOnly Cookie 的 domain 加 #HttpOnly_ 前缀；会话 Cookie 过期时间写 0
+fn to_netscape_cookies(cookies: &[Cookie]) -> String {
+    let flag = |value: bool| if value { "TRUE" } else { "FALSE" };
+
+    let mut content = String::from("# Netscape HTTP Cookie File\n\n");
+    for cookie in cookies {
+        let prefix = if cookie.is_http_only {
+            "#HttpOnly_"
+        } else {
+            ""
+        };
+        let path = if cookie.path.is_empty() {
+            "/"
+        } else {
+            &cookie.path
+        };
+        content.push_str(&format!(
+            "{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            prefix,
+            cookie.domain,
+            flag(cookie.domain.starts_with('.')),
+            path,
+            flag(cookie.is_secure),
+            cookie.expires.unwrap_or(0),
+            cookie.name,
+            cookie.value
+        ));
+    }
+    content
+}
+
+/// 导出 Cookie 为 Netscape cookies.txt 文件，返回输出路径
+#[tauri::command]
+pub async fn export_cookies_netscape(
+    cookies: Vec<Cookie>,
+    output_path: String,
+) -> Result<String, String> {
+    validate_output_path(&output_path, "txt")?;
+
+    std::fs::write(&output_path, to_netscape_cookies(&cookies))
+        .map_err(|e| format!("写入文件失败: {}", e))?;
+
+    info!(
+        "[导出 Cookie] 已导出 {} 个 Cookie: {}",
+        cookies.len(),
+        output_path
+    );
+    Ok(output_path)
+}
+
 // 示例：带结构体的 Command
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserInfo {
@@ -148,24 +529,140 @@ pub async fn async_operation(delay_ms: u64) -> Result<String, String> {
     Ok(format!("异步操作完成，延迟 {} 毫秒", delay_ms))
 }
 
-/// 通用 HTTP POST 请求（用于绕过 CORS 限制）
-#[tauri::command]
-pub async fn http_post(url: String, body: String) -> Result<String, String> {
-    let client = reqwest::Client::new();
-    let response = client
-        .post(&url)
-        .header("Content-Type", "application/json")
-        .body(body)
-        .send()
+/// 将前端传入的请求头转换为 HeaderMap，名称或值非法时返回错误
+fn build_custom_headers(
+    headers: Option<HashMap<String, String>>,
+) -> Result<reqwest::header::HeaderMap, String> {
+    use reqwest::header::{HeaderName, HeaderValue};
+
+    let mut header_map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers.unwrap_or_default() {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("无效的请求头名称: {}", name))?;
+        let header_value =
+            HeaderValue::from_str(&value).map_err(|_| format!("请求头 {} 的值无效", name))?;
+        header_map.insert(header_name, header_value);
+    }
+
+    Ok(header_map)
+}
+
+/// 发送请求（可选超时）
+async fn send_with_timeout(
+    request: reqwest::RequestBuilder,
+    timeout_ms: Option<u64>,
+) -> Result<reqwest::Response, String> {
+    let request = match timeout_ms {
+        Some(ms) => request.timeout(std::time::Duration::from_millis(ms)),
+        None => request,
+    };
+
+    request.send().await.map_err(|e| format!("请求失败: {}", e))
+}
+
+/// 发送请求并读取响应文本（可选超时）
+async fn send_custom_request(
+    request: reqwest::RequestBuilder,
+    timeout_ms: Option<u64>,
+) -> Result<String, String> {
+    send_with_timeout(request, timeout_ms)
+        .await?
+        .text()
         .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+        .map_err(|e| format!("读取响应失败: {}", e))
+}
+
+/// 完整的 HTTP 响应
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// 响应头（名称小写；同名多值按出现顺序保存，如多个 Set-Cookie）
+    pub headers: HashMap<String, Vec<String>>,
+    pub body: String,
+}
+
+/// 按名称收集响应头，同名多值保留为数组（非 UTF-8 的值按有损方式转换）
+fn collect_response_headers(headers: &reqwest::header::HeaderMap) -> HashMap<String, Vec<String>> {
+    let mut collected: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, value) in headers {
+        collected
+            .entry(name.as_str().to_string())
+            .or_default()
+            .push(String::from_utf8_lossy(value.as_bytes()).into_owned());
+    }
+    collected
+}
+
+/// 通用 HTTP 请求，返回状态码、响应头和响应体（非 2xx 不视为错误，由前端根据 status 判断）
+/// 有 body 时默认 Content-Type: application/json，可被 headers 覆盖
+#[tauri::command]
+pub async fn http_request_full(
+    method: String,
+    url: String,
+    body: Option<String>,
+    headers: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+) -> Result<HttpResponse, String> {
+    let method = reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| format!("不支持的请求方法: {}", method))?;
+    let headers = build_custom_headers(headers)?;
+
+    let client = http::client();
+    let mut request = client.request(method, &url);
+    if let Some(body) = body {
+        request = request
+            .header("Content-Type", "application/json")
+            .body(body);
+    }
+    let request = request.headers(headers);
 
-    let text = response
+    let response = send_with_timeout(request, timeout_ms).await?;
+    let status = response.status().as_u16();
+    let headers = collect_response_headers(response.headers());
+    let body = response
         .text()
         .await
         .map_err(|e| format!("读取响应失败: {}", e))?;
 
-    Ok(text)
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// 通用 HTTP POST 请求（用于绕过 CORS 限制）
+/// headers 会覆盖默认的 Content-Type: application/json
+#[tauri::command]
+pub async fn http_post(
+    url: String,
+    body: String,
+    headers: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+) -> Result<String, String> {
+    let headers = build_custom_headers(headers)?;
+
+    let client = http::client();
+    let request = client
+        .post(&url)
+        .header("Content-Type", "application/json")
+        .headers(headers)
+        .body(body);
+
+    send_custom_request(request, timeout_ms).await
+}
+
+/// 通用 HTTP GET 请求（用于绕过 CORS 限制）
+#[tauri::command]
+pub async fn http_get(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+) -> Result<String, String> {
+    let headers = build_custom_headers(headers)?;
+
+    let client = http::client();
+    send_custom_request(client.get(&url).headers(headers), timeout_ms).await
 }
 
 /// 加密 HTTP POST 请求
@@ -173,8 +670,6 @@ pub async fn http_post(url: String, body: String) -> Result<String, String> {
 /// 用于卡密校验等敏感接口
 #[tauri::command]
 pub async fn http_post_encrypted(url: String, body: String) -> Result<String, String> {
-    use crate::crypto;
-
     // 1. 加密请求体
     let encrypted_body = crypto::encrypt(&body).map_err(|e| format!("加密请求失败: {}", e))?;
 
@@ -185,7 +680,7 @@ pub async fn http_post_encrypted(url: String, body: String) -> Result<String, St
     .to_string();
 
     // 3. 发送请求
-    let client = reqwest::Client::new();
+    let client = http::client();
     let response = client
         .post(&url)
         .header("Content-Type", "application/json")
@@ -214,55 +709,428 @@ pub async fn http_post_encrypted(url: String, body: String) -> Result<String, St
     }
 }
 
-/// 保存直播场次数据到文件
+/// 吞吐量测试单次数据量上限（64 MB）
+const MAX_BENCHMARK_SAMPLE_BYTES: usize = 64 * 1024 * 1024;
+
+/// 测试本机 AES-GCM 与 ChaCha20 加密吞吐量
+#[tauri::command]
+pub async fn benchmark_crypto(
+    sample_size_bytes: usize,
+    iterations: u32,
+) -> Result<crypto::CryptoBenchmark, String> {
+    if sample_size_bytes == 0 || sample_size_bytes > MAX_BENCHMARK_SAMPLE_BYTES {
+        return Err(format!(
+            "数据量必须在 1 到 {} 字节之间",
+            MAX_BENCHMARK_SAMPLE_BYTES
+        ));
+    }
+    if iterations == 0 {
+        return Err("迭代次数必须大于 0".to_string());
+    }
+
+    // 加密计算耗时较长，放到阻塞线程池执行
+    let result =
+        tokio::task::spawn_blocking(move || crypto::benchmark(sample_size_bytes, iterations))
+            .await
+            .map_err(|e| format!("执行测试失败: {}", e))?
+            .map_err(|e| format!("加密测试失败: {}", e))?;
+
+    info!(
+        "加密吞吐量: AES-GCM {:.1} MB/s, ChaCha20 {:.1} MB/s",
+        result.aes_mb_per_sec, result.chacha_mb_per_sec
+    );
+    Ok(result)
+}
+
+/// 直播场次数据文件路径（数据目录下 data/live_sessions.json）
+fn live_sessions_path() -> Result<std::path::PathBuf, String> {
+    Ok(get_data_dir()?.join("data").join("live_sessions.json"))
+}
+
+/// 在文件名后追加后缀（如 live_sessions.json -> live_sessions.json.bak）
+pub(crate) fn path_with_suffix(path: &std::path::Path, suffix: &str) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    std::path::PathBuf::from(name)
+}
+
+/// 原子写入文件：先写临时文件再 rename 替换，原文件保留为 .bak
+/// 写入中途崩溃时原文件不受影响
+pub(crate) fn write_file_atomic(path: &std::path::Path, content: &str) -> Result<(), String> {
+    use std::fs;
+    use std::io::Write;
+
+    let tmp_path = path_with_suffix(path, ".tmp");
+    let mut file = fs::File::create(&tmp_path).map_err(|e| format!("创建临时文件失败: {}", e))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("写入临时文件失败: {}", e))?;
+    drop(file);
+
+    if path.exists() {
+        fs::copy(path, path_with_suffix(path, ".bak"))
+            .map_err(|e| format!("备份旧文件失败: {}", e))?;
+    }
+
+    fs::rename(&tmp_path, path).map_err(|e| format!("替换文件失败: {}", e))
+}
+
+/// 读取 JSON 文件并校验格式
+fn read_json_file(path: &std::path::Path) -> Result<String, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    serde_json::from_str::<serde_json::Value>(&content)
+        .map_err(|e| format!("文件内容不是合法 JSON: {}", e))?;
+    Ok(content)
+}
+
+/// 保存直播场次数据到文件（原子替换，保留上一版本为 .bak）
 #[tauri::command]
 pub async fn save_live_sessions(sessions_json: String) -> Result<String, String> {
-    use std::fs;
+    // 非法 JSON 拒绝写入，保留旧文件
+    serde_json::from_str::<serde_json::Value>(&sessions_json)
+        .map_err(|e| format!("直播场次数据不是合法 JSON: {}", e))?;
 
-    // 获取可执行文件所在目录
-    let exe_dir = std::env::current_exe()
-        .map_err(|e| format!("获取可执行文件路径失败: {}", e))?
-        .parent()
-        .map(|p| p.to_path_buf())
-        .ok_or_else(|| "无法获取程序目录".to_string())?;
+    let file_path = live_sessions_path()?;
 
     // 创建 data 文件夹
-    let data_dir = exe_dir.join("data");
-    if !data_dir.exists() {
-        fs::create_dir_all(&data_dir).map_err(|e| format!("创建 data 目录失败: {}", e))?;
+    if let Some(data_dir) = file_path.parent() {
+        std::fs::create_dir_all(data_dir).map_err(|e| format!("创建 data 目录失败: {}", e))?;
     }
 
-    let file_path = data_dir.join("live_sessions.json");
-
-    // 写入文件
-    fs::write(&file_path, &sessions_json).map_err(|e| format!("写入文件失败: {}", e))?;
+    write_file_atomic(&file_path, &sessions_json)?;
 
     info!("直播场次数据已保存到: {:?}", file_path);
     Ok(file_path.to_string_lossy().to_string())
 }
 
-/// 从文件加载直播场次数据
+/// 从文件加载直播场次数据，主文件损坏时回退读取 .bak
 #[tauri::command]
 pub async fn load_live_sessions() -> Result<String, String> {
-    use std::fs;
-
-    // 获取可执行文件所在目录
-    let exe_dir = std::env::current_exe()
-        .map_err(|e| format!("获取可执行文件路径失败: {}", e))?
-        .parent()
-        .map(|p| p.to_path_buf())
-        .ok_or_else(|| "无法获取程序目录".to_string())?;
-
-    let file_path = exe_dir.join("data").join("live_sessions.json");
+    let file_path = live_sessions_path()?;
+    let backup_path = path_with_suffix(&file_path, ".bak");
 
     // 如果文件不存在，返回空数组
-    if !file_path.exists() {
+    if !file_path.exists() && !backup_path.exists() {
         return Ok("[]".to_string());
     }
 
-    // 读取文件
-    let content = fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let content = match read_json_file(&file_path) {
+        Ok(content) => content,
+        Err(e) => {
+            warn!("直播场次数据文件损坏（{}），尝试读取备份", e);
+            read_json_file(&backup_path).map_err(|bak_err| {
+                format!("直播场次数据文件损坏: {}，备份也无法读取: {}", e, bak_err)
+            })?
+        }
+    };
 
     info!("直播场次数据已从文件加载: {:?}", file_path);
     Ok(content)
 }
+
+/// 导出用的直播场次数据（只解析导出需要的字段，其余字段忽略）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionExportRow {
+    live_id: i64,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    account_name: String,
+    #[serde(default)]
+    start_time: String,
+    /// 直播时长（秒）
+    duration_secs: Option<u64>,
+    order_count: Option<i64>,
+    /// 成交金额（GMV，元）
+    order_amount: Option<f64>,
+    #[serde(default)]
+    products: Vec<serde_json::Value>,
+}
+
+/// 场次导出可选的列（列名，表头）
+const SESSION_EXPORT_COLUMNS: &[(&str, &str)] = &[
+    ("live_id", "直播间 ID"),
+    ("title", "标题"),
+    ("account_name", "账号"),
+    ("start_time", "开播时间"),
+    ("duration", "时长"),
+    ("order_count", "订单数"),
+    ("gmv", "GMV（元）"),
+    ("product_count", "商品数"),
+];
+
+/// 生成场次数据 xlsx 文件内容（首行表头，数值列带数字格式，缺失的数据留空）
+fn build_sessions_xlsx(sessions: &[SessionExportRow], columns: &[&str]) -> Result<Vec<u8>, String> {
+    use rust_xlsxwriter::{Format, Workbook};
+
+    let header_format = Format::new().set_bold();
+    let duration_format = Format::new().set_num_format("[h]:mm:ss");
+    let count_format = Format::new().set_num_format("#,##0");
+    let amount_format = Format::new().set_num_format("#,##0.00");
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let write_err = |e: rust_xlsxwriter::XlsxError| format!("写入表格失败: {}", e);
+
+    for (col, key) in columns.iter().enumerate() {
+        let title = SESSION_EXPORT_COLUMNS
+            .iter()
+            .find(|(name, _)| name == key)
+            .map_or(*key, |(_, title)| *title);
+        worksheet
+            .write_string_with_format(0, col as u16, title, &header_format)
+            .map_err(write_err)?;
+    }
+
+    for (i, session) in sessions.iter().enumerate() {
+        let row = (i + 1) as u32;
+        for (col, key) in columns.iter().enumerate() {
+            let col = col as u16;
+            let result = match *key {
+                // 直播间 ID 按文本写入，避免长数字被显示成科学计数法
+                "live_id" => worksheet.write_string(row, col, session.live_id.to_string()),
+                "title" => worksheet.write_string(row, col, &session.title),
+                "account_name" => worksheet.write_string(row, col, &session.account_name),
+                "start_time" => worksheet.write_string(row, col, &session.start_time),
+                "duration" => match session.duration_secs {
+                    Some(secs) => worksheet.write_number_with_format(
+                        row,
+                        col,
+                        secs as f64 / 86400.0,
+                        &duration_format,
+                    ),
+                    None => continue,
+                },
+                "order_count" => match session.order_count {
+                    Some(count) => {
+                        worksheet.write_number_with_format(row, col, count as f64, &count_format)
+                    }
+                    None => continue,
+                },
+                "gmv" => match session.order_amount {
+                    Some(amount) => {
+                        worksheet.write_number_with_format(row, col, amount, &amount_format)
+                    }
+                    None => continue,
+                },
+                "product_count" => worksheet.write_number_with_format(
+                    row,
+                    col,
+                    session.products.len() as f64,
+                    &count_format,
+                ),
+                _ => continue,
+            };
+            result.map_err(write_err)?;
+        }
+    }
+    worksheet.autofit();
+
+    workbook
+        .save_to_buffer()
+        .map_err(|e| format!("生成文件失败: {}", e))
+}
+
+/// 导出直播场次数据为 Excel（场次、时长、订单数、GMV 等）
+/// columns 为空时导出全部列，可选值见 SESSION_EXPORT_COLUMNS
+#[tauri::command]
+pub async fn export_sessions_to_xlsx(
+    sessions_json: String,
+    output_path: String,
+    columns: Option<Vec<String>>,
+) -> Result<String, String> {
+    validate_output_path(&output_path, "xlsx")?;
+    let sessions: Vec<SessionExportRow> = serde_json::from_str(&sessions_json)
+        .map_err(|e| format!("直播场次数据格式不正确: {}", e))?;
+
+    let available: Vec<&str> = SESSION_EXPORT_COLUMNS
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    let columns: Vec<&str> = match &columns {
+        Some(columns) if !columns.is_empty() => columns.iter().map(String::as_str).collect(),
+        _ => available.clone(),
+    };
+    if let Some(unknown) = columns.iter().find(|key| !available.contains(key)) {
+        return Err(format!(
+            "未知的导出列: {}，可选: {}",
+            unknown,
+            available.join(", ")
+        ));
+    }
+
+    info!(
+        "[导出场次] 场次数量: {}, 输出路径: {}",
+        sessions.len(),
+        output_path
+    );
+
+    let buffer = build_sessions_xlsx(&sessions, &columns)?;
+    tokio::fs::write(&output_path, buffer).await.map_err(|e| {
+        format!(
+            "写入文件失败（请确认目录存在且可写，文件未被 Excel 打开）: {}",
+            e
+        )
+    })?;
+
+    info!(
+        "[导出场次] 已导出 {} 个场次到: {}",
+        sessions.len(),
+        output_path
+    );
+    Ok(output_path)
+}
+
+/// 读取账号备注文件（数据目录下 data/account_notes.json）
+fn read_account_notes() -> Result<HashMap<String, String>, String> {
+    use std::fs;
+
+    let file_path = get_data_dir()?.join("data").join("account_notes.json");
+
+    // 如果文件不存在，返回空表
+    if !file_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析账号备注失败: {}", e))
+}
+
+/// 设置账号备注（按京东 pin 区分账号，note 为空时删除备注）
+#[tauri::command]
+pub async fn set_account_note(pin: String, note: String) -> Result<(), String> {
+    use std::fs;
+
+    if pin.trim().is_empty() {
+        return Err("账号 pin 不能为空".to_string());
+    }
+
+    // 创建 data 文件夹
+    let data_dir = get_data_dir()?.join("data");
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir).map_err(|e| format!("创建 data 目录失败: {}", e))?;
+    }
+
+    let mut notes = read_account_notes()?;
+    if note.trim().is_empty() {
+        notes.remove(&pin);
+    } else {
+        notes.insert(pin.clone(), note);
+    }
+
+    let json_content =
+        serde_json::to_string_pretty(&notes).map_err(|e| format!("序列化账号备注失败: {}", e))?;
+    write_file_atomic(&data_dir.join("account_notes.json"), &json_content)?;
+
+    info!("账号备注已更新: {}", logging::mask_str(&pin));
+    Ok(())
+}
+
+/// 获取全部账号备注（pin -> 备注）
+#[tauri::command]
+pub async fn get_account_notes() -> Result<HashMap<String, String>, String> {
+    read_account_notes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_file_name_rejects_traversal() {
+        for name in [
+            "..\\evil",
+            "../evil",
+            "..",
+            "cookies/jd.json",
+            "cookies\\jd.json",
+            "C:evil.json",
+            "jd\0.json",
+            "",
+            "  ",
+        ] {
+            assert!(validate_file_name(name).is_err(), "{:?}", name);
+        }
+        assert_eq!(validate_file_name("jd_user.json").unwrap(), "jd_user.json");
+    }
+
+    #[test]
+    fn test_collect_response_headers_keeps_duplicates() {
+        use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, SET_COOKIE};
+
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("a=1; Path=/"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("b=2; Path=/"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let collected = collect_response_headers(&headers);
+        assert_eq!(collected["set-cookie"], vec!["a=1; Path=/", "b=2; Path=/"]);
+        assert_eq!(collected["content-type"], vec!["application/json"]);
+    }
+
+    #[test]
+    fn test_to_netscape_cookies() {
+        let cookie = |name: &str, domain: &str, expires, is_secure, is_http_only| Cookie {
+            name: name.to_string(),
+            value: "v".to_string(),
+            domain: domain.to_string(),
+            path: "/".to_string(),
+            expires,
+            is_secure,
+            is_http_only,
+            same_site: None,
+        };
+        let cookies = [
+            cookie("pt_pin", ".jd.com", Some(1700000000), false, false),
+            cookie("thor", ".jd.com", Some(1700000000), true, true),
+            cookie("session", "live.jd.com", None, false, false),
+        ];
+
+        assert_eq!(
+            to_netscape_cookies(&cookies),
+            "# Netscape HTTP Cookie File\n\n\
+             .jd.com\tTRUE\t/\tFALSE\t1700000000\tpt_pin\tv\n\
+             #HttpOnly_.jd.com\tTRUE\t/\tTRUE\t1700000000\tthor\tv\n\
+             live.jd.com\tFALSE\t/\tFALSE\t0\tsession\tv\n"
+        );
+    }
+
+    #[test]
+    fn test_validate_output_path() {
+        let dir = std::env::temp_dir();
+        let report = dir.join("report.xlsx");
+        assert_eq!(
+            validate_output_path(&report.to_string_lossy(), "xlsx").unwrap(),
+            report
+        );
+
+        let traversal = dir.join("..").join("report.xlsx");
+        assert!(validate_output_path(&traversal.to_string_lossy(), "xlsx").is_err());
+        assert!(validate_output_path("report.xlsx", "xlsx").is_err());
+        let wrong_ext = dir.join("report.dll");
+        assert!(validate_output_path(&wrong_ext.to_string_lossy(), "xlsx").is_err());
+        let missing_dir = dir.join("no-such-dir-for-test").join("report.xlsx");
+        assert!(validate_output_path(&missing_dir.to_string_lossy(), "xlsx").is_err());
+    }
+
+    #[test]
+    fn test_sniff_image_mime() {
+        assert_eq!(
+            sniff_image_mime(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniff_image_mime(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("image/jpeg")
+        );
+        assert_eq!(sniff_image_mime(b"GIF89a\x01\0"), Some("image/gif"));
+        assert_eq!(
+            sniff_image_mime(b"RIFF\x24\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(sniff_image_mime(b"RIFF\x24\0\0\0WAVEfmt "), None);
+        assert_eq!(sniff_image_mime(b"PK\x03\x04"), None);
+        assert_eq!(sniff_image_mime(b""), None);
+    }
+}
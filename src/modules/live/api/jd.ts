@@ -6,25 +6,117 @@ import { invoke } from '@tauri-apps/api/core'
 import type {
   Cookie,
   JdLoginResult,
-  RecentLiveRoom,
+  AuthorPermissions,
+  CookieValidity,
+  RecentLiveRoomPage,
+  LiveDetail,
+  Checklist,
+  ExplainResult,
+  ExplainingSku,
+  StreamInfo,
+  LiveCommentPage,
   CreateLiveRequest,
+  UpdateLiveRequest,
+  UpdateLiveResult,
+  StartLiveResult,
   LiveGeneralData,
+  SkuBrief,
   SkuInfo,
+  SkuInfoBatchResult,
   AddSkuResult,
+  RemoveSkuResult,
+  ReorderSkuResult,
+  CoverImage,
+  JdApiError,
+  JdError,
 } from '../types'
 
 /**
- * 验证京东登录状态
+ * 京东接口请求错误（toString 返回原始错误信息，兼容直接拼接错误的写法）
  */
-export async function verifyJdLogin(cookies: Cookie[]): Promise<JdLoginResult> {
-  return invoke<JdLoginResult>('verify_jd_login', { cookies })
+export class JdRequestError extends Error {
+  kind: JdError
+  code?: number
+  subcode?: number
+
+  constructor(error: JdApiError) {
+    super(error.message)
+    this.name = 'JdRequestError'
+    this.kind = error.kind
+    this.code = error.code
+    this.subcode = error.subcode
+  }
+
+  toString(): string {
+    return this.message
+  }
+}
+
+/**
+ * 调用返回 JdApiError 的命令，失败时转换为 JdRequestError
+ */
+async function invokeJd<T>(cmd: string, args: Record<string, unknown>): Promise<T> {
+  try {
+    return await invoke<T>(cmd, args)
+  } catch (error) {
+    if (error && typeof error === 'object' && 'kind' in error && 'message' in error) {
+      throw new JdRequestError(error as JdApiError)
+    }
+    throw error
+  }
+}
+
+/**
+ * 验证京东登录状态（10 秒内重复验证返回缓存结果，force 为 true 时强制重新验证）
+ */
+export async function verifyJdLogin(cookies: Cookie[], force?: boolean): Promise<JdLoginResult> {
+  return invoke<JdLoginResult>('verify_jd_login', { cookies, force })
+}
+
+/**
+ * 查询账号身份和权限（能否创建直播、能否带货）
+ * 接口未返回权限信息时不报错，返回 degraded 为 true 的结果
+ */
+export async function getAuthorPermissions(cookies: Cookie[]): Promise<AuthorPermissions> {
+  return invokeJd<AuthorPermissions>('get_author_permissions', { cookies })
+}
+
+/**
+ * 批量操作前预检查 Cookie 是否有效
+ */
+export async function checkCookiesValid(cookies: Cookie[]): Promise<CookieValidity> {
+  return invoke<CookieValidity>('check_cookies_valid', { cookies })
 }
 
 /**
- * 获取最近使用的直播间列表
+ * 获取最近使用的直播间列表（分页，可按状态过滤）
  */
-export async function getRecentLiveRooms(cookies: Cookie[]): Promise<RecentLiveRoom[]> {
-  return invoke<RecentLiveRoom[]>('get_recent_live_rooms', { cookies })
+export async function getRecentLiveRooms(
+  cookies: Cookie[],
+  page?: number,
+  pageSize?: number,
+  statusFilter?: number
+): Promise<RecentLiveRoomPage> {
+  return invokeJd<RecentLiveRoomPage>('get_recent_live_rooms', {
+    cookies,
+    page,
+    pageSize,
+    statusFilter,
+  })
+}
+
+/**
+ * 查询直播间详情
+ */
+export async function getLiveDetail(cookies: Cookie[], liveId: string): Promise<LiveDetail> {
+  return invokeJd<LiveDetail>('get_live_detail', { cookies, liveId })
+}
+
+/**
+ * 开播前检查清单
+ */
+export async function preLiveChecklist(cookies: Cookie[], liveId: string): Promise<Checklist> {
+  return invoke<Checklist>('pre_live_checklist', { cookies, liveId })
 }
 
 /**
@@ -34,14 +126,32 @@ export async function createLiveRoom(
   cookies: Cookie[],
   request: CreateLiveRequest
 ): Promise<number> {
-  return invoke<number>('create_live_room', { cookies, request })
+  return invokeJd<number>('create_live_room', { cookies, request })
+}
+
+/**
+ * 修改直播间标题、封面、描述、欢迎语
+ */
+export async function updateLiveRoom(
+  cookies: Cookie[],
+  liveId: string,
+  patch: UpdateLiveRequest
+): Promise<UpdateLiveResult> {
+  return invokeJd<UpdateLiveResult>('update_live_room', { cookies, liveId, patch })
 }
 
 /**
- * 上传商品到直播间
+ * 开播（预告或未开播的直播间切换为直播中）
+ */
+export async function startLive(cookies: Cookie[], liveId: string): Promise<StartLiveResult> {
+  return invokeJd<StartLiveResult>('start_live', { cookies, liveId })
+}
+
+/**
+ * 上传单个商品到直播间（与 getSkuInfoByFile 走同一 xlsx 上传接口）
  */
 export async function uploadSku(cookies: Cookie[], liveId: string, skuId: string): Promise<void> {
-  return invoke<void>('upload_sku', { cookies, liveId, skuId })
+  return invokeJd<void>('upload_sku', { cookies, liveId, skuId })
 }
 
 /**
@@ -52,7 +162,41 @@ export async function addSkuToBag(
   liveId: string,
   skuIds: string[]
 ): Promise<void> {
-  return invoke<void>('add_sku_to_bag', { cookies, liveId, skuIds })
+  return invokeJd<void>('add_sku_to_bag', { cookies, liveId, skuIds })
+}
+
+/**
+ * 从购物袋删除商品，返回删除、跳过（不在购物袋中）和失败的商品
+ */
+export async function removeSkuFromBag(
+  cookies: Cookie[],
+  liveId: string,
+  skuIds: string[]
+): Promise<RemoveSkuResult> {
+  return invokeJd<RemoveSkuResult>('remove_sku_from_bag', { cookies, liveId, skuIds })
+}
+
+/**
+ * 置顶或取消置顶购物袋商品
+ */
+export async function setSkuTop(
+  cookies: Cookie[],
+  liveId: string,
+  skuId: string,
+  top: boolean
+): Promise<void> {
+  return invokeJd<void>('set_sku_top', { cookies, liveId, skuId, top })
+}
+
+/**
+ * 调整购物袋商品顺序（遗漏的商品保持原顺序排在最后）
+ */
+export async function reorderSkus(
+  cookies: Cookie[],
+  liveId: string,
+  orderedSkuIds: string[]
+): Promise<ReorderSkuResult> {
+  return invokeJd<ReorderSkuResult>('reorder_skus', { cookies, liveId, orderedSkuIds })
 }
 
 /**
@@ -62,14 +206,58 @@ export async function getLiveGeneralData(
   cookies: Cookie[],
   liveId: string
 ): Promise<LiveGeneralData> {
-  return invoke<LiveGeneralData>('get_live_general_data', { cookies, liveId })
+  return invokeJd<LiveGeneralData>('get_live_general_data', { cookies, liveId })
+}
+
+/**
+ * 缓存当前账号 Cookie，之后可调用 *Cached 版本的接口而不必每次传 Cookie（传空数组清除）
+ */
+export async function setActiveCookies(cookies: Cookie[]): Promise<void> {
+  return invoke('set_active_cookies', { cookies })
+}
+
+/**
+ * 获取直播实时数据（使用 setActiveCookies 缓存的 Cookie）
+ */
+export async function getLiveGeneralDataCached(liveId: string): Promise<LiveGeneralData> {
+  return invokeJd<LiveGeneralData>('get_live_general_data_cached', { liveId })
 }
 
 /**
  * 获取 H5 页面 URL
  */
 export async function getH5Url(cookies: Cookie[], liveId: string): Promise<string> {
-  return invoke<string>('get_h5_url', { cookies, liveId })
+  return invokeJd<string>('get_h5_url', { cookies, liveId })
+}
+
+/**
+ * 获取推流信息（推流地址、推流码、H5 观看链接）
+ */
+export async function getStreamInfo(cookies: Cookie[], liveId: string): Promise<StreamInfo> {
+  return invokeJd<StreamInfo>('get_stream_info', { cookies, liveId })
+}
+
+/**
+ * 拉取直播评论，lastId 传上次返回的游标实现增量拉取
+ */
+export async function getLiveComments(
+  cookies: Cookie[],
+  liveId: string,
+  lastId?: string | null,
+  blockWords?: string[]
+): Promise<LiveCommentPage> {
+  return invokeJd<LiveCommentPage>('get_live_comments', { cookies, liveId, lastId, blockWords })
+}
+
+/**
+ * 拉取直播评论（使用 setActiveCookies 缓存的 Cookie）
+ */
+export async function getLiveCommentsCached(
+  liveId: string,
+  lastId?: string | null,
+  blockWords?: string[]
+): Promise<LiveCommentPage> {
+  return invokeJd<LiveCommentPage>('get_live_comments_cached', { liveId, lastId, blockWords })
 }
 
 /**
@@ -80,25 +268,56 @@ export async function startExplain(
   liveId: string,
   skuId: string
 ): Promise<void> {
-  return invoke<void>('start_explain', { cookies, liveId, skuId })
+  return invokeJd<void>('start_explain', { cookies, liveId, skuId })
 }
 
 /**
  * 结束讲解商品
  */
-export async function endExplain(cookies: Cookie[], liveId: string, skuId: string): Promise<void> {
-  return invoke<void>('end_explain', { cookies, liveId, skuId })
+export async function endExplain(
+  cookies: Cookie[],
+  liveId: string,
+  skuId: string
+): Promise<ExplainResult> {
+  return invokeJd<ExplainResult>('end_explain', { cookies, liveId, skuId })
+}
+
+/**
+ * 查询直播间讲解中的商品（无讲解中商品时返回空数组）
+ */
+export async function getCurrentExplaining(
+  cookies: Cookie[],
+  liveId: string
+): Promise<ExplainingSku[]> {
+  return invokeJd<ExplainingSku[]>('get_current_explaining', { cookies, liveId })
 }
 
 /**
- * 通过上传文件获取商品详情
+ * 通过上传文件获取商品详情（部分批次失败时在 failed_batches 中列出）
  */
 export async function getSkuInfoByFile(
   cookies: Cookie[],
   liveId: number,
   skuIds: string[]
+): Promise<SkuInfoBatchResult<SkuInfo>> {
+  return invokeJd<SkuInfoBatchResult<SkuInfo>>('get_sku_info_by_file', {
+    cookies,
+    liveId,
+    skuIds,
+  })
+}
+
+/**
+ * 查询商品详情（优先使用缓存）
+ * 注意：未命中缓存时会向该直播间调用 uploadSku 写接口，京东端副作用未经核实，并非只读查询
+ */
+export async function querySkuDetail(
+  cookies: Cookie[],
+  liveId: number,
+  skuIds: string[],
+  refresh?: boolean
 ): Promise<SkuInfo[]> {
-  return invoke<SkuInfo[]>('get_sku_info_by_file', { cookies, liveId, skuIds })
+  return invokeJd<SkuInfo[]>('query_sku_detail', { cookies, liveId, skuIds, refresh })
 }
 
 /**
@@ -109,5 +328,79 @@ export async function addSkuToBagBatch(
   liveId: number,
   skuList: SkuInfo[]
 ): Promise<AddSkuResult> {
-  return invoke<AddSkuResult>('add_sku_to_bag_batch', { cookies, liveId, skuList })
+  return invokeJd<AddSkuResult>('add_sku_to_bag_batch', { cookies, liveId, skuList })
+}
+
+/**
+ * 获取商品精简信息（完整详情缓存在后端，部分批次失败时在 failed_batches 中列出）
+ */
+export async function getSkuBriefByFile(
+  cookies: Cookie[],
+  liveId: number,
+  skuIds: string[]
+): Promise<SkuInfoBatchResult<SkuBrief>> {
+  return invokeJd<SkuInfoBatchResult<SkuBrief>>('get_sku_info_by_file', {
+    cookies,
+    liveId,
+    skuIds,
+    brief: true,
+  })
+}
+
+/**
+ * 按商品 ID 批量添加到购物袋（使用后端缓存的商品详情）
+ */
+export async function addSkuIdsToBag(
+  cookies: Cookie[],
+  liveId: number,
+  skuIds: string[]
+): Promise<AddSkuResult> {
+  return invokeJd<AddSkuResult>('add_sku_to_bag_batch', { cookies, liveId, skuIds })
+}
+
+/**
+ * 获取可用的封面图片
+ */
+export async function getCoverImages(cookies: Cookie[]): Promise<CoverImage[]> {
+  return invokeJd<CoverImage[]>('get_cover_images', { cookies })
+}
+
+/**
+ * 启动商品价格/库存/状态变动监控（间隔最短 60 秒），返回任务 id
+ * 变动通过 sku-changed / sku-status-changed 事件推送
+ * 注意：每次轮询都会向该直播间调用 uploadSku 写接口（没有只读查询接口），京东端副作用未经核实
+ */
+export async function watchSkuChanges(
+  cookies: Cookie[],
+  liveId: number,
+  skuIds: string[],
+  intervalMs: number
+): Promise<number> {
+  return invoke<number>('watch_sku_changes', { cookies, liveId, skuIds, intervalMs })
+}
+
+/**
+ * 停止商品变动监控
+ */
+export async function stopWatchSkuChanges(taskId: number): Promise<void> {
+  return invoke<void>('stop_watch_sku_changes', { taskId })
+}
+
+/**
+ * 手动设置 api.m.jd.com 接口的 h5st 签名（h5st 为空时清除该接口的签名）
+ * 签名通常与时间戳绑定，t 需与计算签名时使用的时间戳一致
+ */
+export async function setH5stSignature(
+  functionId: string,
+  h5st: string | null,
+  t?: number
+): Promise<void> {
+  return invoke<void>('set_h5st_signature', { functionId, h5st, t })
+}
+
+/**
+ * 清除所有手动签名
+ */
+export async function clearH5stSignatures(): Promise<void> {
+  return invoke<void>('clear_h5st_signatures')
 }
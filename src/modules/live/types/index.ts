@@ -139,6 +139,42 @@ export interface JdLoginResult {
   is_logged_in: boolean
   nickname?: string
   avatar?: string
+  pin?: string // 账号唯一标识
+}
+
+// 账号身份和权限（null 表示京东未返回该权限，按未知处理）
+export interface AuthorPermissions {
+  pin: string | null
+  identity: 'merchant' | 'talent' | 'unknown'
+  can_create_live: boolean | null
+  can_sell: boolean | null
+  degraded: boolean // 接口未返回任何权限字段
+}
+
+// Cookie 预检查结果
+export interface CookieValidity {
+  status: 'valid' | 'invalid' | 'check_failed'
+  pin?: string
+  expires_at?: number // 会话 Cookie 最早过期时间（Unix 秒）
+  expires_in_secs?: number
+  error?: string
+}
+
+// 京东接口业务错误分类
+export type JdError =
+  | 'not_logged_in'
+  | 'rate_limited'
+  | 'forbidden'
+  | 'invalid_param'
+  | 'request'
+  | { unknown: number | null }
+
+// 京东接口命令失败时返回的结构化错误
+export interface JdApiError {
+  kind: JdError
+  code?: number
+  subcode?: number
+  message: string
 }
 
 // 最近使用的直播间
@@ -151,6 +187,88 @@ export interface RecentLiveRoom {
   end_time?: string
 }
 
+// 分页后的直播间列表
+export interface RecentLiveRoomPage {
+  total: number
+  page: number
+  pageSize: number
+  items: RecentLiveRoom[]
+}
+
+// 直播间详情
+export interface LiveDetail {
+  liveId?: number
+  title?: string
+  status?: number
+  indexImage?: string // 封面图（4:3）
+  resizeIndexImage?: string // 封面图（2:1）
+  squareIndexImage?: string // 封面图（1:1）
+  portraitIndexImage?: string // 封面图（3:4）
+  screen?: number
+  canExplain?: number
+  publishTime?: string
+  startTime?: string
+  endTime?: string
+  skuNum?: number
+}
+
+// 开播前检查项
+export interface ChecklistItem {
+  passed: boolean
+  message: string
+}
+
+// 开播前检查清单
+export interface Checklist {
+  login: ChecklistItem
+  live_room: ChecklistItem
+  shopping_bag: ChecklistItem
+  cover: ChecklistItem
+  all_passed: boolean
+}
+
+// 直播评论
+export interface LiveComment {
+  commentId?: string
+  nickName?: string
+  content?: string
+  createTime?: number // Unix 毫秒
+}
+
+// 评论拉取结果
+export interface LiveCommentPage {
+  comments: LiveComment[]
+  last_id: string | null // 下次增量拉取的游标
+  filtered_count: number
+}
+
+// 推流信息
+export interface StreamInfo {
+  push_url: string // OBS「服务器」
+  stream_key: string // OBS「串流密钥」
+  h5_url: string
+}
+
+// 讲解中的商品
+export interface ExplainingSku {
+  sku_id: string
+  title?: string
+  started_at?: number // 开始讲解时间（Unix 毫秒），仅本应用发起的讲解有记录
+}
+
+// 结束讲解结果
+export interface ExplainResult {
+  duration_secs: number
+}
+
+// 封面图片
+export interface CoverImage {
+  fourToThree?: string
+  twoToOne?: string
+  oneToOne?: string
+  threeToFour?: string
+}
+
 // 创建直播间请求
 export interface CreateLiveRequest {
   title: string // 直播间标题
@@ -171,6 +289,30 @@ export interface CreateLiveRequest {
   pcVersion: number // PC 版本，固定 1
 }
 
+// 修改直播间请求（只提交填写的字段）
+export interface UpdateLiveRequest {
+  title?: string
+  indexImage?: string // 封面图（4:3）
+  resizeIndexImage?: string // 封面图（2:1）
+  squareIndexImage?: string // 封面图（1:1）
+  portraitIndexImage?: string // 封面图（3:4）
+  desc?: string
+  welcome?: string
+}
+
+// 修改直播间结果（直播进行中部分字段可能被拒绝）
+export interface UpdateLiveResult {
+  updated_fields: string[]
+  rejected_fields: { field: string; reason: string }[]
+}
+
+// 开播结果
+export interface StartLiveResult {
+  live_id: string
+  status: number | null // 开播后查询到的直播间状态（查询失败时为 null）
+  start_time: string | null
+}
+
 // 创建直播间响应
 export interface CreateLiveResponse {
   success: boolean
@@ -190,7 +332,8 @@ export interface LiveGeneralData {
   comment_count?: number
   share_count?: number
   order_count?: number
-  order_amount?: number
+  order_amount?: number // 成交金额（元，后端已由分换算）
+  amount_unit?: 'yuan'
 }
 
 // Cookie 类型
@@ -350,11 +493,74 @@ export interface SkuInfo {
   fsArrivalPrice?: string
 }
 
+// 商品精简信息
+export interface SkuBrief {
+  sku: string
+  title?: string
+  img?: string
+  price?: string
+}
+
+// 获取失败的商品批次
+export interface FailedSkuBatch {
+  index: number // 批次序号（从 0 开始）
+  sku_ids: string[]
+  error: JdApiError
+}
+
+// 批量获取商品详情结果（部分批次失败时 failed_batches 非空）
+export interface SkuInfoBatchResult<T> {
+  items: T[]
+  failed_batches: FailedSkuBatch[]
+}
+
 // 添加商品结果
 export interface AddSkuResult {
   success: boolean
   success_count: number
   error_msg?: string
+  failed_items: { sku_id: string; reason: string }[]
+}
+
+// 删除商品结果
+export interface RemoveSkuResult {
+  removed: string[] // 实际删除的商品
+  skipped: string[] // 不在购物袋中而跳过的商品
+  failed_items: { sku_id: string; reason: string }[]
+}
+
+// 商品排序结果
+export interface ReorderSkuResult {
+  sku_ids: string[] // 实际提交的完整顺序
+  appended: string[] // 排序列表遗漏、追加到末尾的商品
+}
+
+// 商品变动明细（sku-changed / sku-status-changed 事件）
+export interface SkuChange {
+  skuId: string
+  field: 'price' | 'stock_state' | 'sku_status'
+  oldValue?: string
+  newValue?: string
+  offShelf: boolean // 是否已下架
+}
+
+export interface SkuChangedEvent {
+  taskId: number
+  liveId: number
+  changes: SkuChange[]
+}
+
+// script-switch 事件内容（开始讲解时推送，script 为空表示该商品没有脚本）
+export interface ScriptSwitchEvent {
+  label: string // 脚本窗口 label
+  skuId: string
+  script: string
+}
+
+// cookies-expiring-soon / cookies-expired 事件内容
+export interface CookieExpiryEvent {
+  expiresAt: number // 最早过期的登录 Cookie 的过期时间（Unix 秒）
+  remainingSecs: number // 剩余秒数（已过期时为 0）
 }
 
 // ============ 直播商品数据（持久化）============
@@ -379,4 +585,18 @@ export interface LiveSession {
   createdAt: string // 创建时间
   products: LiveProduct[] // 直播商品列表
   scripts?: AIScript[] // AI 话术列表
+  durationSecs?: number // 直播时长（秒）
+  orderCount?: number // 订单数
+  orderAmount?: number // 成交金额（GMV，元）
 }
+
+// 场次导出 Excel 可选的列
+export type SessionExportColumn =
+  | 'live_id'
+  | 'title'
+  | 'account_name'
+  | 'start_time'
+  | 'duration'
+  | 'order_count'
+  | 'gmv'
+  | 'product_count'